@@ -1,10 +1,22 @@
 use std::env;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 fn main() {
+    let mut cxx_cc = PathBuf::from("src/cxx.cc");
+    let mut cxx_h = env::var_os("CARGO_MANIFEST_DIR")
+        .map(|manifest_dir| Path::new(&manifest_dir).join("include").join("cxx.h"));
+
+    let prefix = cxxbridge_flags::SYMBOL_PREFIX;
+    if prefix != "cxxbridge1" {
+        let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+        cxx_cc = rename_symbols(&out_dir, "src/cxx.cc", prefix);
+        cxx_h = Some(rename_symbols(&out_dir, "include/cxx.h", prefix));
+    }
+
     cc::Build::new()
-        .file("src/cxx.cc")
+        .file(cxx_cc)
         .cpp(true)
         .cpp_link_stdlib(None) // linked via link-cplusplus crate
         .flag_if_supported(cxxbridge_flags::STD)
@@ -14,9 +26,9 @@ fn main() {
     println!("cargo:rerun-if-changed=src/cxx.cc");
     println!("cargo:rerun-if-changed=include/cxx.h");
     println!("cargo:rustc-cfg=built_with_cargo");
+    println!("cargo:rustc-env=CXXBRIDGE_SYMBOL_PREFIX={}", prefix);
 
-    if let Some(manifest_dir) = env::var_os("CARGO_MANIFEST_DIR") {
-        let cxx_h = Path::new(&manifest_dir).join("include").join("cxx.h");
+    if let Some(cxx_h) = cxx_h {
         println!("cargo:HEADER={}", cxx_h.to_string_lossy());
     }
 
@@ -31,6 +43,21 @@ fn main() {
     }
 }
 
+// Writes a copy of the given file into OUT_DIR with its symbols, inline
+// namespace and preprocessor guards renamed from cxxbridge1 and CXXBRIDGE1 to
+// the configured prefix. The relative layout is kept so that cxx.cc still finds
+// ../include/cxx.h.
+fn rename_symbols(out_dir: &Path, file: &str, prefix: &str) -> PathBuf {
+    let original = fs::read_to_string(file).unwrap();
+    let renamed = original
+        .replace("cxxbridge1", prefix)
+        .replace("CXXBRIDGE1", &prefix.to_ascii_uppercase());
+    let path = out_dir.join(file);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, renamed).unwrap();
+    path
+}
+
 struct RustVersion {
     version: String,
     minor: u32,
@@ -18,3 +18,35 @@ pub const STD: &str = {
 
     flag
 };
+
+// Namespace for the symbols shared between the cxx runtime, the `rust/cxx.h`
+// header and generated code: the leading segment of every `extern "C"` symbol
+// name and the name of the inline namespace within `rust`. In uppercase it also
+// begins every preprocessor guard. Two copies of cxx built with prefixes that
+// differ other than by case may be linked into the same binary.
+pub const SYMBOL_PREFIX: &str = {
+    let prefix = match option_env!("CXXBRIDGE_SYMBOL_PREFIX") {
+        Some(prefix) => prefix,
+        None => "cxxbridge1",
+    };
+
+    let bytes = prefix.as_bytes();
+    if bytes.is_empty() || bytes[0].is_ascii_digit() {
+        panic!("CXXBRIDGE_SYMBOL_PREFIX must be a C++ identifier");
+    }
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_alphanumeric() && bytes[i] != b'_' {
+            panic!("CXXBRIDGE_SYMBOL_PREFIX must be a C++ identifier");
+        }
+        i += 1;
+    }
+
+    prefix
+};
+
+// Symbols of user-defined functions in the global namespace are mangled the
+// way cxx 1.0.94 and older did, for linking against C++ or Rust code that was
+// built by those versions. The legacy scheme can collide with cxx's internal
+// symbols; see syntax/mangle.rs.
+pub const LEGACY_MANGLING: bool = cfg!(feature = "legacy-mangling");
@@ -1,3 +1,4 @@
+use crate::gen::CxxStandard;
 use std::fmt::{self, Debug};
 use std::marker::PhantomData;
 use std::path::Path;
@@ -14,6 +15,10 @@ pub struct Cfg<'a> {
     pub exported_header_links: Vec<&'a str>,
     /// See [`CFG.doxygen`][CFG#cfgdoxygen].
     pub doxygen: bool,
+    /// See [`CFG.exceptions`][CFG#cfgexceptions].
+    pub exceptions: bool,
+    /// See [`CFG.cxx_standard`][CFG#cfgcxx_standard].
+    pub cxx_standard: Option<CxxStandard>,
     marker: PhantomData<*const ()>, // !Send + !Sync
 }
 
@@ -305,6 +310,91 @@ pub struct Cfg<'a> {
 /// ```
 ///
 /// Otherwise by default (without `CFG.doxygen`) they'll just be `//` comments.
+///
+/// <p style="margin:0"><br><br></p>
+///
+/// <div style="float:right;margin:22px 50px 0;font-size:1.15em;opacity:.73"><strong>bool</strong></div>
+///
+/// ## **`CFG.exceptions`**
+///
+/// Boolean, true by default. Whether the generated C++ code is allowed to use
+/// C++ exceptions.
+///
+/// Set this to false if the C++ side of the build uses `-fno-exceptions`.
+/// Functions in the bridge that return `Result` are then declared in C++ as
+/// returning `rust::Expected<T>`: C++ implementations of `extern "C++"`
+/// functions return either a value or a `rust::Error`, and the C++ signatures
+/// of `extern "Rust"` functions hand back the Rust error in the same way rather
+/// than throwing it.
+///
+/// ### Example
+///
+/// ```no_run
+/// // build.rs
+///
+/// use cxx_build::CFG;
+///
+/// fn main() {
+///     CFG.exceptions = false;
+///
+///     cxx_build::bridge("src/bridge.rs")
+///         .flag_if_supported("-fno-exceptions")
+///         .compile("demo");
+/// }
+/// ```
+///
+/// ```cpp
+/// // src/demo.cc
+///
+/// rust::Expected<std::size_t> parse_header(rust::Str data) noexcept {
+///   if (data.empty()) {
+///     return rust::Error("empty header");
+///   }
+///   return data.size();
+/// }
+/// ```
+///
+/// <p style="margin:0"><br><br></p>
+///
+/// <div style="float:right;margin:22px 50px 0;font-size:1.15em;opacity:.73"><strong>Option&lt;CxxStandard&gt;</strong></div>
+///
+/// ## **`CFG.cxx_standard`**
+///
+/// The version of the C++ standard to compile this crate's C++ code as. None
+/// by default, in which case no `-std` flag is passed and the generated code
+/// only assumes C++11.
+///
+/// Unlike the `c++14`/`c++17`/`c++20` features of the `cxx` crate, which are
+/// unified across the whole dependency graph, this is specific to the build
+/// script that sets it. When set, the corresponding `-std=c++NN` (or
+/// `/std:c++NN` for MSVC) flag is added to the returned `cc::Build`. There is
+/// no need to additionally pass your own `-std` flag.
+///
+/// The generated code is otherwise written against C++11 no matter the
+/// setting; in particular it never makes use of `std::optional` or
+/// `std::span`. At the moment the only output affected is shared constants,
+/// which are emitted as `inline constexpr` from C++17 on, and are then also
+/// exported from a C++20 module interface if one is requested.
+///
+/// The `rust/cxx.h` header and the C++ half of the `cxx` crate compile the same
+/// under every standard, so this setting does not need to agree with whichever
+/// `c++NN` feature of `cxx` is enabled in the dependency graph.
+///
+/// ### Example
+///
+/// ```no_run
+/// // build.rs
+///
+/// use cxx_build::{CxxStandard, CFG};
+///
+/// fn main() {
+///     CFG.cxx_standard = Some(CxxStandard::Cxx20);
+///
+///     cxx_build::bridge("src/bridge.rs")
+///         .file("src/demo.cc")
+///         .compile("demo");
+/// }
+/// ```
 #[cfg(doc)]
 pub static mut CFG: Cfg = Cfg {
     include_prefix: "",
@@ -312,6 +402,8 @@ pub static mut CFG: Cfg = Cfg {
     exported_header_prefixes: Vec::new(),
     exported_header_links: Vec::new(),
     doxygen: false,
+    exceptions: true,
+    cxx_standard: None,
     marker: PhantomData,
 };
 
@@ -323,6 +415,8 @@ impl<'a> Debug for Cfg<'a> {
             exported_header_prefixes,
             exported_header_links,
             doxygen,
+            exceptions,
+            cxx_standard,
             marker: _,
         } = self;
         formatter
@@ -332,6 +426,8 @@ impl<'a> Debug for Cfg<'a> {
             .field("exported_header_prefixes", exported_header_prefixes)
             .field("exported_header_links", exported_header_links)
             .field("doxygen", doxygen)
+            .field("exceptions", exceptions)
+            .field("cxx_standard", cxx_standard)
             .finish()
     }
 }
@@ -341,6 +437,7 @@ pub use self::r#impl::Cfg::CFG;
 
 #[cfg(not(doc))]
 mod r#impl {
+    use crate::gen::CxxStandard;
     use crate::intern::{intern, InternedString};
     use crate::syntax::map::UnorderedMap as Map;
     use crate::vec::{self, InternedVec as _};
@@ -357,6 +454,8 @@ mod r#impl {
         exported_header_prefixes: Vec<InternedString>,
         exported_header_links: Vec<InternedString>,
         doxygen: bool,
+        exceptions: bool,
+        cxx_standard: Option<CxxStandard>,
     }
 
     impl CurrentCfg {
@@ -368,12 +467,16 @@ mod r#impl {
             let exported_header_prefixes = Vec::new();
             let exported_header_links = Vec::new();
             let doxygen = false;
+            let exceptions = true;
+            let cxx_standard = None;
             CurrentCfg {
                 include_prefix,
                 exported_header_dirs,
                 exported_header_prefixes,
                 exported_header_links,
                 doxygen,
+                exceptions,
+                cxx_standard,
             }
         }
     }
@@ -407,12 +510,16 @@ mod r#impl {
             let exported_header_prefixes = current.exported_header_prefixes.vec();
             let exported_header_links = current.exported_header_links.vec();
             let doxygen = current.doxygen;
+            let exceptions = current.exceptions;
+            let cxx_standard = current.cxx_standard;
             super::Cfg {
                 include_prefix,
                 exported_header_dirs,
                 exported_header_prefixes,
                 exported_header_links,
                 doxygen,
+                exceptions,
+                cxx_standard,
                 marker: PhantomData,
             }
         }
@@ -479,6 +586,8 @@ mod r#impl {
                     exported_header_prefixes,
                     exported_header_links,
                     doxygen,
+                    exceptions,
+                    cxx_standard,
                     marker: _,
                 } = cfg;
                 let mut current = CURRENT.write().unwrap_or_else(PoisonError::into_inner);
@@ -487,6 +596,8 @@ mod r#impl {
                 current.exported_header_prefixes = vec::intern(exported_header_prefixes);
                 current.exported_header_links = vec::intern(exported_header_links);
                 current.doxygen = *doxygen;
+                current.exceptions = *exceptions;
+                current.cxx_standard = *cxx_standard;
             } else {
                 CONST_DEREFS.with(|derefs| derefs.borrow_mut().remove(&self.handle()));
             }
@@ -0,0 +1,192 @@
+// Records how the generated C++ sources are compiled, in the JSON Compilation
+// Database format understood by clangd and similar tooling:
+// https://clang.llvm.org/docs/JSONCompilationDatabase.html
+//
+// Every cxx_build build script writes a fragment of its own under
+// target/cxxbridge/compile_commands/ and then reassembles
+// target/cxxbridge/compile_commands.json from all the fragments present, so
+// that the result covers every crate of the workspace regardless of the order
+// in which Cargo runs the build scripts. Fragments are JSON Lines, one entry
+// per line, which makes them mergeable without a JSON parser.
+//
+// The arguments recorded are those of the cc::Build as it stands when
+// cxx_build::bridge returns it: the compiler, the -std flag from CFG, the
+// include paths of the CXX include directory, and whatever cc picks up from the
+// environment (CXXFLAGS, target defaults). Flags, defines and include paths the
+// build script adds to the returned cc::Build afterwards happen outside of
+// cxx_build and are not reflected.
+//
+// All of this is best effort. Failing to write the database is not worth
+// failing the build over.
+
+use crate::Project;
+use cc::Build;
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+pub(crate) fn write(prj: &Project, build: &Build, sources: &[PathBuf]) {
+    let compiler = match build.try_get_compiler() {
+        Ok(compiler) => compiler,
+        Err(_) => return,
+    };
+
+    let mut fragment = String::new();
+    for source in sources {
+        fragment.push_str("{\"directory\": ");
+        push_json_string(&mut fragment, prj.manifest_dir.as_os_str());
+        fragment.push_str(", \"file\": ");
+        push_json_string(&mut fragment, source.as_os_str());
+        fragment.push_str(", \"arguments\": [");
+        push_json_string(&mut fragment, compiler.path().as_os_str());
+        for arg in compiler.args() {
+            fragment.push_str(", ");
+            push_json_string(&mut fragment, arg);
+        }
+        fragment.push_str(", \"-c\", ");
+        push_json_string(&mut fragment, source.as_os_str());
+        fragment.push_str("]}\n");
+    }
+
+    let fragment_name = match (
+        env::var_os("CARGO_PKG_NAME"),
+        env::var_os("CARGO_PKG_VERSION"),
+    ) {
+        (Some(name), Some(version)) => {
+            format!(
+                "{}-{}.jsonl",
+                name.to_string_lossy(),
+                version.to_string_lossy()
+            )
+        }
+        _ => return,
+    };
+    let fragments_dir = prj.shared_dir.join("compile_commands");
+    if fs::create_dir_all(&fragments_dir).is_err()
+        || fs::write(fragments_dir.join(fragment_name), fragment).is_err()
+    {
+        return;
+    }
+
+    merge(
+        &fragments_dir,
+        &prj.shared_dir.join("compile_commands.json"),
+    );
+}
+
+fn merge(fragments_dir: &Path, database: &Path) {
+    let mut fragment_paths = Vec::new();
+    if let Ok(entries) = fs::read_dir(fragments_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension() == Some(OsStr::new("jsonl")) {
+                fragment_paths.push(path);
+            }
+        }
+    }
+    fragment_paths.sort();
+
+    let mut content = String::from("[\n");
+    let mut first = true;
+    for path in fragment_paths {
+        let fragment = match fs::read_to_string(&path) {
+            Ok(fragment) => fragment,
+            Err(_) => continue,
+        };
+        for line in fragment.lines().filter(|line| !line.is_empty()) {
+            if !first {
+                content.push_str(",\n");
+            }
+            content.push_str("  ");
+            content.push_str(line);
+            first = false;
+        }
+    }
+    content.push_str("\n]\n");
+
+    // Concurrently running build scripts may be merging at the same time.
+    // Each one writes privately and then renames into place, so that readers
+    // never observe a partially written database.
+    let mut tmp = database.as_os_str().to_owned();
+    tmp.push(format!(".{}.tmp", process::id()));
+    let tmp = PathBuf::from(tmp);
+    if fs::write(&tmp, content).is_ok() && fs::rename(&tmp, database).is_err() {
+        let _ = fs::remove_file(tmp);
+    }
+}
+
+fn push_json_string(json: &mut String, string: &OsStr) {
+    json.push('"');
+    for ch in string.to_string_lossy().chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            ch if ch < ' ' => json.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => json.push(ch),
+        }
+    }
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge, push_json_string};
+    use std::env;
+    use std::ffi::OsStr;
+    use std::fs;
+    use std::process;
+
+    fn json_string(string: &str) -> String {
+        let mut json = String::new();
+        push_json_string(&mut json, OsStr::new(string));
+        json
+    }
+
+    #[test]
+    fn test_push_json_string() {
+        assert_eq!(json_string(""), r#""""#);
+        assert_eq!(json_string("-std=c++17"), r#""-std=c++17""#);
+        assert_eq!(json_string(r#"-DNAME="value""#), r#""-DNAME=\"value\"""#);
+        assert_eq!(json_string(r"C:\src\lib.rs.cc"), r#""C:\\src\\lib.rs.cc""#);
+        assert_eq!(json_string("a\nb\rc\td"), r#""a\nb\rc\td""#);
+        assert_eq!(json_string("\u{1}\u{1f}"), r#""\u0001\u001f""#);
+        assert_eq!(json_string("caf\u{e9}"), "\"caf\u{e9}\"");
+    }
+
+    #[test]
+    fn test_merge() {
+        let dir = env::temp_dir().join(format!("cxxbridge-compile-commands-{}", process::id()));
+        let fragments_dir = dir.join("compile_commands");
+        let database = dir.join("compile_commands.json");
+        fs::create_dir_all(&fragments_dir).unwrap();
+
+        fs::write(
+            fragments_dir.join("b-0.1.0.jsonl"),
+            "{\"file\": \"b.cc\"}\n",
+        )
+        .unwrap();
+        fs::write(
+            fragments_dir.join("a-0.1.0.jsonl"),
+            "{\"file\": \"a1.cc\"}\n{\"file\": \"a2.cc\"}\n",
+        )
+        .unwrap();
+        fs::write(fragments_dir.join("empty-0.1.0.jsonl"), "").unwrap();
+        fs::write(fragments_dir.join("unrelated.txt"), "not json").unwrap();
+        merge(&fragments_dir, &database);
+
+        let merged = fs::read_to_string(&database);
+        let leftovers = fs::read_dir(&dir).unwrap().count();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let expected =
+            "[\n  {\"file\": \"a1.cc\"},\n  {\"file\": \"a2.cc\"},\n  {\"file\": \"b.cc\"}\n]\n";
+        assert_eq!(merged.unwrap(), expected);
+        // Only the fragments directory and the database, no temporary file.
+        assert_eq!(leftovers, 2);
+    }
+}
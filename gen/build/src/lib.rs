@@ -83,6 +83,7 @@
 
 mod cargo;
 mod cfg;
+mod compile_commands;
 mod deps;
 mod error;
 mod gen;
@@ -111,6 +112,7 @@ use std::path::{Path, PathBuf};
 use std::process;
 
 pub use crate::cfg::{Cfg, CFG};
+pub use crate::gen::CxxStandard;
 
 /// This returns a [`cc::Build`] on which you should continue to set up any
 /// additional source files or compiler flags, and lastly call its [`compile`]
@@ -203,6 +205,7 @@ impl Project {
 //              $CARGO_PKG_NAME/
 //                 .../
 //                    lib.rs.h
+//                    lib.rs.fwd.h
 //           sources/
 //              $CARGO_PKG_NAME/
 //                 .../
@@ -219,9 +222,14 @@ fn build(rust_source_files: &mut dyn Iterator<Item = impl AsRef<Path>>) -> Resul
     let mut build = Build::new();
     build.cpp(true);
     build.cpp_link_stdlib(None); // linked via link-cplusplus crate
+    if let Some(cxx_standard) = CFG.cxx_standard {
+        build.flag_if_supported(cxx_standard_flag(cxx_standard));
+    }
 
+    let mut generated_sources = Vec::new();
     for path in rust_source_files {
-        generate_bridge(prj, &mut build, path.as_ref())?;
+        let implementation_path = generate_bridge(prj, &mut build, path.as_ref())?;
+        generated_sources.push(implementation_path);
     }
 
     this_crate.print_to_cargo();
@@ -235,9 +243,25 @@ fn build(rust_source_files: &mut dyn Iterator<Item = impl AsRef<Path>>) -> Resul
         }
     }
 
+    compile_commands::write(prj, &build, &generated_sources);
+
     Ok(build)
 }
 
+fn cxx_standard_flag(cxx_standard: CxxStandard) -> &'static str {
+    let msvc = env::var_os("CARGO_CFG_TARGET_ENV").map_or(false, |env| env == "msvc");
+    match (cxx_standard, msvc) {
+        (CxxStandard::Cxx11, false) => "-std=c++11",
+        (CxxStandard::Cxx14, false) => "-std=c++14",
+        (CxxStandard::Cxx17, false) => "-std=c++17",
+        (CxxStandard::Cxx20, false) => "-std=c++20",
+        // MSVC has no C++11 mode; its oldest is C++14.
+        (CxxStandard::Cxx11, true) | (CxxStandard::Cxx14, true) => "/std:c++14",
+        (CxxStandard::Cxx17, true) => "/std:c++17",
+        (CxxStandard::Cxx20, true) => "/std:c++20",
+    }
+}
+
 fn validate_cfg(prj: &Project) -> Result<()> {
     for exported_dir in &CFG.exported_header_dirs {
         if !exported_dir.is_absolute() {
@@ -389,17 +413,19 @@ fn make_include_dir(prj: &Project) -> Result<PathBuf> {
         out::symlink_file(original, cxx_h)?;
         out::symlink_file(original, shared_cxx_h)?;
     } else {
-        out::write(shared_cxx_h, gen::include::HEADER.as_bytes())?;
+        out::write(shared_cxx_h, gen::include::header().as_bytes())?;
         out::symlink_file(shared_cxx_h, cxx_h)?;
     }
     Ok(include_dir)
 }
 
-fn generate_bridge(prj: &Project, build: &mut Build, rust_source_file: &Path) -> Result<()> {
+fn generate_bridge(prj: &Project, build: &mut Build, rust_source_file: &Path) -> Result<PathBuf> {
     let opt = Opt {
         allow_dot_includes: false,
         cfg_evaluator: Box::new(CargoEnvCfgEvaluator),
         doxygen: CFG.doxygen,
+        exceptions: CFG.exceptions,
+        cxx_standard: CFG.cxx_standard.unwrap_or(CxxStandard::Cxx11),
         ..Opt::default()
     };
     let generated = gen::generate_from_path(rust_source_file, &opt);
@@ -416,16 +442,25 @@ fn generate_bridge(prj: &Project, build: &mut Build, rust_source_file: &Path) ->
     let ref link_path = include_dir.join(rel_path);
     let _ = out::symlink_file(header_path, link_path);
 
+    let ref rel_path_fwd_h = rel_path.with_appended_extension(".fwd.h");
+    let ref fwd_header_path = include_dir.join(rel_path_fwd_h);
+    out::write(fwd_header_path, &generated.fwd_header)?;
+
     let ref rel_path_cc = rel_path.with_appended_extension(".cc");
     let ref implementation_path = sources_dir.join(rel_path_cc);
     out::write(implementation_path, &generated.implementation)?;
     build.file(implementation_path);
 
     let shared_h = prj.shared_dir.join(&prj.include_prefix).join(rel_path_h);
+    let shared_fwd_h = prj
+        .shared_dir
+        .join(&prj.include_prefix)
+        .join(rel_path_fwd_h);
     let shared_cc = prj.shared_dir.join(&prj.include_prefix).join(rel_path_cc);
     let _ = out::symlink_file(header_path, shared_h);
+    let _ = out::symlink_file(fwd_header_path, shared_fwd_h);
     let _ = out::symlink_file(implementation_path, shared_cc);
-    Ok(())
+    Ok(implementation_path.clone())
 }
 
 fn best_effort_copy_headers(src: &Path, dst: &Path, max_depth: usize) {
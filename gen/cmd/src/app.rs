@@ -5,8 +5,10 @@ mod test;
 use super::{Opt, Output};
 use crate::cfg::{self, CfgValue};
 use crate::gen::include::Include;
+use crate::gen::{CxxStandard, Module};
 use crate::syntax::IncludeKind;
 use clap::builder::{ArgAction, ValueParser};
+use clap::error::ErrorKind;
 use clap::{Arg, Command};
 use std::collections::{BTreeMap as Map, BTreeSet as Set};
 use std::path::PathBuf;
@@ -39,12 +41,20 @@ fn app() -> Command {
         .disable_help_flag(true)
         .disable_version_flag(true)
         .arg(arg_input())
+        .arg(arg_c_header())
         .arg(arg_cfg())
+        .arg(arg_check())
         .arg(arg_cxx_impl_annotations())
+        .arg(arg_fwd_header())
         .arg(arg_header())
         .arg(arg_help())
         .arg(arg_include())
-        .arg(arg_output());
+        .arg(arg_module())
+        .arg(arg_module_header())
+        .arg(arg_no_exceptions())
+        .arg(arg_output())
+        .arg(arg_preserve_cfg())
+        .arg(arg_std());
     if let Some(version) = option_env!("CARGO_PKG_VERSION") {
         app = app.arg(arg_version()).version(version);
     }
@@ -52,12 +62,20 @@ fn app() -> Command {
 }
 
 const INPUT: &str = "input";
+const C_HEADER: &str = "c-header";
 const CFG: &str = "cfg";
+const CHECK: &str = "check";
 const CXX_IMPL_ANNOTATIONS: &str = "cxx-impl-annotations";
+const FWD_HEADER: &str = "fwd-header";
 const HELP: &str = "help";
 const HEADER: &str = "header";
 const INCLUDE: &str = "include";
+const MODULE: &str = "module";
+const MODULE_HEADER: &str = "module-header";
+const NO_EXCEPTIONS: &str = "no-exceptions";
 const OUTPUT: &str = "output";
+const PRESERVE_CFG: &str = "preserve-cfg";
+const STD: &str = "std";
 const VERSION: &str = "version";
 
 pub(super) fn from_args() -> Opt {
@@ -73,23 +91,26 @@ pub(super) fn from_args() -> Opt {
         .get_one::<String>(CXX_IMPL_ANNOTATIONS)
         .map(String::clone);
     let header = matches.get_flag(HEADER);
+    let fwd_header = matches.get_flag(FWD_HEADER);
+    let c_header = matches.get_flag(C_HEADER);
+    let exceptions = !matches.get_flag(NO_EXCEPTIONS);
+    let preserve_cfg = matches.get_flag(PRESERVE_CFG);
+    let cxx_standard = match matches.get_one::<String>(STD).map(String::as_str) {
+        None | Some("c++11") => CxxStandard::Cxx11,
+        Some("c++14") => CxxStandard::Cxx14,
+        Some("c++17") => CxxStandard::Cxx17,
+        Some("c++20") => CxxStandard::Cxx20,
+        Some(_) => unreachable!(),
+    };
     let include = matches
         .get_many::<String>(INCLUDE)
         .unwrap_or_default()
-        .map(|include| {
-            if include.starts_with('<') && include.ends_with('>') {
-                Include {
-                    path: include[1..include.len() - 1].to_owned(),
-                    kind: IncludeKind::Bracketed,
-                }
-            } else {
-                Include {
-                    path: include.to_owned(),
-                    kind: IncludeKind::Quoted,
-                }
-            }
-        })
+        .map(|include| parse_include(include))
         .collect();
+    let module = matches.get_one::<String>(MODULE).map(|name| Module {
+        name: name.clone(),
+        header: parse_include(matches.get_one::<String>(MODULE_HEADER).unwrap()),
+    });
 
     let mut outputs = Vec::new();
     for path in matches.get_many::<PathBuf>(OUTPUT).unwrap_or_default() {
@@ -103,6 +124,32 @@ pub(super) fn from_args() -> Opt {
         outputs.push(Output::Stdout);
     }
 
+    let check = matches.get_flag(CHECK);
+    if check
+        && outputs
+            .iter()
+            .any(|output| matches!(output, Output::Stdout))
+    {
+        app()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--check compares against existing files, it cannot be used with output to stdout",
+            )
+            .exit();
+    }
+
+    let module_output = outputs
+        .iter()
+        .any(|output| output.ends_with(".cppm") || output.ends_with(".ixx"));
+    if input.is_some() && module_output && module.is_none() {
+        app()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "--module is required to name the module of a generated module interface",
+            )
+            .exit();
+    }
+
     let mut cfg = Map::new();
     for arg in matches.get_many::<String>(CFG).unwrap_or_default() {
         let (name, value) = cfg::parse.parse_str(arg).unwrap();
@@ -112,10 +159,31 @@ pub(super) fn from_args() -> Opt {
     Opt {
         input,
         header,
+        fwd_header,
+        c_header,
+        module,
         cxx_impl_annotations,
+        exceptions,
         include,
         outputs,
         cfg,
+        preserve_cfg,
+        cxx_standard,
+        check,
+    }
+}
+
+fn parse_include(include: &str) -> Include {
+    if include.starts_with('<') && include.ends_with('>') {
+        Include {
+            path: include[1..include.len() - 1].to_owned(),
+            kind: IncludeKind::Bracketed,
+        }
+    } else {
+        Include {
+            path: include.to_owned(),
+            kind: IncludeKind::Quoted,
+        }
     }
 }
 
@@ -126,6 +194,20 @@ fn arg_input() -> Arg {
         .value_parser(ValueParser::path_buf())
 }
 
+fn arg_c_header() -> Arg {
+    const HELP: &str = "\
+Emit C header declaring the extern \"Rust\" functions for callers
+written in C, with pointer+length pairs in place of &str and
+slices. Fails if any of them is not expressible in C. Optional
+if using `-o` with a path ending in `.c.h`.";
+    Arg::new(C_HEADER)
+        .long(C_HEADER)
+        .num_args(0)
+        .requires(INPUT)
+        .conflicts_with_all(&[HEADER, FWD_HEADER, MODULE])
+        .help(HELP)
+}
+
 fn arg_cfg() -> Arg {
     const HELP: &str = "\
 Compilation configuration matching what will be used to build
@@ -153,6 +235,19 @@ the Rust side of the bridge.";
         .help(HELP)
 }
 
+fn arg_check() -> Arg {
+    const HELP: &str = "\
+Instead of writing the output files, check that they already
+contain exactly what would be generated. Exits with an error
+describing the first difference in each file that is out of
+date. Requires -o.";
+    Arg::new(CHECK)
+        .long(CHECK)
+        .num_args(0)
+        .requires(OUTPUT)
+        .help(HELP)
+}
+
 fn arg_cxx_impl_annotations() -> Arg {
     const HELP: &str = "\
 Optional annotation for implementations of C++ function wrappers
@@ -176,6 +271,18 @@ by C++ code.
         .help(HELP)
 }
 
+fn arg_fwd_header() -> Arg {
+    const HELP: &str = "\
+Emit header with only forward declarations of the shared structs,
+shared enums and opaque Rust types. Optional if using `-o` with a
+path ending in `.fwd.h`.";
+    Arg::new(FWD_HEADER)
+        .long(FWD_HEADER)
+        .num_args(0)
+        .conflicts_with(HEADER)
+        .help(HELP)
+}
+
 fn arg_header() -> Arg {
     const HELP: &str = "\
 Emit header with declarations only. Optional if using `-o` with
@@ -204,6 +311,48 @@ into the generated C++ code as #include lines.";
         .help(HELP)
 }
 
+fn arg_module() -> Arg {
+    const HELP: &str = "\
+Emit C++20 module interface unit for the bridge, exporting its
+declarations as the module of the given name. Output paths
+ending in `.cppm` or `.ixx` receive the module interface. It
+imports the `cxx` module, whose interface unit is emitted by
+`--header` with no input file and such an output path.";
+    Arg::new(MODULE)
+        .long(MODULE)
+        .num_args(1)
+        .value_name("name")
+        .value_parser(ValueParser::string())
+        .requires(MODULE_HEADER)
+        .conflicts_with_all(&[HEADER, FWD_HEADER])
+        .help(HELP)
+}
+
+fn arg_module_header() -> Arg {
+    const HELP: &str = "\
+Path by which the module interface unit #includes the header
+generated for the bridge, from which it exports the declarations.
+Required with --module.";
+    Arg::new(MODULE_HEADER)
+        .long(MODULE_HEADER)
+        .num_args(1)
+        .value_name("path")
+        .value_parser(ValueParser::string())
+        .requires(MODULE)
+        .help(HELP)
+}
+
+fn arg_no_exceptions() -> Arg {
+    const HELP: &str = "\
+Generate C++ that does not use exceptions, for code built with
+-fno-exceptions. Functions returning Result are declared in C++
+as returning rust::Expected<T> instead of throwing rust::Error.";
+    Arg::new(NO_EXCEPTIONS)
+        .long(NO_EXCEPTIONS)
+        .num_args(0)
+        .help(HELP)
+}
+
 fn arg_output() -> Arg {
     const HELP: &str = "\
 Path of file to write as output. Output goes to stdout if -o is
@@ -217,6 +366,32 @@ not specified.";
         .help(HELP)
 }
 
+fn arg_preserve_cfg() -> Arg {
+    const HELP: &str = "\
+Instead of requiring every cfg attribute to be decided by --cfg,
+emit the items whose cfg is left undecided inside preprocessor
+conditionals. #[cfg(name)] is tested as CXXBRIDGE_CFG_name and
+#[cfg(name = \"value\")] as CXXBRIDGE_CFG_name_eq_value, with every
+underscore doubled and any other character not valid in an
+identifier written as _x and two hex digits per byte.";
+    Arg::new(PRESERVE_CFG)
+        .long(PRESERVE_CFG)
+        .num_args(0)
+        .help(HELP)
+}
+
+fn arg_std() -> Arg {
+    const HELP: &str = "\
+Version of the C++ standard that the generated code will be
+compiled as. Defaults to c++11.";
+    Arg::new(STD)
+        .long(STD)
+        .num_args(1)
+        .value_name("standard")
+        .value_parser(["c++11", "c++14", "c++17", "c++20"])
+        .help(HELP)
+}
+
 fn arg_version() -> Arg {
     Arg::new(VERSION)
         .long(VERSION)
@@ -17,11 +17,12 @@ impl CfgValue {
 
 pub struct FlagsCfgEvaluator {
     map: Map<String, Set<CfgValue>>,
+    preserve_cfg: bool,
 }
 
 impl FlagsCfgEvaluator {
-    pub fn new(map: Map<String, Set<CfgValue>>) -> Self {
-        FlagsCfgEvaluator { map }
+    pub fn new(map: Map<String, Set<CfgValue>>, preserve_cfg: bool) -> Self {
+        FlagsCfgEvaluator { map, preserve_cfg }
     }
 }
 
@@ -31,7 +32,9 @@ impl CfgEvaluator for FlagsCfgEvaluator {
         if let Some(value) = value {
             if let Some(set) = set {
                 CfgResult::from(set.contains(&CfgValue::Str(value.to_owned())))
-            } else if name == "feature" {
+            } else if name == "feature" && !self.preserve_cfg {
+                // Without --preserve-cfg, features not listed are disabled.
+                // With it, they're left for the C++ build to decide.
                 CfgResult::False
             } else {
                 let msg = format!(
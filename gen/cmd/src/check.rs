@@ -0,0 +1,48 @@
+use crate::gen::fs;
+use std::io;
+use std::path::Path;
+
+pub(crate) enum Outcome {
+    UpToDate,
+    Missing,
+    Stale(Difference),
+}
+
+pub(crate) struct Difference {
+    pub line: usize,
+    pub existing: Option<String>,
+    pub generated: Option<String>,
+}
+
+// Compares what would be generated against the file currently on disk, without
+// modifying anything.
+pub(crate) fn check(path: &Path, generated: &[u8]) -> fs::Result<Outcome> {
+    let existing = match fs::read(path) {
+        Ok(existing) => existing,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Outcome::Missing),
+        Err(err) => return Err(err),
+    };
+    if existing == generated {
+        return Ok(Outcome::UpToDate);
+    }
+
+    let existing = String::from_utf8_lossy(&existing);
+    let generated = String::from_utf8_lossy(generated);
+    let mut existing_lines = existing.split('\n');
+    let mut generated_lines = generated.split('\n');
+    let mut line = 1;
+    loop {
+        let existing = existing_lines.next();
+        let generated = generated_lines.next();
+        // Both at the end can only happen if the difference was in bytes that
+        // are not valid UTF-8.
+        if existing != generated || existing.is_none() {
+            return Ok(Outcome::Stale(Difference {
+                line,
+                existing: existing.map(str::to_owned),
+                generated: generated.map(str::to_owned),
+            }));
+        }
+        line += 1;
+    }
+}
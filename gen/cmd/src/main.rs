@@ -31,14 +31,17 @@
 
 mod app;
 mod cfg;
+mod check;
 mod gen;
 mod output;
 mod syntax;
 
 use crate::cfg::{CfgValue, FlagsCfgEvaluator};
+use crate::check::Outcome;
 use crate::gen::error::{report, Result};
 use crate::gen::fs;
 use crate::gen::include::{self, Include};
+use crate::gen::{CxxStandard, Module};
 use crate::output::Output;
 use std::collections::{BTreeMap as Map, BTreeSet as Set};
 use std::io::{self, Write};
@@ -49,10 +52,17 @@ use std::process;
 struct Opt {
     input: Option<PathBuf>,
     header: bool,
+    fwd_header: bool,
+    c_header: bool,
+    module: Option<Module>,
     cxx_impl_annotations: Option<String>,
+    exceptions: bool,
     include: Vec<Include>,
     outputs: Vec<Output>,
     cfg: Map<String, Set<CfgValue>>,
+    preserve_cfg: bool,
+    cxx_standard: CxxStandard,
+    check: bool,
 }
 
 fn main() {
@@ -64,8 +74,12 @@ fn main() {
 
 enum Kind {
     GeneratedHeader,
+    GeneratedFwdHeader,
+    GeneratedCHeader,
     GeneratedImplementation,
+    GeneratedModuleInterface,
     Header,
+    ModuleInterface,
 }
 
 fn try_main() -> Result<()> {
@@ -73,10 +87,25 @@ fn try_main() -> Result<()> {
 
     let mut outputs = Vec::new();
     let mut gen_header = false;
+    let mut gen_fwd_header = false;
+    let mut gen_c_header = false;
     let mut gen_implementation = false;
     for output in opt.outputs {
+        let module_output = output.ends_with(".cppm") || output.ends_with(".ixx");
         let kind = if opt.input.is_none() {
-            Kind::Header
+            if module_output {
+                Kind::ModuleInterface
+            } else {
+                Kind::Header
+            }
+        } else if opt.module.is_some() && (module_output || matches!(output, Output::Stdout)) {
+            Kind::GeneratedModuleInterface
+        } else if opt.c_header || output.ends_with(".c.h") {
+            gen_c_header = true;
+            Kind::GeneratedCHeader
+        } else if opt.fwd_header || output.ends_with(".fwd.h") {
+            gen_fwd_header = true;
+            Kind::GeneratedFwdHeader
         } else if opt.header
             || output.ends_with(".h")
             || output.ends_with(".hh")
@@ -94,9 +123,15 @@ fn try_main() -> Result<()> {
     let gen = gen::Opt {
         include: opt.include,
         cxx_impl_annotations: opt.cxx_impl_annotations,
+        exceptions: opt.exceptions,
         gen_header,
+        gen_fwd_header,
         gen_implementation,
-        cfg_evaluator: Box::new(FlagsCfgEvaluator::new(opt.cfg)),
+        cfg_evaluator: Box::new(FlagsCfgEvaluator::new(opt.cfg, opt.preserve_cfg)),
+        preserve_cfg: opt.preserve_cfg,
+        cxx_standard: opt.cxx_standard,
+        module: opt.module,
+        c_header: gen_c_header,
         ..Default::default()
     };
 
@@ -106,17 +141,60 @@ fn try_main() -> Result<()> {
         Default::default()
     };
 
+    let header = include::header();
+    let mut checked = 0;
+    let mut out_of_date = 0;
     for (output, kind) in outputs {
         let content = match kind {
             Kind::GeneratedHeader => &generated_code.header,
+            Kind::GeneratedFwdHeader => &generated_code.fwd_header,
+            Kind::GeneratedCHeader => &generated_code.c_header,
             Kind::GeneratedImplementation => &generated_code.implementation,
-            Kind::Header => include::HEADER.as_bytes(),
+            Kind::GeneratedModuleInterface => &generated_code.module_interface,
+            Kind::Header => header.as_bytes(),
+            Kind::ModuleInterface => include::MODULE_INTERFACE.as_bytes(),
         };
         match output {
             Output::Stdout => drop(io::stdout().write_all(content)),
+            Output::File(path) if opt.check => {
+                checked += 1;
+                let stderr = &mut io::stderr();
+                match check::check(&path, content)? {
+                    Outcome::UpToDate => continue,
+                    Outcome::Missing => {
+                        let _ = writeln!(stderr, "cxxbridge: `{}` does not exist", path.display());
+                    }
+                    Outcome::Stale(difference) => {
+                        let _ = writeln!(
+                            stderr,
+                            "cxxbridge: `{}` is out of date, first difference at line {}:",
+                            path.display(),
+                            difference.line,
+                        );
+                        for (sign, line) in
+                            [("-", difference.existing), ("+", difference.generated)]
+                        {
+                            let line = line.unwrap_or_else(|| "(end of file)".to_owned());
+                            let _ = writeln!(stderr, "  {} {}", sign, line);
+                        }
+                    }
+                }
+                out_of_date += 1;
+            }
             Output::File(path) => fs::write(path, content)?,
         }
     }
 
+    if out_of_date > 0 {
+        let _ = writeln!(
+            io::stderr(),
+            "cxxbridge: {} of {} checked file{} out of date; rerun without --check to regenerate",
+            out_of_date,
+            checked,
+            if checked == 1 { " is" } else { "s are" },
+        );
+        process::exit(1);
+    }
+
     Ok(())
 }
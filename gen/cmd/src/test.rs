@@ -13,10 +13,22 @@ Arguments:
           Input Rust source file containing #[cxx::bridge].
 
 Options:
+      --c-header
+          Emit C header declaring the extern \"Rust\" functions for callers
+          written in C, with pointer+length pairs in place of &str and
+          slices. Fails if any of them is not expressible in C. Optional
+          if using `-o` with a path ending in `.c.h`.
+
       --cfg <name=\"value\" | name[=true] | name=false>
           Compilation configuration matching what will be used to build
           the Rust side of the bridge.
 
+      --check
+          Instead of writing the output files, check that they already
+          contain exactly what would be generated. Exits with an error
+          describing the first difference in each file that is out of
+          date. Requires -o.
+
         --cxx-impl-annotations <annotation>
             Optional annotation for implementations of C++ function wrappers
             that may be exposed to Rust. You may for example need to provide
@@ -31,6 +43,11 @@ Options:
             by Rust code; CXX_CPP_EXPORT attaches to symbols which are used
             by C++ code.
 
+      --fwd-header
+          Emit header with only forward declarations of the shared structs,
+          shared enums and opaque Rust types. Optional if using `-o` with a
+          path ending in `.fwd.h`.
+
       --header
           Emit header with declarations only. Optional if using `-o` with
           a path ending in `.h`.
@@ -43,10 +60,41 @@ Options:
           parse or even require the given paths to exist; they simply go
           into the generated C++ code as #include lines.
 
+      --module <name>
+          Emit C++20 module interface unit for the bridge, exporting its
+          declarations as the module of the given name. Output paths
+          ending in `.cppm` or `.ixx` receive the module interface. It
+          imports the `cxx` module, whose interface unit is emitted by
+          `--header` with no input file and such an output path.
+
+      --module-header <path>
+          Path by which the module interface unit #includes the header
+          generated for the bridge, from which it exports the declarations.
+          Required with --module.
+
+      --no-exceptions
+          Generate C++ that does not use exceptions, for code built with
+          -fno-exceptions. Functions returning Result are declared in C++
+          as returning rust::Expected<T> instead of throwing rust::Error.
+
   -o, --output <output>
           Path of file to write as output. Output goes to stdout if -o is
           not specified.
 
+      --preserve-cfg
+          Instead of requiring every cfg attribute to be decided by --cfg,
+          emit the items whose cfg is left undecided inside preprocessor
+          conditionals. #[cfg(name)] is tested as CXXBRIDGE_CFG_name and
+          #[cfg(name = \"value\")] as CXXBRIDGE_CFG_name_eq_value, with every
+          underscore doubled and any other character not valid in an
+          identifier written as _x and two hex digits per byte.
+
+      --std <standard>
+          Version of the C++ standard that the generated code will be
+          compiled as. Defaults to c++11.
+          
+          [possible values: c++11, c++14, c++17, c++20]
+
       --version
           Print version information.
 ";
@@ -45,8 +45,8 @@ mod gen;
 mod syntax;
 
 pub use crate::error::Error;
-pub use crate::gen::include::{Include, HEADER};
-pub use crate::gen::{GeneratedCode, Opt};
+pub use crate::gen::include::{Include, HEADER, MODULE_INTERFACE};
+pub use crate::gen::{CxxStandard, GeneratedCode, Module, Opt};
 pub use crate::syntax::IncludeKind;
 use proc_macro2::TokenStream;
 
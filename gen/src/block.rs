@@ -1,16 +1,27 @@
+use crate::gen::cfg;
+use crate::syntax::cfg::CfgExpr;
 use proc_macro2::Ident;
+use std::fmt::{self, Debug};
+use std::ptr;
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone)]
 pub enum Block<'a> {
     AnonymousNamespace,
     Namespace(&'static str),
     UserDefinedNamespace(&'a Ident),
     InlineNamespace(&'static str),
     ExternC,
+    Cfg(&'a CfgExpr),
 }
 
 impl<'a> Block<'a> {
     pub fn write_begin(self, out: &mut String) {
+        if let Block::Cfg(expr) = self {
+            out.push_str("#if ");
+            cfg::write_condition(out, expr);
+            out.push('\n');
+            return;
+        }
         if let Block::InlineNamespace(_) = self {
             out.push_str("inline ");
         }
@@ -19,6 +30,12 @@ impl<'a> Block<'a> {
     }
 
     pub fn write_end(self, out: &mut String) {
+        if let Block::Cfg(expr) = self {
+            out.push_str("#endif // ");
+            cfg::write_condition(out, expr);
+            out.push('\n');
+            return;
+        }
         out.push_str("} // ");
         self.write_common(out);
         out.push('\n');
@@ -40,6 +57,33 @@ impl<'a> Block<'a> {
                 out.push_str(name);
             }
             Block::ExternC => out.push_str("extern \"C\""),
+            Block::Cfg(_) => unreachable!(),
+        }
+    }
+}
+
+impl<'a> PartialEq for Block<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Block::AnonymousNamespace, Block::AnonymousNamespace) => true,
+            (Block::Namespace(a), Block::Namespace(b)) => a == b,
+            (Block::UserDefinedNamespace(a), Block::UserDefinedNamespace(b)) => a == b,
+            (Block::InlineNamespace(a), Block::InlineNamespace(b)) => a == b,
+            (Block::ExternC, Block::ExternC) => true,
+            // A cfg block only ever closes the exact conditional that opened it.
+            (Block::Cfg(a), Block::Cfg(b)) => ptr::eq(*a, *b),
+            _ => false,
+        }
+    }
+}
+
+impl<'a> Debug for Block<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut string = String::new();
+        match self {
+            Block::Cfg(_) => string.push_str("#if"),
+            _ => self.write_common(&mut string),
         }
+        formatter.write_str(&string)
     }
 }
@@ -1,6 +1,8 @@
 use crate::gen::block::Block;
 use crate::gen::ifndef;
 use crate::gen::out::{Content, OutFile};
+use crate::syntax::mangle::{self, CXXBRIDGE};
+use std::collections::BTreeSet;
 
 #[derive(Default, PartialEq)]
 pub struct Builtins<'a> {
@@ -9,18 +11,24 @@ pub struct Builtins<'a> {
     pub rust_str: bool,
     pub rust_slice: bool,
     pub rust_box: bool,
+    pub rust_arc: bool,
     pub rust_vec: bool,
     pub rust_fn: bool,
+    pub rust_fn_mut: bool,
     pub rust_isize: bool,
     pub opaque: bool,
     pub layout: bool,
     pub unsafe_bitcopy: bool,
     pub unsafe_bitcopy_t: bool,
     pub rust_error: bool,
+    pub rust_panic: bool,
+    pub rust_expected: bool,
     pub manually_drop: bool,
     pub maybe_uninit: bool,
     pub trycatch: bool,
+    pub fail: bool,
     pub ptr_len: bool,
+    pub repr_panic: bool,
     pub repr_fat: bool,
     pub rust_str_new_unchecked: bool,
     pub rust_str_repr: bool,
@@ -32,6 +40,9 @@ pub struct Builtins<'a> {
     pub is_complete: bool,
     pub destroy: bool,
     pub deleter_if: bool,
+    pub base_cast: bool,
+    pub shared_from_this: bool,
+    pub tuple_repr: BTreeSet<usize>,
     pub content: Content<'a>,
 }
 
@@ -94,18 +105,52 @@ pub(super) fn write(out: &mut OutFile) {
     }
 
     if builtin.rust_box {
+        include.memory = true;
         include.new = true;
         include.type_traits = true;
         include.utility = true;
     }
 
+    if builtin.rust_arc {
+        include.utility = true;
+    }
+
     if builtin.rust_fn {
         include.utility = true;
     }
 
+    if builtin.rust_fn_mut {
+        include.functional = true;
+        include.memory = true;
+        include.type_traits = true;
+        include.utility = true;
+    }
+
+    if builtin.rust_expected {
+        include.new = true;
+        include.stdexcept = true;
+        include.utility = true;
+        builtin.panic = true;
+        builtin.rust_error = true;
+    }
+
     if builtin.rust_error {
         include.exception = true;
         builtin.friend_impl = true;
+        builtin.ptr_len = true;
+    }
+
+    if builtin.rust_panic {
+        include.cstddef = true;
+        include.cstdint = true;
+        include.exception = true;
+        builtin.friend_impl = true;
+        builtin.repr_panic = true;
+    }
+
+    if builtin.repr_panic {
+        include.cstdint = true;
+        builtin.ptr_len = true;
     }
 
     if builtin.rust_isize {
@@ -138,17 +183,21 @@ pub(super) fn write(out: &mut OutFile) {
     }
 
     if builtin.trycatch {
+        builtin.fail = true;
+    }
+
+    if builtin.fail {
         builtin.ptr_len = true;
     }
 
     out.begin_block(Block::Namespace("rust"));
-    out.begin_block(Block::InlineNamespace("cxxbridge1"));
+    out.begin_block(Block::InlineNamespace(CXXBRIDGE));
 
     let cxx_header = include.has_cxx_header();
     if !cxx_header {
         writeln!(out, "// #include \"rust/cxx.h\"");
 
-        ifndef::write(out, builtin.panic, "CXXBRIDGE1_PANIC");
+        ifndef::write(out, builtin.panic, "PANIC");
 
         if builtin.rust_string {
             out.next_section();
@@ -178,20 +227,24 @@ pub(super) fn write(out: &mut OutFile) {
             writeln!(out, "::std::size_t align_of();");
         }
 
-        ifndef::write(out, builtin.rust_string, "CXXBRIDGE1_RUST_STRING");
-        ifndef::write(out, builtin.rust_str, "CXXBRIDGE1_RUST_STR");
-        ifndef::write(out, builtin.rust_slice, "CXXBRIDGE1_RUST_SLICE");
-        ifndef::write(out, builtin.rust_box, "CXXBRIDGE1_RUST_BOX");
-        ifndef::write(out, builtin.unsafe_bitcopy_t, "CXXBRIDGE1_RUST_BITCOPY_T");
-        ifndef::write(out, builtin.unsafe_bitcopy, "CXXBRIDGE1_RUST_BITCOPY");
-        ifndef::write(out, builtin.rust_vec, "CXXBRIDGE1_RUST_VEC");
-        ifndef::write(out, builtin.rust_fn, "CXXBRIDGE1_RUST_FN");
-        ifndef::write(out, builtin.rust_error, "CXXBRIDGE1_RUST_ERROR");
-        ifndef::write(out, builtin.rust_isize, "CXXBRIDGE1_RUST_ISIZE");
-        ifndef::write(out, builtin.opaque, "CXXBRIDGE1_RUST_OPAQUE");
-        ifndef::write(out, builtin.is_complete, "CXXBRIDGE1_IS_COMPLETE");
-        ifndef::write(out, builtin.layout, "CXXBRIDGE1_LAYOUT");
-        ifndef::write(out, builtin.relocatable, "CXXBRIDGE1_RELOCATABLE");
+        ifndef::write(out, builtin.rust_string, "RUST_STRING");
+        ifndef::write(out, builtin.rust_str, "RUST_STR");
+        ifndef::write(out, builtin.rust_slice, "RUST_SLICE");
+        ifndef::write(out, builtin.rust_box, "RUST_BOX");
+        ifndef::write(out, builtin.rust_arc, "RUST_ARC");
+        ifndef::write(out, builtin.unsafe_bitcopy_t, "RUST_BITCOPY_T");
+        ifndef::write(out, builtin.unsafe_bitcopy, "RUST_BITCOPY");
+        ifndef::write(out, builtin.rust_vec, "RUST_VEC");
+        ifndef::write(out, builtin.rust_fn, "RUST_FN");
+        ifndef::write(out, builtin.rust_fn_mut, "RUST_FNMUT");
+        ifndef::write(out, builtin.rust_error, "RUST_ERROR");
+        ifndef::write(out, builtin.rust_panic, "RUST_PANIC");
+        ifndef::write(out, builtin.rust_expected, "RUST_EXPECTED");
+        ifndef::write(out, builtin.rust_isize, "RUST_ISIZE");
+        ifndef::write(out, builtin.opaque, "RUST_OPAQUE");
+        ifndef::write(out, builtin.is_complete, "IS_COMPLETE");
+        ifndef::write(out, builtin.layout, "LAYOUT");
+        ifndef::write(out, builtin.relocatable, "RELOCATABLE");
     }
 
     if builtin.rust_str_new_unchecked {
@@ -226,6 +279,91 @@ pub(super) fn write(out: &mut OutFile) {
         writeln!(out, "}};");
     }
 
+    if builtin.repr_panic {
+        out.next_section();
+        writeln!(out, "struct Panic final {{");
+        writeln!(out, "  PtrLen msg;");
+        writeln!(out, "  PtrLen file;");
+        writeln!(out, "  ::std::uint32_t line;");
+        writeln!(out, "  ::std::uint32_t column;");
+        writeln!(out, "}};");
+    }
+
+    for &arity in &builtin.tuple_repr {
+        include.tuple = true;
+        include.utility = true;
+        let params = (0..arity)
+            .map(|i| format!("typename T{}", i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let args = (0..arity)
+            .map(|i| format!("T{}", i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let elems = (0..arity)
+            .map(|i| format!("_{}", i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        // Also the type of tuple fields of shared structs, so it is guarded
+        // against being defined by more than one generated header.
+        let guard = format!("{}_REPR_TUPLE{}", mangle::preprocessor_prefix(), arity);
+        out.next_section();
+        writeln!(out, "#ifndef {}", guard);
+        writeln!(out, "#define {}", guard);
+        writeln!(out, "template <{}>", params);
+        writeln!(out, "struct Tuple{} final {{", arity);
+        for i in 0..arity {
+            writeln!(out, "  T{} _{};", i, i);
+        }
+        writeln!(out, "  Tuple{}() = default;", arity);
+        write!(out, "  Tuple{}(", arity);
+        for i in 0..arity {
+            if i > 0 {
+                write!(out, ", ");
+            }
+            write!(out, "T{} t{}", i, i);
+        }
+        write!(out, ")");
+        for i in 0..arity {
+            let sep = if i == 0 { "\n      : " } else { ",\n        " };
+            write!(out, "{}_{}(::std::move(t{}))", sep, i, i);
+        }
+        writeln!(out, " {{}}");
+        write!(out, "  Tuple{}(::std::tuple<{}> &&tuple)", arity, args);
+        for i in 0..arity {
+            let sep = if i == 0 { "\n      : " } else { ",\n        " };
+            write!(out, "{}_{}(::std::move(::std::get<{}>(tuple)))", sep, i, i);
+        }
+        writeln!(out, " {{}}");
+        if arity == 2 {
+            writeln!(out, "  Tuple2(::std::pair<T0, T1> &&pair)");
+            writeln!(
+                out,
+                "      : _0(::std::move(pair.first)), _1(::std::move(pair.second)) {{}}"
+            );
+        }
+        writeln!(out, "  ::std::tuple<{}> into() && {{", args);
+        write!(out, "    return ::std::tuple<{}>(", args);
+        for i in 0..arity {
+            if i > 0 {
+                write!(out, ", ");
+            }
+            write!(out, "::std::move(_{})", i);
+        }
+        writeln!(out, ");");
+        writeln!(out, "  }}");
+        writeln!(out, "  operator ::std::tuple<{}>() const {{", args);
+        writeln!(out, "    return ::std::tuple<{}>({});", args, elems);
+        writeln!(out, "  }}");
+        if arity == 2 {
+            writeln!(out, "  operator ::std::pair<T0, T1>() const {{");
+            writeln!(out, "    return ::std::pair<T0, T1>(_0, _1);");
+            writeln!(out, "  }}");
+        }
+        writeln!(out, "}};");
+        writeln!(out, "#endif // {}", guard);
+    }
+
     out.end_block(Block::Namespace("repr"));
 
     out.begin_block(Block::Namespace("detail"));
@@ -254,7 +392,79 @@ pub(super) fn write(out: &mut OutFile) {
         writeln!(out, "}};");
     }
 
-    if builtin.trycatch {
+    if builtin.base_cast {
+        include.memory = true;
+        include.type_traits = true;
+        out.next_section();
+        writeln!(out, "template <typename Base, typename Derived>");
+        writeln!(
+            out,
+            "Base *upcast_unique(Derived *ptr, ::std::true_type) noexcept {{",
+        );
+        writeln!(out, "  return ptr;");
+        writeln!(out, "}}");
+        writeln!(out, "template <typename Base, typename Derived>");
+        writeln!(
+            out,
+            "Base *upcast_unique(Derived *, ::std::false_type) noexcept {{",
+        );
+        writeln!(out, "  return nullptr;");
+        writeln!(out, "}}");
+        out.next_section();
+        writeln!(out, "template <typename Derived, typename Base>");
+        writeln!(
+            out,
+            "::std::shared_ptr<Derived> dynamic_pointer_cast(::std::shared_ptr<Base> const &ptr, ::std::true_type) noexcept {{",
+        );
+        writeln!(out, "  return ::std::dynamic_pointer_cast<Derived>(ptr);");
+        writeln!(out, "}}");
+        writeln!(out, "template <typename Derived, typename Base>");
+        writeln!(
+            out,
+            "::std::shared_ptr<Derived> dynamic_pointer_cast(::std::shared_ptr<Base> const &, ::std::false_type) noexcept {{",
+        );
+        writeln!(out, "  return nullptr;");
+        writeln!(out, "}}");
+        out.next_section();
+        writeln!(out, "template <typename Derived, typename Base>");
+        writeln!(
+            out,
+            "auto static_pointer_cast(::std::shared_ptr<Base> const &ptr, int) noexcept -> decltype(static_cast<Derived *>(ptr.get()), ::std::shared_ptr<Derived>()) {{",
+        );
+        writeln!(out, "  return ::std::static_pointer_cast<Derived>(ptr);");
+        writeln!(out, "}}");
+        writeln!(out, "template <typename Derived, typename Base>");
+        writeln!(
+            out,
+            "::std::shared_ptr<Derived> static_pointer_cast(::std::shared_ptr<Base> const &, long) noexcept {{",
+        );
+        writeln!(out, "  return nullptr;");
+        writeln!(out, "}}");
+    }
+
+    if builtin.shared_from_this {
+        include.memory = true;
+        out.next_section();
+        writeln!(out, "template <typename T>");
+        writeln!(
+            out,
+            "auto shared_from_this(T const &self, int) noexcept -> decltype(self.weak_from_this(), ::std::shared_ptr<T>()) {{",
+        );
+        writeln!(
+            out,
+            "  return ::std::const_pointer_cast<T>(::std::static_pointer_cast<T const>(self.weak_from_this().lock()));",
+        );
+        writeln!(out, "}}");
+        writeln!(out, "template <typename T>");
+        writeln!(
+            out,
+            "::std::shared_ptr<T> shared_from_this(T const &, long) noexcept {{",
+        );
+        writeln!(out, "  return nullptr;");
+        writeln!(out, "}}");
+    }
+
+    if builtin.fail {
         include.string = true;
         out.next_section();
         writeln!(out, "class Fail final {{");
@@ -359,6 +569,30 @@ pub(super) fn write(out: &mut OutFile) {
         writeln!(out, "}};");
     }
 
+    if builtin.rust_panic {
+        out.next_section();
+        writeln!(out, "template <>");
+        writeln!(out, "class impl<Panic> final {{");
+        writeln!(out, "public:");
+        writeln!(out, "  static Panic panic(repr::Panic repr) noexcept {{");
+        writeln!(out, "    Panic panic;");
+        writeln!(
+            out,
+            "    panic.msg = static_cast<char const *>(repr.msg.ptr);"
+        );
+        writeln!(out, "    panic.len = repr.msg.len;");
+        writeln!(
+            out,
+            "    panic.file_ = static_cast<char const *>(repr.file.ptr);"
+        );
+        writeln!(out, "    panic.file_len = repr.file.len;");
+        writeln!(out, "    panic.line_ = repr.line;");
+        writeln!(out, "    panic.column_ = repr.column;");
+        writeln!(out, "    return panic;");
+        writeln!(out, "  }}");
+        writeln!(out, "}};");
+    }
+
     if builtin.destroy {
         out.next_section();
         writeln!(out, "template <typename T>");
@@ -393,7 +627,7 @@ pub(super) fn write(out: &mut OutFile) {
     }
 
     out.end_block(Block::AnonymousNamespace);
-    out.end_block(Block::InlineNamespace("cxxbridge1"));
+    out.end_block(Block::InlineNamespace(CXXBRIDGE));
 
     if builtin.trycatch {
         out.begin_block(Block::Namespace("behavior"));
@@ -0,0 +1,605 @@
+// The C header: the extern "Rust" functions of a bridge, along with the shared
+// structs, shared enums and opaque Rust types they deal in, for callers written
+// in C rather than C++. Each function is declared by its Rust symbol and given a
+// static inline wrapper under a C-friendly name, which is the part that turns
+// pointer+length pairs into the `&str` and `&[T]` representation. Extern "C++"
+// items have no place in it and are left out.
+
+use crate::gen::out::OutFile;
+use crate::gen::{write, Opt};
+use crate::syntax::atom::Atom::{self, *};
+use crate::syntax::mangle::CXXBRIDGE;
+use crate::syntax::report::Errors;
+use crate::syntax::set::{OrderedSet, UnorderedSet};
+use crate::syntax::symbol::Symbol;
+use crate::syntax::{
+    mangle, Api, Discriminant, EnumRepr, ExternFn, Pair, Signature, Struct, Type, Types,
+};
+use proc_macro2::Ident;
+
+pub(super) fn check(cx: &mut Errors, apis: &[Api], types: &Types) {
+    let c = C::new(types);
+
+    for api in apis {
+        match api {
+            Api::Enum(enm) if !types.cxx.contains(&enm.name.rust) => {
+                let min = "-2147483648".parse::<Discriminant>().unwrap();
+                let max = "2147483647".parse::<Discriminant>().unwrap();
+                for variant in &enm.variants {
+                    if variant.discriminant < min || variant.discriminant > max {
+                        cx.error(
+                            &variant.name.rust,
+                            "discriminant does not fit in a C enumeration constant",
+                        );
+                    }
+                }
+            }
+            Api::RustFunction(efn) => check_function(cx, &c, efn),
+            _ => {}
+        }
+    }
+}
+
+fn check_function(cx: &mut Errors, c: &C, efn: &ExternFn) {
+    if efn.throws {
+        cx.error(
+            efn,
+            "functions returning Result are not supported in the C header",
+        );
+    }
+    if efn.catch_unwind {
+        cx.error(efn, "#[catch_unwind] is not supported in the C header");
+    }
+    for arg in &efn.args {
+        if !c.is_signature_type(&arg.ty) {
+            cx.error(&arg.ty, "unsupported type in the C header");
+        }
+    }
+    if let Some(ret) = &efn.ret {
+        if !c.is_signature_type(ret) {
+            cx.error(ret, "unsupported return type in the C header");
+        }
+    }
+}
+
+pub(super) fn gen(apis: &[Api], types: &Types, opt: &Opt) -> Vec<u8> {
+    let mut out_file = OutFile::new(true, opt, types);
+    let out = &mut out_file;
+    let c = C::new(types);
+
+    let functions: Vec<&ExternFn> = apis
+        .iter()
+        .filter_map(|api| match api {
+            Api::RustFunction(efn) => Some(efn),
+            _ => None,
+        })
+        .collect();
+
+    let mut boxes = OrderedSet::new();
+    let mut fat = false;
+    let mut utf8 = false;
+    for efn in &functions {
+        for ty in efn.args.iter().map(|arg| &arg.ty).chain(&efn.ret) {
+            match ty {
+                Type::RustBox(ty) => {
+                    if let Type::Ident(inner) = &ty.inner {
+                        boxes.insert(&inner.rust);
+                    }
+                }
+                Type::Str(_) | Type::SliceRef(_) => fat = true,
+                _ => {}
+            }
+        }
+        utf8 |= efn.args.iter().any(|arg| matches!(arg.ty, Type::Str(_)));
+    }
+
+    writeln!(out, "#pragma once");
+    writeln!(out, "#include <stdbool.h>");
+    writeln!(out, "#include <stddef.h>");
+    writeln!(out, "#include <stdint.h>");
+    if utf8 {
+        writeln!(out, "#include <stdlib.h>");
+    }
+
+    out.next_section();
+    writeln!(out, "#ifdef __cplusplus");
+    writeln!(out, "extern \"C\" {{");
+    writeln!(out, "#endif");
+
+    if fat {
+        write_fat(out);
+    }
+
+    out.next_section();
+    for api in apis {
+        match api {
+            Api::Struct(strct) if !types.cxx.contains(&strct.name.rust) => {
+                out.begin_cfg(&strct.cfg);
+                let name = c_name(&strct.name);
+                writeln!(out, "typedef struct {} {};", name, name);
+                out.end_cfg(&strct.cfg);
+            }
+            Api::RustType(ety) => {
+                out.begin_cfg(&ety.cfg);
+                let name = c_name(&ety.name);
+                writeln!(out, "typedef struct {} {};", name, name);
+                out.end_cfg(&ety.cfg);
+            }
+            _ => {}
+        }
+    }
+
+    for api in apis {
+        if let Api::Enum(enm) = api {
+            if types.cxx.contains(&enm.name.rust) {
+                continue;
+            }
+            out.next_section();
+            out.begin_cfg(&enm.cfg);
+            write::write_doc(out, "", &enm.doc);
+            let name = c_name(&enm.name);
+            write!(out, "typedef ");
+            match &enm.repr {
+                #[cfg(feature = "experimental-enum-variants-from-header")]
+                EnumRepr::Foreign { .. } => unreachable!(),
+                EnumRepr::Native { atom, .. } => write_atom(out, *atom),
+            }
+            writeln!(out, " {};", name);
+            writeln!(out, "enum {{");
+            for variant in &enm.variants {
+                write::write_doc(out, "  ", &variant.doc);
+                writeln!(
+                    out,
+                    "  {}_{} = {},",
+                    name, variant.name.cxx, variant.discriminant,
+                );
+            }
+            writeln!(out, "}};");
+            out.end_cfg(&enm.cfg);
+        }
+    }
+
+    for strct in &types.toposorted_structs {
+        if c.complete_structs.contains(&strct.name.rust) {
+            out.next_section();
+            write_struct(out, strct);
+        }
+    }
+
+    for efn in &functions {
+        out.next_section();
+        write_function(out, efn);
+    }
+
+    for ident in &boxes {
+        out.next_section();
+        write_box_free(out, ident);
+    }
+
+    out.next_section();
+    writeln!(out, "#ifdef __cplusplus");
+    writeln!(out, "}} // extern \"C\"");
+    writeln!(out, "#endif");
+
+    out_file.content()
+}
+
+struct C<'a> {
+    types: &'a Types<'a>,
+    // Shared structs all of whose fields are expressible in C, which therefore
+    // get a full definition rather than only being declared.
+    complete_structs: UnorderedSet<&'a Ident>,
+}
+
+impl<'a> C<'a> {
+    fn new(types: &'a Types<'a>) -> Self {
+        let mut c = C {
+            types,
+            complete_structs: UnorderedSet::new(),
+        };
+        for strct in &types.toposorted_structs {
+            let complete = !types.cxx.contains(&strct.name.rust)
+                && strct.fields.iter().all(|field| match &field.ty {
+                    Type::Array(array) => c.is_value(&array.inner),
+                    ty => c.is_value(ty) || matches!(ty, Type::Ptr(_)) && c.is_signature_type(ty),
+                });
+            if complete {
+                c.complete_structs.insert(&strct.name.rust);
+            }
+        }
+        c
+    }
+
+    // Types that C can hold by value.
+    fn is_value(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Ident(ident) => match Atom::from(&ident.rust) {
+                Some(CxxString) | Some(RustString) => false,
+                Some(_) => true,
+                None => {
+                    self.complete_structs.contains(&ident.rust)
+                        || self.types.enums.contains_key(&ident.rust)
+                            && !self.types.cxx.contains(&ident.rust)
+                }
+            },
+            _ => false,
+        }
+    }
+
+    // Types that C can hold a pointer to, possibly without knowing their
+    // definition.
+    fn is_pointee(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Ident(ident) => {
+                self.is_value(ty)
+                    || self.types.rust.contains(&ident.rust)
+                    || self.types.structs.contains_key(&ident.rust)
+                        && !self.types.cxx.contains(&ident.rust)
+            }
+            Type::Ptr(ptr) => self.is_pointee(&ptr.inner),
+            _ => false,
+        }
+    }
+
+    fn is_signature_type(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Ident(_) => self.is_value(ty),
+            Type::Ref(ty) => self.is_pointee(&ty.inner),
+            Type::Ptr(ty) => self.is_pointee(&ty.inner),
+            Type::RustBox(ty) => match &ty.inner {
+                Type::Ident(inner) => {
+                    self.types.rust.contains(&inner.rust)
+                        || self.types.structs.contains_key(&inner.rust)
+                            && !self.types.cxx.contains(&inner.rust)
+                }
+                _ => false,
+            },
+            Type::Str(_) => true,
+            Type::SliceRef(ty) => self.is_value(&ty.inner),
+            _ => false,
+        }
+    }
+}
+
+fn c_name(name: &Pair) -> String {
+    let mut c_name = String::new();
+    for segment in &name.namespace {
+        c_name += &segment.to_string();
+        c_name.push('_');
+    }
+    c_name += &name.cxx.to_string();
+    c_name
+}
+
+fn write_fat(out: &mut OutFile) {
+    // The representation of &str and &[T] is private to Rust, so they are only
+    // ever built and taken apart through these functions from the cxx crate.
+    let guard = format!("{}_C_FAT", mangle::preprocessor_prefix());
+    let fat = fat();
+    out.next_section();
+    writeln!(out, "#ifndef {}", guard);
+    writeln!(out, "#define {}", guard);
+    writeln!(out, "typedef struct {} {{", fat);
+    writeln!(out, "  uintptr_t repr[2];");
+    writeln!(out, "}} {};", fat);
+    writeln!(out, "#ifdef __cplusplus");
+    writeln!(out, "#define {}(T) alignof(T)", alignof());
+    writeln!(out, "#else");
+    writeln!(out, "#define {}(T) _Alignof(T)", alignof());
+    writeln!(out, "#endif");
+    writeln!(
+        out,
+        "bool {}$str$from({} *self, char const *ptr, size_t len);",
+        CXXBRIDGE, fat,
+    );
+    writeln!(
+        out,
+        "char const *{}$str$ptr({} const *self);",
+        CXXBRIDGE, fat,
+    );
+    writeln!(out, "size_t {}$str$len({} const *self);", CXXBRIDGE, fat);
+    writeln!(
+        out,
+        "void {}$slice$new({} *self, void const *ptr, size_t len);",
+        CXXBRIDGE, fat,
+    );
+    writeln!(out, "void *{}$slice$ptr({} const *self);", CXXBRIDGE, fat);
+    writeln!(out, "size_t {}$slice$len({} const *self);", CXXBRIDGE, fat);
+    writeln!(out, "#endif // {}", guard);
+}
+
+// The C type standing in for the fat pointer of &str and &[T].
+fn fat() -> String {
+    format!("{}_fat", CXXBRIDGE)
+}
+
+fn alignof() -> String {
+    format!("{}_C_ALIGNOF", mangle::preprocessor_prefix())
+}
+
+fn write_struct<'a>(out: &mut OutFile<'a>, strct: &'a Struct) {
+    out.begin_cfg(&strct.cfg);
+    write::write_doc(out, "", &strct.doc);
+    writeln!(out, "struct {} {{", c_name(&strct.name));
+    for field in &strct.fields {
+        out.begin_cfg(&field.cfg);
+        write::write_doc(out, "  ", &field.doc);
+        write!(out, "  ");
+        match &field.ty {
+            Type::Array(array) => {
+                write_type_space(out, &array.inner);
+                writeln!(out, "{}[{}];", field.name.cxx, array.len);
+            }
+            ty => {
+                write_type_space(out, ty);
+                writeln!(out, "{};", field.name.cxx);
+            }
+        }
+        out.end_cfg(&field.cfg);
+    }
+    writeln!(out, "}};");
+    out.end_cfg(&strct.cfg);
+}
+
+fn write_function<'a>(out: &mut OutFile<'a>, efn: &'a ExternFn) {
+    let link_name = mangle::extern_fn(efn, out.types);
+    let indirect_return = efn
+        .ret
+        .as_ref()
+        .map_or(false, |ret| out.types.needs_indirect_abi(ret));
+
+    out.begin_cfg(&efn.cfg);
+    write_extern_decl(out, &link_name, efn, indirect_return);
+
+    write::write_doc(out, "", &efn.doc);
+    write!(out, "static inline ");
+    match &efn.ret {
+        Some(Type::Str(_)) => write!(out, "char const *"),
+        Some(Type::SliceRef(slice)) => {
+            let ty = pointer_space(out.types, &slice.inner, slice.mutable);
+            write!(out, "{}", ty);
+        }
+        Some(ret) => write_type_space(out, ret),
+        None => write!(out, "void "),
+    }
+    let local_name = match (&efn.receiver, &efn.self_type) {
+        (Some(receiver), _) => {
+            let self_type = out.types.resolve(&receiver.ty).name;
+            format!("{}_{}", c_name(self_type), efn.name.cxx)
+        }
+        (None, Some(self_type)) => {
+            let self_type = out.types.resolve(self_type).name;
+            format!("{}_{}", c_name(self_type), efn.name.cxx)
+        }
+        (None, None) => c_name(&efn.name),
+    };
+    write!(out, "{}(", local_name);
+    let mut params = Vec::new();
+    if let Some(receiver) = &efn.receiver {
+        let self_type = c_name(out.types.resolve(&receiver.ty).name);
+        let constness = if receiver.mutable { "" } else { " const" };
+        params.push(format!("{}{} *self", self_type, constness));
+    }
+    for arg in &efn.args {
+        let mut param = String::new();
+        match &arg.ty {
+            Type::Str(_) => param += "char const *",
+            Type::SliceRef(slice) => {
+                param += &pointer_space(out.types, &slice.inner, slice.mutable);
+            }
+            ty => param += &type_space(out.types, ty),
+        }
+        param += &arg.name.cxx.to_string();
+        params.push(param);
+        if let Type::Str(_) | Type::SliceRef(_) = arg.ty {
+            params.push(format!("size_t {}_len", arg.name.cxx));
+        }
+    }
+    if let Some(Type::Str(_)) | Some(Type::SliceRef(_)) = efn.ret {
+        params.push("size_t *return_len".to_owned());
+    }
+    if params.is_empty() {
+        write!(out, "void");
+    }
+    write!(out, "{}", params.join(", "));
+    writeln!(out, ") {{");
+
+    for arg in &efn.args {
+        let name = &arg.name.cxx;
+        match &arg.ty {
+            Type::Str(_) => {
+                writeln!(out, "  {} {}$;", fat(), name);
+                writeln!(
+                    out,
+                    "  if (!{}$str$from(&{1}$, {1} ? {1} : (char const *)1, {1}_len)) {{",
+                    CXXBRIDGE, name,
+                );
+                writeln!(out, "    abort();");
+                writeln!(out, "  }}");
+            }
+            Type::SliceRef(slice) => {
+                writeln!(out, "  {} {}$;", fat(), name);
+                // Rust needs a dangling but aligned pointer for an empty slice.
+                write!(
+                    out,
+                    "  {}$slice$new(&{1}$, {1} ? (void const *){1} : ",
+                    CXXBRIDGE, name
+                );
+                write!(out, "(void const *)(uintptr_t){}(", alignof());
+                write_type(out, &slice.inner);
+                writeln!(out, "), {}_len);", name);
+            }
+            _ => {}
+        }
+    }
+
+    write!(out, "  ");
+    if indirect_return {
+        write_type_space(out, efn.ret.as_ref().unwrap());
+        writeln!(out, "return$;");
+        write!(out, "  ");
+    } else {
+        match &efn.ret {
+            Some(Type::Str(_)) | Some(Type::SliceRef(_)) => {
+                write!(out, "{} return$ = ", fat())
+            }
+            Some(_) => write!(out, "return "),
+            None => {}
+        }
+    }
+    write!(out, "{}(", link_name);
+    let mut args = Vec::new();
+    if efn.receiver.is_some() {
+        args.push("self".to_owned());
+    }
+    for arg in &efn.args {
+        let name = &arg.name.cxx;
+        args.push(match &arg.ty {
+            Type::Str(_) | Type::SliceRef(_) => format!("{}$", name),
+            ty if out.types.needs_indirect_abi(ty) => format!("&{}", name),
+            _ => name.to_string(),
+        });
+    }
+    if indirect_return {
+        args.push("&return$".to_owned());
+    }
+    writeln!(out, "{});", args.join(", "));
+    match &efn.ret {
+        Some(Type::Str(_)) => {
+            writeln!(out, "  *return_len = {}$str$len(&return$);", CXXBRIDGE);
+            writeln!(out, "  return {}$str$ptr(&return$);", CXXBRIDGE);
+        }
+        Some(Type::SliceRef(slice)) => {
+            writeln!(out, "  *return_len = {}$slice$len(&return$);", CXXBRIDGE);
+            let ty = pointer_space(out.types, &slice.inner, slice.mutable);
+            writeln!(out, "  return ({}){}$slice$ptr(&return$);", ty, CXXBRIDGE);
+        }
+        _ if indirect_return => writeln!(out, "  return return$;"),
+        _ => {}
+    }
+    writeln!(out, "}}");
+    out.end_cfg(&efn.cfg);
+}
+
+fn write_extern_decl(
+    out: &mut OutFile,
+    link_name: &Symbol,
+    sig: &Signature,
+    indirect_return: bool,
+) {
+    match &sig.ret {
+        Some(Type::Str(_)) | Some(Type::SliceRef(_)) => write!(out, "{} ", fat()),
+        Some(_) if indirect_return => write!(out, "void "),
+        Some(ret) => write_type_space(out, ret),
+        None => write!(out, "void "),
+    }
+    write!(out, "{}(", link_name);
+    let mut params = Vec::new();
+    if let Some(receiver) = &sig.receiver {
+        let self_type = c_name(out.types.resolve(&receiver.ty).name);
+        let constness = if receiver.mutable { "" } else { " const" };
+        params.push(format!("{}{} *self", self_type, constness));
+    }
+    for arg in &sig.args {
+        let mut param = match &arg.ty {
+            Type::Str(_) | Type::SliceRef(_) => format!("{} ", fat()),
+            ty => type_space(out.types, ty),
+        };
+        if out.types.needs_indirect_abi(&arg.ty) {
+            param += "*";
+        }
+        param += &arg.name.cxx.to_string();
+        params.push(param);
+    }
+    if indirect_return {
+        let mut param = type_space(out.types, sig.ret.as_ref().unwrap());
+        param += "*return$";
+        params.push(param);
+    }
+    if params.is_empty() {
+        write!(out, "void");
+    }
+    writeln!(out, "{});", params.join(", "));
+}
+
+fn write_box_free(out: &mut OutFile, ident: &Ident) {
+    let resolve = out.types.resolve(ident);
+    let name = c_name(resolve.name);
+    writeln!(
+        out,
+        "void {}$box${}$drop({} **ptr);",
+        CXXBRIDGE,
+        resolve.name.to_symbol(),
+        name,
+    );
+    writeln!(out, "static inline void {}_free({} *ptr) {{", name, name);
+    writeln!(
+        out,
+        "  {}$box${}$drop(&ptr);",
+        CXXBRIDGE,
+        resolve.name.to_symbol(),
+    );
+    writeln!(out, "}}");
+}
+
+fn write_type(out: &mut OutFile, ty: &Type) {
+    let ty = type_space(out.types, ty);
+    write!(out, "{}", ty.trim_end());
+}
+
+fn write_type_space(out: &mut OutFile, ty: &Type) {
+    let ty = type_space(out.types, ty);
+    write!(out, "{}", ty);
+}
+
+// The C spelling of a type, followed by a space unless it ends in `*`.
+fn type_space(types: &Types, ty: &Type) -> String {
+    match ty {
+        Type::Ident(ident) => {
+            let mut ty = match Atom::from(&ident.rust) {
+                Some(atom) => atom_name(atom).to_owned(),
+                None => c_name(types.resolve(ident).name),
+            };
+            ty.push(' ');
+            ty
+        }
+        Type::Ref(ty) => pointer_space(types, &ty.inner, ty.mutable),
+        Type::Ptr(ty) => pointer_space(types, &ty.inner, ty.mutable),
+        Type::RustBox(ty) => pointer_space(types, &ty.inner, true),
+        _ => unreachable!(),
+    }
+}
+
+// Also how a slice of the inner type is passed to and returned from C.
+fn pointer_space(types: &Types, inner: &Type, mutable: bool) -> String {
+    let mut ty = type_space(types, inner);
+    if !mutable {
+        ty += "const ";
+    }
+    ty.push('*');
+    ty
+}
+
+fn write_atom(out: &mut OutFile, atom: Atom) {
+    write!(out, "{}", atom_name(atom));
+}
+
+fn atom_name(atom: Atom) -> &'static str {
+    match atom {
+        Bool => "bool",
+        Char => "char",
+        U8 => "uint8_t",
+        U16 => "uint16_t",
+        U32 => "uint32_t",
+        U64 => "uint64_t",
+        Usize => "size_t",
+        I8 => "int8_t",
+        I16 => "int16_t",
+        I32 => "int32_t",
+        I64 => "int64_t",
+        Isize => "intptr_t",
+        F32 => "float",
+        F64 => "double",
+        CxxString | RustString => unreachable!(),
+    }
+}
@@ -1,10 +1,14 @@
 use crate::gen::{CfgEvaluator, CfgResult};
 use crate::syntax::cfg::CfgExpr;
+use crate::syntax::instantiate::ImplKey;
+use crate::syntax::map::UnorderedMap as Map;
 use crate::syntax::report::Errors;
-use crate::syntax::Api;
+use crate::syntax::visit::{self, Visit};
+use crate::syntax::{Api, Type, Types};
 use quote::quote;
 use std::collections::BTreeSet as Set;
-use syn::Error;
+use std::mem;
+use syn::{Error, Ident, LitStr};
 
 pub(super) struct UnsupportedCfgEvaluator;
 
@@ -37,6 +41,243 @@ pub(super) fn strip(
     }
 }
 
+// Counterpart of `strip` for Opt::preserve_cfg. Whatever part of each cfg the
+// evaluator is able to decide gets decided, and the undetermined remainder is
+// left behind in place of the original cfg, to be written out as a
+// preprocessor conditional.
+pub(super) fn preserve(cfg_evaluator: &dyn CfgEvaluator, apis: &mut Vec<Api>) {
+    retain_simplified(cfg_evaluator, apis, Api::cfg_mut);
+    for api in apis {
+        match api {
+            Api::Struct(strct) => {
+                retain_simplified(cfg_evaluator, &mut strct.fields, |field| &mut field.cfg);
+            }
+            Api::Enum(enm) => {
+                retain_simplified(cfg_evaluator, &mut enm.variants, |variant| &mut variant.cfg);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn retain_simplified<T>(
+    cfg_evaluator: &dyn CfgEvaluator,
+    vec: &mut Vec<T>,
+    cfg: fn(&mut T) -> &mut CfgExpr,
+) {
+    *vec = mem::take(vec)
+        .into_iter()
+        .filter_map(|mut elem| {
+            let expr = cfg(&mut elem);
+            match simplify(cfg_evaluator, expr) {
+                Simplified::Known(false) => None,
+                Simplified::Known(true) => {
+                    *expr = CfgExpr::Unconditional;
+                    Some(elem)
+                }
+                Simplified::Residual(residual) => {
+                    *expr = residual;
+                    Some(elem)
+                }
+            }
+        })
+        .collect();
+}
+
+enum Simplified {
+    Known(bool),
+    Residual(CfgExpr),
+}
+
+fn simplify(cfg_evaluator: &dyn CfgEvaluator, expr: &CfgExpr) -> Simplified {
+    match expr {
+        CfgExpr::Unconditional => Simplified::Known(true),
+        CfgExpr::Eq(ident, string) => {
+            let key = ident.to_string();
+            let value = string.as_ref().map(|string| string.value());
+            match cfg_evaluator.eval(&key, value.as_deref()) {
+                CfgResult::True => Simplified::Known(true),
+                CfgResult::False => Simplified::Known(false),
+                CfgResult::Undetermined { .. } => Simplified::Residual(expr.clone()),
+            }
+        }
+        CfgExpr::All(list) | CfgExpr::Any(list) => {
+            let short_circuit = matches!(expr, CfgExpr::Any(_));
+            let mut residuals = Vec::new();
+            for subexpr in list {
+                match simplify(cfg_evaluator, subexpr) {
+                    Simplified::Known(value) if value == short_circuit => {
+                        return Simplified::Known(value);
+                    }
+                    Simplified::Known(_) => {}
+                    Simplified::Residual(residual) => residuals.push(residual),
+                }
+            }
+            match residuals.len() {
+                0 => Simplified::Known(!short_circuit),
+                1 => Simplified::Residual(residuals.pop().unwrap()),
+                _ if short_circuit => Simplified::Residual(CfgExpr::Any(residuals)),
+                _ => Simplified::Residual(CfgExpr::All(residuals)),
+            }
+        }
+        CfgExpr::Not(subexpr) => match simplify(cfg_evaluator, subexpr) {
+            Simplified::Known(value) => Simplified::Known(!value),
+            Simplified::Residual(residual) => {
+                Simplified::Residual(CfgExpr::Not(Box::new(residual)))
+            }
+        },
+    }
+}
+
+// Renders a preserved cfg as the condition of a preprocessor #if. Every cfg
+// option is represented by a macro that the C++ build is expected to define
+// for the configurations in which that option holds:
+//
+//     #[cfg(name)]                CXXBRIDGE_CFG_name
+//     #[cfg(name = "value")]      CXXBRIDGE_CFG_name_eq_value
+//
+// where within the name and the value every underscore is doubled and every
+// other byte which is not valid in a C++ identifier is written as _x followed
+// by two hex digits. The escapes and the _eq_ separator each begin with a
+// single underscore followed by a distinct character, so no two cfgs share a
+// macro. For example feature = "a-b", feature = "a_b" and feature_a_b become
+// CXXBRIDGE_CFG_feature_eq_a_x2db, CXXBRIDGE_CFG_feature_eq_a__b and
+// CXXBRIDGE_CFG_feature__a__b.
+pub(super) fn write_condition(out: &mut String, expr: &CfgExpr) {
+    match expr {
+        CfgExpr::Unconditional => out.push('1'),
+        CfgExpr::Eq(ident, string) => {
+            out.push_str("defined(");
+            out.push_str(&macro_name(ident, string.as_ref().map(LitStr::value)));
+            out.push(')');
+        }
+        CfgExpr::All(list) | CfgExpr::Any(list) => {
+            let (op, empty) = match expr {
+                CfgExpr::All(_) => (" && ", '1'),
+                _ => (" || ", '0'),
+            };
+            if list.is_empty() {
+                out.push(empty);
+                return;
+            }
+            out.push('(');
+            for (i, subexpr) in list.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(op);
+                }
+                write_condition(out, subexpr);
+            }
+            out.push(')');
+        }
+        CfgExpr::Not(subexpr) => {
+            out.push('!');
+            write_condition(out, subexpr);
+        }
+    }
+}
+
+fn macro_name(ident: &Ident, value: Option<String>) -> String {
+    let mut name = String::from("CXXBRIDGE_CFG_");
+    escape(&mut name, &ident.to_string());
+    if let Some(value) = value {
+        name.push_str("_eq_");
+        escape(&mut name, &value);
+    }
+    name
+}
+
+fn escape(out: &mut String, component: &str) {
+    for byte in component.bytes() {
+        if byte.is_ascii_alphanumeric() {
+            out.push(char::from(byte));
+        } else if byte == b'_' {
+            out.push_str("__");
+        } else {
+            out.push_str(&format!("_x{:02x}", byte));
+        }
+    }
+}
+
+// Under Opt::preserve_cfg, the C++ half of a generic instantiation such as
+// rust::Vec<T> needs to exist in exactly those configurations in which the
+// Rust half does, which is whenever at least one of the items mentioning it
+// exists. Instantiations absent from the returned map are unconditional.
+pub(super) fn impl_cfgs<'a>(apis: &'a [Api], types: &Types<'a>) -> Map<ImplKey<'a>, CfgExpr> {
+    let mut contributions = Map::new();
+    let mut contribute = |ty: &'a Type, cfg: &CfgExpr| {
+        let mut collect = CollectImplKeys(Vec::new());
+        collect.visit_type(ty);
+        for impl_key in collect.0 {
+            contributions
+                .entry(impl_key)
+                .or_insert_with(Vec::new)
+                .push(cfg.clone());
+        }
+    };
+
+    for api in apis {
+        match api {
+            Api::Struct(strct) => {
+                for field in &strct.fields {
+                    contribute(&field.ty, &conjunction(&strct.cfg, &field.cfg));
+                }
+            }
+            Api::CxxFunction(efn) | Api::RustFunction(efn) => {
+                for arg in &efn.args {
+                    contribute(&arg.ty, &efn.cfg);
+                }
+                if let Some(ret) = &efn.ret {
+                    contribute(ret, &efn.cfg);
+                }
+                if let Some(error) = &efn.error {
+                    contribute(error, &efn.cfg);
+                }
+            }
+            Api::Impl(imp) => contribute(&imp.ty, &imp.cfg),
+            _ => {}
+        }
+    }
+
+    // Mirrors Types::collect, where a UniquePtr of a Rust type pulls in the
+    // corresponding Box instantiation.
+    let mut impl_cfgs = Map::new();
+    for impl_key in types.impls.keys() {
+        let mut cfgs = contributions.get(impl_key).cloned().unwrap_or_default();
+        if let ImplKey::RustBox(ident) = impl_key {
+            if let Some(more) = contributions.get(&ImplKey::UniquePtr(*ident)) {
+                cfgs.extend(more.iter().cloned());
+            }
+        }
+        if cfgs.is_empty() || cfgs.iter().any(|cfg| matches!(cfg, CfgExpr::Unconditional)) {
+            continue;
+        }
+        let cfg = if cfgs.len() == 1 {
+            cfgs.pop().unwrap()
+        } else {
+            CfgExpr::Any(cfgs)
+        };
+        impl_cfgs.insert(*impl_key, cfg);
+    }
+    impl_cfgs
+}
+
+struct CollectImplKeys<'a>(Vec<ImplKey<'a>>);
+
+impl<'a> Visit<'a> for CollectImplKeys<'a> {
+    fn visit_type(&mut self, ty: &'a Type) {
+        self.0.extend(ty.impl_key());
+        visit::visit_type(self, ty);
+    }
+}
+
+fn conjunction(outer: &CfgExpr, inner: &CfgExpr) -> CfgExpr {
+    let mut cfg = outer.clone();
+    if !matches!(inner, CfgExpr::Unconditional) {
+        cfg.merge(inner.clone());
+    }
+    cfg
+}
+
 pub(super) fn eval(
     cx: &mut Errors,
     cfg_errors: &mut Set<String>,
@@ -109,7 +350,7 @@ fn try_eval(cfg_evaluator: &dyn CfgEvaluator, expr: &CfgExpr) -> Result<bool, Ve
 }
 
 impl Api {
-    fn cfg(&self) -> &CfgExpr {
+    pub(super) fn cfg(&self) -> &CfgExpr {
         match self {
             Api::Include(include) => &include.cfg,
             Api::Struct(strct) => &strct.cfg,
@@ -118,6 +359,20 @@ impl Api {
             Api::CxxFunction(efn) | Api::RustFunction(efn) => &efn.cfg,
             Api::TypeAlias(alias) => &alias.cfg,
             Api::Impl(imp) => &imp.cfg,
+            Api::Const(konst) | Api::CxxConst(konst) => &konst.cfg,
+        }
+    }
+
+    pub(super) fn cfg_mut(&mut self) -> &mut CfgExpr {
+        match self {
+            Api::Include(include) => &mut include.cfg,
+            Api::Struct(strct) => &mut strct.cfg,
+            Api::Enum(enm) => &mut enm.cfg,
+            Api::CxxType(ety) | Api::RustType(ety) => &mut ety.cfg,
+            Api::CxxFunction(efn) | Api::RustFunction(efn) => &mut efn.cfg,
+            Api::TypeAlias(alias) => &mut alias.cfg,
+            Api::Impl(imp) => &mut imp.cfg,
+            Api::Const(konst) | Api::CxxConst(konst) => &mut konst.cfg,
         }
     }
 }
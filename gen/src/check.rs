@@ -1,6 +1,6 @@
 use crate::gen::Opt;
 use crate::syntax::report::Errors;
-use crate::syntax::{error, Api};
+use crate::syntax::{error, Api, Type};
 use quote::{quote, quote_spanned};
 use std::path::{Component, Path};
 
@@ -10,6 +10,9 @@ pub(super) fn precheck(cx: &mut Errors, apis: &[Api], opt: &Opt) {
     if !opt.allow_dot_includes {
         check_dot_includes(cx, apis);
     }
+    if !opt.exceptions {
+        check_no_exceptions(cx, apis);
+    }
 }
 
 fn check_dot_includes(cx: &mut Errors, apis: &[Api]) {
@@ -25,3 +28,26 @@ fn check_dot_includes(cx: &mut Errors, apis: &[Api]) {
         }
     }
 }
+
+fn check_no_exceptions(cx: &mut Errors, apis: &[Api]) {
+    for api in apis {
+        if let Api::CxxFunction(efn) | Api::RustFunction(efn) = api {
+            if efn.catch_unwind {
+                cx.error(efn, "#[catch_unwind] requires C++ exceptions");
+            }
+            for arg in &efn.args {
+                let throws = match &arg.ty {
+                    Type::Fn(f) => f.throws,
+                    Type::DynFn(f) => f.sig.throws,
+                    _ => false,
+                };
+                if throws {
+                    cx.error(
+                        &arg.ty,
+                        "function pointer or closure returning Result requires C++ exceptions",
+                    );
+                }
+            }
+        }
+    }
+}
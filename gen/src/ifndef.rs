@@ -1,11 +1,17 @@
 use crate::gen::include::HEADER;
 use crate::gen::out::Content;
+use crate::syntax::mangle;
 
-pub(super) fn write(out: &mut Content, needed: bool, guard: &str) {
+// Copies the sections of cxx.h guarded by CXXBRIDGE1_{name}, renaming the guard
+// to agree with the configured symbol prefix.
+pub(super) fn write(out: &mut Content, needed: bool, name: &str) {
+    let guard = format!("CXXBRIDGE1_{}", name);
     let ifndef = format!("#ifndef {}", guard);
     let define = format!("#define {}", guard);
     let endif = format!("#endif // {}", guard);
 
+    let renamed = format!("{}_{}", mangle::preprocessor_prefix(), name);
+
     let mut offset = 0;
     loop {
         let begin = find_line(offset, &ifndef);
@@ -16,8 +22,8 @@ pub(super) fn write(out: &mut Content, needed: bool, guard: &str) {
             }
             out.next_section();
             if offset == 0 {
-                writeln!(out, "{}", ifndef);
-                writeln!(out, "{}", define);
+                writeln!(out, "#ifndef {}", renamed);
+                writeln!(out, "#define {}", renamed);
             }
             for line in HEADER[begin + ifndef.len()..end].trim().lines() {
                 if line != define && !line.trim_start().starts_with("//") {
@@ -28,7 +34,7 @@ pub(super) fn write(out: &mut Content, needed: bool, guard: &str) {
         } else if offset == 0 {
             panic!("not found in cxx.h header: {}", guard)
         } else {
-            writeln!(out, "{}", endif);
+            writeln!(out, "#endif // {}", renamed);
             return;
         }
     }
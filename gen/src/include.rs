@@ -1,10 +1,33 @@
 use crate::gen::out::{Content, OutFile};
+use crate::syntax::mangle::{self, CXXBRIDGE};
 use crate::syntax::{self, IncludeKind};
+use std::borrow::Cow;
 use std::ops::{Deref, DerefMut};
 
 /// The complete contents of the "rust/cxx.h" header.
 pub static HEADER: &str = include_str!("include/cxx.h");
 
+// HEADER with its symbols, inline namespace and preprocessor guards renamed to
+// the symbol prefix configured through cxxbridge-flags, to agree with the
+// generated code.
+#[allow(dead_code)] // not used by cxx_gen
+pub(crate) fn header() -> Cow<'static, str> {
+    if CXXBRIDGE == "cxxbridge1" {
+        Cow::Borrowed(HEADER)
+    } else {
+        let renamed = HEADER
+            .replace("cxxbridge1", CXXBRIDGE)
+            .replace("CXXBRIDGE1", &mangle::preprocessor_prefix());
+        Cow::Owned(renamed)
+    }
+}
+
+/// The complete contents of the C++20 module interface unit for the `cxx`
+/// module, which exports the `rust` runtime types of "rust/cxx.h" to the
+/// module interfaces generated for bridges.
+#[allow(dead_code)] // only used by cxx_gen and cxxbridge, not cxx-build
+pub static MODULE_INTERFACE: &str = include_str!("include/cxx.cppm");
+
 /// A header to #include.
 ///
 /// The cxxbridge tool does not parse or even require the given paths to exist;
@@ -33,8 +56,10 @@ pub struct Includes<'a> {
     pub iterator: bool,
     pub memory: bool,
     pub new: bool,
+    pub sstream: bool,
     pub stdexcept: bool,
     pub string: bool,
+    pub tuple: bool,
     pub type_traits: bool,
     pub utility: bool,
     pub vector: bool,
@@ -94,8 +119,10 @@ pub(super) fn write(out: &mut OutFile) {
         iterator,
         memory,
         new,
+        sstream,
         stdexcept,
         string,
+        tuple,
         type_traits,
         utility,
         vector,
@@ -140,12 +167,18 @@ pub(super) fn write(out: &mut OutFile) {
     if new && !cxx_header {
         writeln!(out, "#include <new>");
     }
+    if sstream {
+        writeln!(out, "#include <sstream>");
+    }
     if stdexcept && !cxx_header {
         writeln!(out, "#include <stdexcept>");
     }
     if string && !cxx_header {
         writeln!(out, "#include <string>");
     }
+    if tuple {
+        writeln!(out, "#include <tuple>");
+    }
     if type_traits && !cxx_header {
         writeln!(out, "#include <type_traits>");
     }
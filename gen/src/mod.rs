@@ -3,6 +3,7 @@
 
 mod block;
 mod builtin;
+mod c;
 mod cfg;
 mod check;
 pub(super) mod error;
@@ -54,14 +55,73 @@ pub struct Opt {
     /// Rust code from one shared object or executable depends on these C++
     /// functions in another.
     pub cxx_impl_annotations: Option<String>,
+    /// Whether the generated C++ code may use exceptions. Defaults to true.
+    /// For C++ built with `-fno-exceptions`, set this to false and functions
+    /// returning `Result` will use `rust::Expected<T>` as their C++ return
+    /// type in both directions instead of throwing and catching `rust::Error`.
+    pub exceptions: bool,
+    /// Whether to carry `#[cfg]` attributes that cannot be evaluated at
+    /// generation time over into the generated C++ as preprocessor
+    /// conditionals, rather than failing. Defaults to false. This allows a
+    /// single generated header to serve every configuration of the Rust
+    /// crate; the C++ build then defines `CXXBRIDGE_CFG_name` for each enabled
+    /// `#[cfg(name)]` and `CXXBRIDGE_CFG_name_eq_value` for each enabled
+    /// `#[cfg(name = "value")]`, with every underscore of the name and value
+    /// doubled and every other byte that is not valid in an identifier written
+    /// as `_x` and two hex digits.
+    pub preserve_cfg: bool,
+    /// Version of the C++ standard that the generated code is going to be
+    /// compiled as. Defaults to C++11. Currently this only decides whether
+    /// shared constants are emitted as `inline constexpr`, which takes C++17;
+    /// everything else is C++11 regardless.
+    pub cxx_standard: CxxStandard,
+    /// C++20 module for which to generate a module interface unit into
+    /// `GeneratedCode::module_interface`. Defaults to None, meaning no module
+    /// interface is generated.
+    pub module: Option<Module>,
+    /// Whether to also generate a C header into `GeneratedCode::c_header`,
+    /// making the bridge's extern "Rust" functions callable from C. Defaults to
+    /// false. Every extern "Rust" function then needs to be expressible in C,
+    /// otherwise generation fails.
+    pub c_header: bool,
 
     pub(super) gen_header: bool,
+    pub(super) gen_fwd_header: bool,
     pub(super) gen_implementation: bool,
     pub(super) allow_dot_includes: bool,
     pub(super) cfg_evaluator: Box<dyn CfgEvaluator>,
     pub(super) doxygen: bool,
 }
 
+/// A C++20 module exporting the contents of a bridge.
+///
+/// The module interface unit exports the shared structs and enums, opaque Rust
+/// types and free Rust functions of the bridge, and re-exports the `cxx` module
+/// of `rust` runtime types whose interface unit is `MODULE_INTERFACE`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Module {
+    /// Name of the module, such as `crate.bridge`.
+    pub name: String,
+    /// The header generated for the same bridge, which the interface unit
+    /// #includes in its global module fragment to obtain the declarations it
+    /// exports.
+    pub header: Include,
+}
+
+/// Version of the C++ standard.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[non_exhaustive]
+pub enum CxxStandard {
+    /// C++11
+    Cxx11,
+    /// C++14
+    Cxx14,
+    /// C++17
+    Cxx17,
+    /// C++20
+    Cxx20,
+}
+
 pub(super) trait CfgEvaluator {
     fn eval(&self, name: &str, value: Option<&str>) -> CfgResult;
 }
@@ -77,8 +137,20 @@ pub(super) enum CfgResult {
 pub struct GeneratedCode {
     /// The bytes of a C++ header file.
     pub header: Vec<u8>,
+    /// The bytes of a C++ header file containing only forward declarations of
+    /// the shared structs, shared enums and opaque Rust types, for use in
+    /// headers that don't need their definitions.
+    pub fwd_header: Vec<u8>,
     /// The bytes of a C++ implementation file (e.g. .cc, cpp etc.)
     pub implementation: Vec<u8>,
+    /// The bytes of a C++20 module interface unit (e.g. .cppm, .ixx), if
+    /// `Opt::module` was set.
+    #[allow(dead_code)] // only used by cxx_gen and cxxbridge, not cxx-build
+    pub module_interface: Vec<u8>,
+    /// The bytes of a C header declaring the extern "Rust" functions, if
+    /// `Opt::c_header` was set.
+    #[allow(dead_code)] // only used by cxx_gen and cxxbridge, not cxx-build
+    pub c_header: Vec<u8>,
 }
 
 impl Default for Opt {
@@ -86,7 +158,13 @@ impl Default for Opt {
         Opt {
             include: Vec::new(),
             cxx_impl_annotations: None,
+            exceptions: true,
+            preserve_cfg: false,
+            cxx_standard: CxxStandard::Cxx11,
+            module: None,
+            c_header: false,
             gen_header: true,
+            gen_fwd_header: true,
             gen_implementation: true,
             allow_dot_includes: true,
             cfg_evaluator: Box::new(UnsupportedCfgEvaluator),
@@ -148,19 +226,26 @@ pub(super) fn generate(syntax: File, opt: &Opt) -> Result<GeneratedCode> {
                 ..Default::default()
             },
         );
-        if cfg::eval(errors, cfg_errors, opt.cfg_evaluator.as_ref(), &cfg) {
+        if opt.preserve_cfg || cfg::eval(errors, cfg_errors, opt.cfg_evaluator.as_ref(), &cfg) {
             let ref namespace = bridge.namespace;
             let trusted = bridge.unsafety.is_some();
-            apis.extend(syntax::parse_items(
-                errors,
-                bridge.content,
-                trusted,
-                namespace,
-            ));
+            let mut items = syntax::parse_items(errors, bridge.content, trusted, namespace);
+            if opt.preserve_cfg && !matches!(cfg, CfgExpr::Unconditional) {
+                // The bridge module's own cfg is pushed down onto every item
+                // in it, to be preserved or evaluated along with theirs.
+                for api in &mut items {
+                    api.cfg_mut().merge(cfg.clone());
+                }
+            }
+            apis.extend(items);
         }
     }
 
-    cfg::strip(errors, cfg_errors, opt.cfg_evaluator.as_ref(), apis);
+    if opt.preserve_cfg {
+        cfg::preserve(opt.cfg_evaluator.as_ref(), apis);
+    } else {
+        cfg::strip(errors, cfg_errors, opt.cfg_evaluator.as_ref(), apis);
+    }
     errors.propagate()?;
 
     let ref types = Types::collect(errors, apis);
@@ -171,18 +256,36 @@ pub(super) fn generate(syntax: File, opt: &Opt) -> Result<GeneratedCode> {
     check::typecheck(errors, apis, types, generator);
     errors.propagate()?;
 
+    if opt.c_header {
+        c::check(errors, apis, types);
+        errors.propagate()?;
+    }
+
     // Some callers may wish to generate both header and implementation from the
     // same token stream to avoid parsing twice. Others only need to generate
     // one or the other.
-    let (mut header, mut implementation) = Default::default();
+    let (mut header, mut fwd_header, mut implementation) = Default::default();
+    let (mut module_interface, mut c_header) = Default::default();
     if opt.gen_header {
         header = write::gen(apis, types, opt, true);
     }
+    if opt.gen_fwd_header {
+        fwd_header = write::gen_fwd_header(apis, types, opt);
+    }
     if opt.gen_implementation {
         implementation = write::gen(apis, types, opt, false);
     }
+    if let Some(module) = &opt.module {
+        module_interface = write::gen_module_interface(apis, types, opt, module);
+    }
+    if opt.c_header {
+        c_header = c::gen(apis, types, opt);
+    }
     Ok(GeneratedCode {
         header,
+        fwd_header,
         implementation,
+        module_interface,
+        c_header,
     })
 }
@@ -2,6 +2,7 @@ use crate::gen::block::Block;
 use crate::gen::builtin::Builtins;
 use crate::gen::include::Includes;
 use crate::gen::Opt;
+use crate::syntax::cfg::CfgExpr;
 use crate::syntax::namespace::Namespace;
 use crate::syntax::Types;
 use std::cell::RefCell;
@@ -60,6 +61,21 @@ impl<'a> OutFile<'a> {
         self.content.get_mut().set_namespace(namespace);
     }
 
+    // Enclose what follows in a preprocessor conditional if the cfg of the
+    // item being written was preserved. The namespace must not change until
+    // the matching end_cfg.
+    pub fn begin_cfg(&mut self, cfg: &'a CfgExpr) {
+        if self.opt.preserve_cfg && !matches!(cfg, CfgExpr::Unconditional) {
+            self.begin_block(Block::Cfg(cfg));
+        }
+    }
+
+    pub fn end_cfg(&mut self, cfg: &'a CfgExpr) {
+        if self.opt.preserve_cfg && !matches!(cfg, CfgExpr::Unconditional) {
+            self.end_block(Block::Cfg(cfg));
+        }
+    }
+
     pub fn write_fmt(&self, args: Arguments) {
         let content = &mut *self.content.borrow_mut();
         Write::write_fmt(content, args).unwrap();
@@ -170,8 +186,16 @@ impl<'a> Content<'a> {
     }
 
     fn flush_blocks(&mut self) {
-        self.section_pending = !self.bytes.is_empty();
         let mut read = self.blocks.len() - self.blocks_pending;
+        // Preprocessor conditionals don't get set apart by blank lines the way
+        // namespaces do, so that a conditional struct field or forward
+        // declaration stays together with its neighbors.
+        let only_cfg = self.blocks[read..]
+            .iter()
+            .all(|boundary| matches!(boundary.block(), Block::Cfg(_)));
+        if !only_cfg {
+            self.section_pending = !self.bytes.is_empty();
+        }
         let mut write = read;
 
         while read < self.blocks.len() {
@@ -190,7 +214,9 @@ impl<'a> Content<'a> {
                     let begin_block = self.blocks[write];
                     assert_eq!(begin_block, BlockBoundary::Begin(end_block));
                     Block::write_end(end_block, &mut self.bytes);
-                    self.section_pending = true;
+                    if !matches!(end_block, Block::Cfg(_)) {
+                        self.section_pending = true;
+                    }
                 }
             }
             read += 1;
@@ -201,6 +227,12 @@ impl<'a> Content<'a> {
 }
 
 impl<'a> BlockBoundary<'a> {
+    fn block(self) -> Block<'a> {
+        match self {
+            BlockBoundary::Begin(block) | BlockBoundary::End(block) => block,
+        }
+    }
+
     fn rev(self) -> BlockBoundary<'a> {
         match self {
             BlockBoundary::Begin(block) => BlockBoundary::End(block),
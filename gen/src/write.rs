@@ -1,20 +1,28 @@
 use crate::gen::block::Block;
 use crate::gen::nested::NamespaceEntries;
 use crate::gen::out::OutFile;
-use crate::gen::{builtin, include, Opt};
+use crate::gen::{builtin, cfg, include, CxxStandard, Module, Opt};
 use crate::syntax::atom::Atom::{self, *};
+use crate::syntax::cfg::CfgExpr;
 use crate::syntax::instantiate::{ImplKey, NamedImplKey};
+use crate::syntax::mangle::CXXBRIDGE;
 use crate::syntax::map::UnorderedMap as Map;
 use crate::syntax::set::UnorderedSet;
 use crate::syntax::symbol::{self, Symbol};
 use crate::syntax::trivial::{self, TrivialReason};
 use crate::syntax::{
-    derive, mangle, Api, Doc, Enum, EnumRepr, ExternFn, ExternType, Pair, Signature, Struct, Trait,
-    Type, TypeAlias, Types, Var,
+    derive, mangle, Api, Const, Doc, Enum, EnumRepr, ExternFn, ExternType, IncludeKind, Pair,
+    Signature, Struct, Trait, Tuple, Type, TypeAlias, Types, Var,
 };
 use proc_macro2::Ident;
+use syn::{Expr, Lit, UnOp};
 
 pub(super) fn gen(apis: &[Api], types: &Types, opt: &Opt, header: bool) -> Vec<u8> {
+    let impl_cfgs = if opt.preserve_cfg {
+        cfg::impl_cfgs(apis, types)
+    } else {
+        Map::new()
+    };
     let mut out_file = OutFile::new(header, opt, types);
     let out = &mut out_file;
 
@@ -24,7 +32,7 @@ pub(super) fn gen(apis: &[Api], types: &Types, opt: &Opt, header: bool) -> Vec<u
     write_forward_declarations(out, apis);
     write_data_structures(out, apis);
     write_functions(out, apis);
-    write_generic_instantiations(out);
+    write_generic_instantiations(out, &impl_cfgs);
 
     builtin::write(out);
     include::write(out);
@@ -32,22 +40,138 @@ pub(super) fn gen(apis: &[Api], types: &Types, opt: &Opt, header: bool) -> Vec<u
     out_file.content()
 }
 
-fn write_forward_declarations(out: &mut OutFile, apis: &[Api]) {
+// The *.rs.fwd.h header, holding nothing but forward declarations of the
+// shared structs, shared enums and opaque Rust types. It's for C++ headers which
+// only deal in pointers and references to those, and would rather not pay for
+// parsing the full generated header and the runtime inlined into it. Opaque C++
+// types are left out since they're the user's to declare.
+pub(super) fn gen_fwd_header(apis: &[Api], types: &Types, opt: &Opt) -> Vec<u8> {
+    let mut out_file = OutFile::new(true, opt, types);
+    let out = &mut out_file;
+
+    let needs_forward_declaration = |api: &&Api| match api {
+        Api::Struct(_) | Api::RustType(_) => true,
+        Api::Enum(enm) => !out.types.cxx.contains(&enm.name.rust),
+        _ => false,
+    };
+    let apis: Vec<&Api> = apis.iter().filter(needs_forward_declaration).collect();
+
+    for api in &apis {
+        if let Api::Enum(enm) = api {
+            match &enm.repr {
+                #[cfg(feature = "experimental-enum-variants-from-header")]
+                EnumRepr::Foreign { .. } => {}
+                EnumRepr::Native { atom: Usize, .. } => out.include.cstddef = true,
+                EnumRepr::Native { atom: Isize, .. } => out.builtin.rust_isize = true,
+                EnumRepr::Native { .. } => out.include.cstdint = true,
+            }
+        }
+    }
+
+    write_namespaced_declarations(out, apis);
+
+    builtin::write(out);
+    include::write(out);
+
+    out_file.content()
+}
+
+// The module interface unit, for C++ code that imports the bridge as a C++20
+// module rather than including its header. The declarations still come from
+// the header, which goes in the global module fragment, and the interface
+// exports them by using-declarations alongside the `cxx` module's.
+pub(super) fn gen_module_interface(
+    apis: &[Api],
+    types: &Types,
+    opt: &Opt,
+    module: &Module,
+) -> Vec<u8> {
+    let mut out_file = OutFile::new(false, opt, types);
+    let out = &mut out_file;
+
+    writeln!(out, "module;");
+    match module.header.kind {
+        IncludeKind::Quoted => {
+            writeln!(out, "#include \"{}\"", module.header.path.escape_default());
+        }
+        IncludeKind::Bracketed => writeln!(out, "#include <{}>", module.header.path),
+    }
+
+    out.next_section();
+    writeln!(out, "export module {};", module.name);
+    out.next_section();
+    writeln!(out, "export import cxx;");
+
+    let needs_export = |api: &&Api| match api {
+        Api::Struct(strct) => !out.types.cxx.contains(&strct.name.rust),
+        Api::Enum(enm) => !out.types.cxx.contains(&enm.name.rust),
+        Api::RustType(_) => true,
+        Api::RustFunction(efn) => efn.sig.receiver.is_none() && efn.sig.self_type.is_none(),
+        // Before C++17 these are not inline variables, and so have internal
+        // linkage, which rules out exporting them.
+        Api::Const(_) => out.opt.cxx_standard >= CxxStandard::Cxx17,
+        _ => false,
+    };
+    let apis_by_namespace = NamespaceEntries::new(apis.iter().filter(needs_export).collect());
+
+    out.next_section();
+    write(out, &apis_by_namespace, 0);
+
+    fn write<'a>(out: &mut OutFile<'a>, ns_entries: &NamespaceEntries<'a>, indent: usize) {
+        let export = if indent == 0 { "export " } else { "" };
+
+        for api in ns_entries.direct_content() {
+            let name = match api {
+                Api::Struct(strct) => &strct.name,
+                Api::Enum(enm) => &enm.name,
+                Api::RustType(ety) => &ety.name,
+                Api::RustFunction(efn) => &efn.name,
+                Api::Const(konst) => &konst.name,
+                _ => unreachable!(),
+            };
+            out.begin_cfg(api.cfg());
+            write!(out, "{:1$}{2}using ", "", indent, export);
+            for segment in &name.namespace {
+                write!(out, "::{}", segment);
+            }
+            writeln!(out, "::{};", name.cxx);
+            out.end_cfg(api.cfg());
+        }
+
+        for (namespace, nested_ns_entries) in ns_entries.nested_content() {
+            writeln!(
+                out,
+                "{:1$}{2}namespace {3} {{",
+                "", indent, export, namespace
+            );
+            write(out, nested_ns_entries, indent + 2);
+            writeln!(out, "{:1$}}}", "", indent);
+        }
+    }
+
+    out_file.content()
+}
+
+fn write_forward_declarations<'a>(out: &mut OutFile<'a>, apis: &'a [Api]) {
     let needs_forward_declaration = |api: &&Api| match api {
         Api::Struct(_) | Api::CxxType(_) | Api::RustType(_) => true,
         Api::Enum(enm) => !out.types.cxx.contains(&enm.name.rust),
         _ => false,
     };
 
-    let apis_by_namespace =
-        NamespaceEntries::new(apis.iter().filter(needs_forward_declaration).collect());
+    write_namespaced_declarations(out, apis.iter().filter(needs_forward_declaration).collect());
+}
+
+fn write_namespaced_declarations<'a>(out: &mut OutFile<'a>, apis: Vec<&'a Api>) {
+    let apis_by_namespace = NamespaceEntries::new(apis);
 
     write(out, &apis_by_namespace, 0);
 
-    fn write(out: &mut OutFile, ns_entries: &NamespaceEntries, indent: usize) {
+    fn write<'a>(out: &mut OutFile<'a>, ns_entries: &NamespaceEntries<'a>, indent: usize) {
         let apis = ns_entries.direct_content();
 
         for api in apis {
+            out.begin_cfg(api.cfg());
             write!(out, "{:1$}", "", indent);
             match api {
                 Api::Struct(strct) => write_struct_decl(out, &strct.name),
@@ -56,6 +180,7 @@ fn write_forward_declarations(out: &mut OutFile, apis: &[Api]) {
                 Api::RustType(ety) => write_struct_decl(out, &ety.name),
                 _ => unreachable!(),
             }
+            out.end_cfg(api.cfg());
         }
 
         for (namespace, nested_ns_entries) in ns_entries.nested_content() {
@@ -70,15 +195,35 @@ fn write_data_structures<'a>(out: &mut OutFile<'a>, apis: &'a [Api]) {
     let mut methods_for_type = Map::new();
     for api in apis {
         if let Api::CxxFunction(efn) | Api::RustFunction(efn) = api {
-            if let Some(receiver) = &efn.sig.receiver {
+            let self_type = match &efn.sig.receiver {
+                Some(receiver) => Some(&receiver.ty),
+                None => efn.sig.self_type.as_ref().filter(|_| !efn.constructor),
+            };
+            if let Some(self_type) = self_type {
                 methods_for_type
-                    .entry(&receiver.ty.rust)
+                    .entry(&self_type.rust)
                     .or_insert_with(Vec::new)
                     .push(efn);
             }
         }
     }
 
+    // Constants come ahead of the structs, which may refer to them in the
+    // length of an array field.
+    for api in apis {
+        match api {
+            Api::Const(konst) => {
+                out.next_section();
+                write_const(out, konst);
+            }
+            Api::CxxConst(konst) => {
+                out.next_section();
+                check_const(out, konst);
+            }
+            _ => {}
+        }
+    }
+
     let mut structs_written = UnorderedSet::new();
     let mut toposorted_structs = out.types.toposorted_structs.iter();
     for api in apis {
@@ -129,7 +274,9 @@ fn write_data_structures<'a>(out: &mut OutFile<'a>, apis: &'a [Api]) {
     for api in apis {
         if let Api::TypeAlias(ety) = api {
             if let Some(reasons) = out.types.required_trivial.get(&ety.name.rust) {
-                check_trivial_extern_type(out, ety, reasons)
+                out.begin_cfg(&ety.cfg);
+                check_trivial_extern_type(out, ety, reasons);
+                out.end_cfg(&ety.cfg);
             }
         }
     }
@@ -140,7 +287,12 @@ fn write_functions<'a>(out: &mut OutFile<'a>, apis: &'a [Api]) {
         for api in apis {
             match api {
                 Api::Struct(strct) => write_struct_operator_decls(out, strct),
+                Api::CxxType(ety) => {
+                    write_cxx_type_operators(out, ety);
+                    write_cxx_type_bases(out, ety);
+                }
                 Api::RustType(ety) => write_opaque_type_layout_decls(out, ety),
+                Api::TypeAlias(alias) => write_cxx_type_in_place_support(out, alias, apis),
                 Api::CxxFunction(efn) => write_cxx_function_shim(out, efn),
                 Api::RustFunction(efn) => write_rust_function_decl(out, efn),
                 _ => {}
@@ -163,7 +315,7 @@ fn write_functions<'a>(out: &mut OutFile<'a>, apis: &'a [Api]) {
     }
 }
 
-fn write_std_specializations(out: &mut OutFile, apis: &[Api]) {
+fn write_std_specializations<'a>(out: &mut OutFile<'a>, apis: &'a [Api]) {
     out.set_namespace(Default::default());
     out.begin_block(Block::Namespace("std"));
 
@@ -171,6 +323,7 @@ fn write_std_specializations(out: &mut OutFile, apis: &[Api]) {
         if let Api::Struct(strct) = api {
             if derive::contains(&strct.derives, Trait::Hash) {
                 out.next_section();
+                out.begin_cfg(&strct.cfg);
                 out.include.cstddef = true;
                 out.include.functional = true;
                 let qualified = strct.name.to_fully_qualified();
@@ -188,6 +341,7 @@ fn write_std_specializations(out: &mut OutFile, apis: &[Api]) {
                 writeln!(out, "{}(self);", link_name);
                 writeln!(out, "  }}");
                 writeln!(out, "}};");
+                out.end_cfg(&strct.cfg);
             }
         }
     }
@@ -214,20 +368,30 @@ fn pick_includes_and_builtins(out: &mut OutFile, apis: &[Api]) {
                 Some(Bool) | Some(Char) | Some(F32) | Some(F64) | None => {}
             },
             Type::RustBox(_) => out.builtin.rust_box = true,
+            Type::RustArc(_) => out.builtin.rust_arc = true,
             Type::RustVec(_) => out.builtin.rust_vec = true,
-            Type::UniquePtr(_) => out.include.memory = true,
+            Type::UniquePtr(ptr) => {
+                out.include.memory = true;
+                if let Type::Ident(inner) = &ptr.inner {
+                    if out.types.rust.contains(&inner.rust) {
+                        out.builtin.rust_box = true;
+                    }
+                }
+            }
             Type::SharedPtr(_) | Type::WeakPtr(_) => out.include.memory = true,
             Type::Str(_) => out.builtin.rust_str = true,
             Type::CxxVector(_) => out.include.vector = true,
             Type::Fn(_) => out.builtin.rust_fn = true,
+            Type::DynFn(_) => out.builtin.rust_fn_mut = true,
             Type::SliceRef(_) => out.builtin.rust_slice = true,
             Type::Array(_) => out.include.array = true,
+            Type::Tuple(_) => out.include.tuple = true,
             Type::Ref(_) | Type::Void(_) | Type::Ptr(_) => {}
         }
     }
 }
 
-fn write_doc(out: &mut OutFile, indent: &str, doc: &Doc) {
+pub(super) fn write_doc(out: &mut OutFile, indent: &str, doc: &Doc) {
     let mut lines = 0;
     for line in doc.to_string().lines() {
         if out.opt.doxygen {
@@ -246,22 +410,35 @@ fn write_doc(out: &mut OutFile, indent: &str, doc: &Doc) {
     }
 }
 
-fn write_struct<'a>(out: &mut OutFile<'a>, strct: &'a Struct, methods: &[&ExternFn]) {
+fn write_struct<'a>(out: &mut OutFile<'a>, strct: &'a Struct, methods: &[&'a ExternFn]) {
     let operator_eq = derive::contains(&strct.derives, Trait::PartialEq);
     let operator_ord = derive::contains(&strct.derives, Trait::PartialOrd);
 
     out.set_namespace(&strct.name.namespace);
-    let guard = format!("CXXBRIDGE1_STRUCT_{}", strct.name.to_symbol());
+    out.begin_cfg(&strct.cfg);
+    let guard = format!(
+        "{}_STRUCT_{}",
+        mangle::preprocessor_prefix(),
+        strct.name.to_symbol()
+    );
     writeln!(out, "#ifndef {}", guard);
     writeln!(out, "#define {}", guard);
     write_doc(out, "", &strct.doc);
     writeln!(out, "struct {} final {{", strct.name.cxx);
 
     for field in &strct.fields {
+        out.begin_cfg(&field.cfg);
         write_doc(out, "  ", &field.doc);
         write!(out, "  ");
-        write_type_space(out, &field.ty);
+        match &field.ty {
+            Type::Tuple(tuple) => {
+                write_tuple_repr(out, tuple);
+                write!(out, " ");
+            }
+            ty => write_type_space(out, ty),
+        }
         writeln!(out, "{};", field.name.cxx);
+        out.end_cfg(&field.cfg);
     }
 
     out.next_section();
@@ -270,13 +447,18 @@ fn write_struct<'a>(out: &mut OutFile<'a>, strct: &'a Struct, methods: &[&Extern
         if !method.doc.is_empty() {
             out.next_section();
         }
+        out.begin_cfg(&method.cfg);
         write_doc(out, "  ", &method.doc);
         write!(out, "  ");
+        if method.self_type.is_some() {
+            write!(out, "static ");
+        }
         let sig = &method.sig;
         let local_name = method.name.cxx.to_string();
         let indirect_call = false;
         write_rust_function_shim_decl(out, &local_name, sig, indirect_call);
         writeln!(out, ";");
+        out.end_cfg(&method.cfg);
         if !method.doc.is_empty() {
             out.next_section();
         }
@@ -323,6 +505,7 @@ fn write_struct<'a>(out: &mut OutFile<'a>, strct: &'a Struct, methods: &[&Extern
 
     writeln!(out, "}};");
     writeln!(out, "#endif // {}", guard);
+    out.end_cfg(&strct.cfg);
 }
 
 fn write_struct_decl(out: &mut OutFile, ident: &Pair) {
@@ -344,9 +527,14 @@ fn write_struct_using(out: &mut OutFile, ident: &Pair) {
     writeln!(out, "using {} = {};", ident.cxx, ident.to_fully_qualified());
 }
 
-fn write_opaque_type<'a>(out: &mut OutFile<'a>, ety: &'a ExternType, methods: &[&ExternFn]) {
+fn write_opaque_type<'a>(out: &mut OutFile<'a>, ety: &'a ExternType, methods: &[&'a ExternFn]) {
     out.set_namespace(&ety.name.namespace);
-    let guard = format!("CXXBRIDGE1_STRUCT_{}", ety.name.to_symbol());
+    out.begin_cfg(&ety.cfg);
+    let guard = format!(
+        "{}_STRUCT_{}",
+        mangle::preprocessor_prefix(),
+        ety.name.to_symbol()
+    );
     writeln!(out, "#ifndef {}", guard);
     writeln!(out, "#define {}", guard);
     write_doc(out, "", &ety.doc);
@@ -362,13 +550,18 @@ fn write_opaque_type<'a>(out: &mut OutFile<'a>, ety: &'a ExternType, methods: &[
         if i > 0 && !method.doc.is_empty() {
             out.next_section();
         }
+        out.begin_cfg(&method.cfg);
         write_doc(out, "  ", &method.doc);
         write!(out, "  ");
+        if method.self_type.is_some() {
+            write!(out, "static ");
+        }
         let sig = &method.sig;
         let local_name = method.name.cxx.to_string();
         let indirect_call = false;
         write_rust_function_shim_decl(out, &local_name, sig, indirect_call);
         writeln!(out, ";");
+        out.end_cfg(&method.cfg);
         if !method.doc.is_empty() {
             out.next_section();
         }
@@ -387,6 +580,7 @@ fn write_opaque_type<'a>(out: &mut OutFile<'a>, ety: &'a ExternType, methods: &[
     writeln!(out, "  }};");
     writeln!(out, "}};");
     writeln!(out, "#endif // {}", guard);
+    out.end_cfg(&ety.cfg);
 }
 
 fn write_enum<'a>(out: &mut OutFile<'a>, enm: &'a Enum) {
@@ -396,7 +590,12 @@ fn write_enum<'a>(out: &mut OutFile<'a>, enm: &'a Enum) {
         EnumRepr::Native { atom, .. } => *atom,
     };
     out.set_namespace(&enm.name.namespace);
-    let guard = format!("CXXBRIDGE1_ENUM_{}", enm.name.to_symbol());
+    out.begin_cfg(&enm.cfg);
+    let guard = format!(
+        "{}_ENUM_{}",
+        mangle::preprocessor_prefix(),
+        enm.name.to_symbol()
+    );
     writeln!(out, "#ifndef {}", guard);
     writeln!(out, "#define {}", guard);
     write_doc(out, "", &enm.doc);
@@ -404,11 +603,14 @@ fn write_enum<'a>(out: &mut OutFile<'a>, enm: &'a Enum) {
     write_atom(out, repr);
     writeln!(out, " {{");
     for variant in &enm.variants {
+        out.begin_cfg(&variant.cfg);
         write_doc(out, "  ", &variant.doc);
         writeln!(out, "  {} = {},", variant.name.cxx, variant.discriminant);
+        out.end_cfg(&variant.cfg);
     }
     writeln!(out, "}};");
     writeln!(out, "#endif // {}", guard);
+    out.end_cfg(&enm.cfg);
 }
 
 fn check_enum<'a>(out: &mut OutFile<'a>, enm: &'a Enum) {
@@ -418,6 +620,7 @@ fn check_enum<'a>(out: &mut OutFile<'a>, enm: &'a Enum) {
         EnumRepr::Native { atom, .. } => *atom,
     };
     out.set_namespace(&enm.name.namespace);
+    out.begin_cfg(&enm.cfg);
     out.include.type_traits = true;
     writeln!(
         out,
@@ -428,6 +631,7 @@ fn check_enum<'a>(out: &mut OutFile<'a>, enm: &'a Enum) {
     write_atom(out, repr);
     writeln!(out, "), \"incorrect size\");");
     for variant in &enm.variants {
+        out.begin_cfg(&variant.cfg);
         write!(out, "static_assert(static_cast<");
         write_atom(out, repr);
         writeln!(
@@ -435,6 +639,102 @@ fn check_enum<'a>(out: &mut OutFile<'a>, enm: &'a Enum) {
             ">({}::{}) == {}, \"disagrees with the value in #[cxx::bridge]\");",
             enm.name.cxx, variant.name.cxx, variant.discriminant,
         );
+        out.end_cfg(&variant.cfg);
+    }
+    out.end_cfg(&enm.cfg);
+}
+
+fn write_const<'a>(out: &mut OutFile<'a>, konst: &'a Const) {
+    out.set_namespace(&konst.name.namespace);
+    out.begin_cfg(&konst.cfg);
+    let guard = format!(
+        "{}_CONST_{}",
+        mangle::preprocessor_prefix(),
+        konst.name.to_symbol()
+    );
+    writeln!(out, "#ifndef {}", guard);
+    writeln!(out, "#define {}", guard);
+    write_doc(out, "", &konst.doc);
+    if out.opt.cxx_standard >= CxxStandard::Cxx17 {
+        // Same address in every translation unit, rather than a copy each.
+        write!(out, "inline ");
+    }
+    write!(out, "constexpr ");
+    write_type(out, &konst.ty);
+    write!(out, " {} = ", konst.name.cxx);
+    write_const_value(out, konst);
+    writeln!(out, ";");
+    writeln!(out, "#endif // {}", guard);
+    out.end_cfg(&konst.cfg);
+}
+
+fn check_const<'a>(out: &mut OutFile<'a>, konst: &'a Const) {
+    out.set_namespace(&konst.name.namespace);
+    out.begin_cfg(&konst.cfg);
+    write!(out, "static_assert({} == ", konst.name.cxx);
+    write_const_value(out, konst);
+    writeln!(out, ", \"disagrees with the value in #[cxx::bridge]\");");
+    out.end_cfg(&konst.cfg);
+}
+
+fn write_const_value(out: &mut OutFile, konst: &Const) {
+    let atom = match &konst.ty {
+        Type::Ident(ident) => Atom::from(&ident.rust),
+        _ => None,
+    };
+    let (negative, lit) = match &konst.value {
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => match &*unary.expr {
+            Expr::Lit(lit) => (true, &lit.lit),
+            _ => unreachable!(),
+        },
+        Expr::Lit(lit) => (false, &lit.lit),
+        _ => unreachable!(),
+    };
+    match lit {
+        Lit::Bool(lit) => write!(out, "{}", lit.value),
+        Lit::Int(lit) => {
+            let digits = lit.base10_digits();
+            if negative && is_signed_min(atom, digits) {
+                // The magnitude of the minimum is out of range of the signed
+                // type, and -9223372036854775808 would negate an unsigned
+                // literal, so write it as one more than the maximum, negated.
+                let magnitude: u64 = digits.parse().unwrap();
+                write!(out, "(-{} - 1)", magnitude - 1);
+                return;
+            }
+            if negative {
+                write!(out, "-");
+            }
+            write!(out, "{}", digits);
+            if let Some(U8 | U16 | U32 | U64 | Usize) = atom {
+                write!(out, "u");
+            }
+        }
+        Lit::Float(lit) => {
+            let digits = lit.base10_digits();
+            if negative {
+                write!(out, "-");
+            }
+            write!(out, "{}", digits);
+            if !digits.contains(|ch| ch == '.' || ch == 'e' || ch == 'E') {
+                write!(out, ".0");
+            }
+            if let Some(F32) = atom {
+                write!(out, "f");
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn is_signed_min(atom: Option<Atom>, digits: &str) -> bool {
+    match atom {
+        Some(I8) => digits == "128",
+        Some(I16) => digits == "32768",
+        Some(I32) => digits == "2147483648",
+        Some(I64) => digits == "9223372036854775808",
+        Some(Isize) => digits == "2147483648" || digits == "9223372036854775808",
+        _ => false,
     }
 }
 
@@ -504,6 +804,7 @@ fn check_trivial_extern_type(out: &mut OutFile, alias: &TypeAlias, reasons: &[Tr
 
 fn write_struct_operator_decls<'a>(out: &mut OutFile<'a>, strct: &'a Struct) {
     out.set_namespace(&strct.name.namespace);
+    out.begin_cfg(&strct.cfg);
     out.begin_block(Block::ExternC);
 
     if derive::contains(&strct.derives, Trait::PartialEq) {
@@ -567,6 +868,7 @@ fn write_struct_operator_decls<'a>(out: &mut OutFile<'a>, strct: &'a Struct) {
     }
 
     out.end_block(Block::ExternC);
+    out.end_cfg(&strct.cfg);
 }
 
 fn write_struct_operators<'a>(out: &mut OutFile<'a>, strct: &'a Struct) {
@@ -575,6 +877,7 @@ fn write_struct_operators<'a>(out: &mut OutFile<'a>, strct: &'a Struct) {
     }
 
     out.set_namespace(&strct.name.namespace);
+    out.begin_cfg(&strct.cfg);
 
     if derive::contains(&strct.derives, Trait::PartialEq) {
         out.next_section();
@@ -651,10 +954,12 @@ fn write_struct_operators<'a>(out: &mut OutFile<'a>, strct: &'a Struct) {
         }
         writeln!(out, "}}");
     }
+    out.end_cfg(&strct.cfg);
 }
 
 fn write_opaque_type_layout_decls<'a>(out: &mut OutFile<'a>, ety: &'a ExternType) {
     out.set_namespace(&ety.name.namespace);
+    out.begin_cfg(&ety.cfg);
     out.begin_block(Block::ExternC);
 
     let link_name = mangle::operator(&ety.name, "sizeof");
@@ -664,6 +969,7 @@ fn write_opaque_type_layout_decls<'a>(out: &mut OutFile<'a>, ety: &'a ExternType
     writeln!(out, "::std::size_t {}() noexcept;", link_name);
 
     out.end_block(Block::ExternC);
+    out.end_cfg(&ety.cfg);
 }
 
 fn write_opaque_type_layout<'a>(out: &mut OutFile<'a>, ety: &'a ExternType) {
@@ -672,6 +978,7 @@ fn write_opaque_type_layout<'a>(out: &mut OutFile<'a>, ety: &'a ExternType) {
     }
 
     out.set_namespace(&ety.name.namespace);
+    out.begin_cfg(&ety.cfg);
 
     out.next_section();
     let link_name = mangle::operator(&ety.name, "sizeof");
@@ -692,6 +999,172 @@ fn write_opaque_type_layout<'a>(out: &mut OutFile<'a>, ety: &'a ExternType) {
     );
     writeln!(out, "  return {}();", link_name);
     writeln!(out, "}}");
+    out.end_cfg(&ety.cfg);
+}
+
+fn write_cxx_type_in_place_support<'a>(out: &mut OutFile<'a>, alias: &'a TypeAlias, apis: &[Api]) {
+    let has_constructor = apis.iter().any(|api| match api {
+        Api::CxxFunction(efn) => {
+            efn.constructor && efn.self_type.as_ref().unwrap().rust == alias.name.rust
+        }
+        _ => false,
+    });
+    if !has_constructor {
+        return;
+    }
+
+    out.set_namespace(&alias.name.namespace);
+    out.begin_cfg(&alias.cfg);
+    out.begin_block(Block::ExternC);
+    out.include.cstddef = true;
+    out.builtin.destroy = true;
+    let qualified = alias.name.to_fully_qualified();
+
+    out.next_section();
+    let link_name = mangle::operator(&alias.name, "sizeof");
+    writeln!(out, "::std::size_t {}() noexcept {{", link_name);
+    writeln!(out, "  return sizeof({});", qualified);
+    writeln!(out, "}}");
+
+    out.next_section();
+    let link_name = mangle::operator(&alias.name, "alignof");
+    writeln!(out, "::std::size_t {}() noexcept {{", link_name);
+    writeln!(out, "  return alignof({});", qualified);
+    writeln!(out, "}}");
+
+    out.next_section();
+    let link_name = mangle::operator(&alias.name, "drop");
+    writeln!(out, "void {}({} *self) noexcept {{", link_name, qualified);
+    writeln!(out, "  ::rust::destroy(self);");
+    writeln!(out, "}}");
+
+    out.end_block(Block::ExternC);
+    out.end_cfg(&alias.cfg);
+}
+
+fn write_cxx_type_operators<'a>(out: &mut OutFile<'a>, ety: &'a ExternType) {
+    if ety.derives.is_empty() {
+        return;
+    }
+
+    out.set_namespace(&ety.name.namespace);
+    out.begin_cfg(&ety.cfg);
+    out.begin_block(Block::ExternC);
+    let qualified = ety.name.to_fully_qualified();
+
+    for derive in &ety.derives {
+        match derive.what {
+            Trait::Add => {
+                out.next_section();
+                let link_name = mangle::operator(&ety.name, "add");
+                writeln!(
+                    out,
+                    "{0} *{1}({0} const &lhs, {0} const &rhs) noexcept {{",
+                    qualified, link_name,
+                );
+                writeln!(out, "  return new {}(lhs + rhs);", qualified);
+                writeln!(out, "}}");
+            }
+            Trait::Clone => {
+                out.next_section();
+                out.include.memory = true;
+                out.include.new = true;
+                out.include.type_traits = true;
+                let link_name = mangle::operator(&ety.name, "clone");
+                writeln!(
+                    out,
+                    "{0} *{1}({0} const &self) noexcept {{",
+                    qualified, link_name,
+                );
+                writeln!(
+                    out,
+                    "  static_assert(::std::is_copy_constructible<{}>::value, \"derive(Clone) on {} requires an accessible copy constructor\");",
+                    qualified, ety.name.rust,
+                );
+                writeln!(out, "  return new {}(self);", qualified);
+                writeln!(out, "}}");
+
+                out.next_section();
+                let link_name = mangle::operator(&ety.name, "clone_shared");
+                writeln!(
+                    out,
+                    "void {1}({0} const &self, ::std::shared_ptr<{0}> *ptr) noexcept {{",
+                    qualified, link_name,
+                );
+                writeln!(
+                    out,
+                    "  ::new (ptr) ::std::shared_ptr<{0}>(new {0}(self));",
+                    qualified,
+                );
+                writeln!(out, "}}");
+            }
+            Trait::PartialEq => {
+                out.next_section();
+                let link_name = mangle::operator(&ety.name, "eq");
+                writeln!(
+                    out,
+                    "bool {}({1} const &lhs, {1} const &rhs) noexcept {{",
+                    link_name, qualified,
+                );
+                writeln!(out, "  return lhs == rhs;");
+                writeln!(out, "}}");
+            }
+            Trait::PartialOrd => {
+                out.next_section();
+                let link_name = mangle::operator(&ety.name, "lt");
+                writeln!(
+                    out,
+                    "bool {}({1} const &lhs, {1} const &rhs) noexcept {{",
+                    link_name, qualified,
+                );
+                writeln!(out, "  return lhs < rhs;");
+                writeln!(out, "}}");
+            }
+            Trait::Hash => {
+                out.next_section();
+                out.include.cstddef = true;
+                out.include.functional = true;
+                let link_name = mangle::operator(&ety.name, "hash");
+                writeln!(
+                    out,
+                    "::std::size_t {}({} const &self) noexcept {{",
+                    link_name, qualified,
+                );
+                writeln!(out, "  return ::std::hash<{}>{{}}(self);", qualified);
+                writeln!(out, "}}");
+            }
+            Trait::Debug => {
+                out.next_section();
+                out.include.new = true;
+                out.include.sstream = true;
+                let link_name = mangle::operator(&ety.name, "debug");
+                out.builtin.trycatch = true;
+                writeln!(
+                    out,
+                    "bool {}({} const &self, ::rust::String *return$) noexcept {{",
+                    link_name, qualified,
+                );
+                writeln!(out, "  bool ok$ = false;");
+                writeln!(out, "  ::rust::behavior::trycatch(");
+                writeln!(out, "      [&] {{");
+                writeln!(out, "        ::std::ostringstream stream;");
+                writeln!(out, "        stream << self;");
+                writeln!(
+                    out,
+                    "        new (return$) ::rust::String(::rust::String::lossy(stream.str()));",
+                );
+                writeln!(out, "        ok$ = true;");
+                writeln!(out, "      }},");
+                writeln!(out, "      [](const char *) noexcept {{}});");
+                writeln!(out, "  return ok$;");
+                writeln!(out, "}}");
+            }
+            _ => {}
+        }
+    }
+
+    out.end_block(Block::ExternC);
+    out.end_cfg(&ety.cfg);
 }
 
 fn begin_function_definition(out: &mut OutFile) {
@@ -703,9 +1176,12 @@ fn begin_function_definition(out: &mut OutFile) {
 fn write_cxx_function_shim<'a>(out: &mut OutFile<'a>, efn: &'a ExternFn) {
     out.next_section();
     out.set_namespace(&efn.name.namespace);
+    out.begin_cfg(&efn.cfg);
     out.begin_block(Block::ExternC);
     begin_function_definition(out);
-    if efn.throws {
+    if efn.error.is_some() {
+        write!(out, "bool ");
+    } else if efn.throws {
         out.builtin.ptr_len = true;
         write!(out, "::rust::repr::PtrLen ");
     } else {
@@ -728,61 +1204,134 @@ fn write_cxx_function_shim<'a>(out: &mut OutFile<'a>, efn: &'a ExternFn) {
         if i > 0 || efn.receiver.is_some() {
             write!(out, ", ");
         }
-        if arg.ty == RustString {
-            write_type_space(out, &arg.ty);
-            write!(out, "const *{}", arg.name.cxx);
-        } else if let Type::RustVec(_) = arg.ty {
-            write_type_space(out, &arg.ty);
-            write!(out, "const *{}", arg.name.cxx);
-        } else {
-            write_extern_arg(out, arg);
+        write_cxx_function_shim_arg(out, arg);
+    }
+    if let Some(self_type) = efn.self_type.as_ref().filter(|_| efn.constructor) {
+        if !efn.args.is_empty() {
+            write!(out, ", ");
         }
+        write!(
+            out,
+            "{} *slot$",
+            out.types.resolve(self_type).name.to_fully_qualified(),
+        );
     }
     let indirect_return = indirect_return(efn, out.types);
     if indirect_return {
-        if !efn.args.is_empty() || efn.receiver.is_some() {
+        if !efn.args.is_empty() || efn.receiver.is_some() || efn.constructor {
             write!(out, ", ");
         }
         write_indirect_return_type_space(out, efn.ret.as_ref().unwrap());
         write!(out, "*return$");
     }
+    if let Some(error) = &efn.error {
+        if indirect_return || !efn.args.is_empty() || efn.receiver.is_some() {
+            write!(out, ", ");
+        }
+        write_type_space(out, error);
+        write!(out, "*error$");
+    }
     writeln!(out, ") noexcept {{");
-    write!(out, "  ");
-    write_return_type(out, &efn.ret);
-    match &efn.receiver {
-        None => write!(out, "(*{}$)(", efn.name.rust),
-        Some(receiver) => write!(
-            out,
-            "({}::*{}$)(",
-            out.types.resolve(&receiver.ty).name.to_fully_qualified(),
-            efn.name.rust,
-        ),
+    if !efn.constructor {
+        write!(out, "  ");
+        write_signature_return_type(out, efn);
+        match &efn.receiver {
+            None => write!(out, "(*{}$)(", efn.name.rust),
+            Some(receiver) => write!(
+                out,
+                "({}::*{}$)(",
+                out.types.resolve(&receiver.ty).name.to_fully_qualified(),
+                efn.name.rust,
+            ),
+        }
+        for (i, arg) in efn.args.iter().enumerate() {
+            if i > 0 {
+                write!(out, ", ");
+            }
+            write_type(out, &arg.ty);
+        }
+        write!(out, ")");
+        if let Some(receiver) = &efn.receiver {
+            if !receiver.mutable {
+                write!(out, " const");
+            }
+        }
+        write!(out, " = ");
+        match (&efn.receiver, &efn.self_type) {
+            (Some(receiver), _) => write!(
+                out,
+                "&{}::{}",
+                out.types.resolve(&receiver.ty).name.to_fully_qualified(),
+                efn.name.cxx,
+            ),
+            (None, Some(self_type)) => write!(
+                out,
+                "&{}::{}",
+                out.types.resolve(self_type).name.to_fully_qualified(),
+                efn.name.cxx,
+            ),
+            (None, None) => write!(out, "{}", efn.name.to_fully_qualified()),
+        }
+        writeln!(out, ";");
     }
-    for (i, arg) in efn.args.iter().enumerate() {
-        if i > 0 {
-            write!(out, ", ");
+    write_cxx_function_call_begin(out, efn, indirect_return);
+    if let Some(self_type) = efn.self_type.as_ref().filter(|_| efn.constructor) {
+        out.include.new = true;
+        let self_type = out.types.resolve(self_type).name.to_fully_qualified();
+        write!(out, "slot$ ? new (slot$) {}(", self_type);
+        write_cxx_function_call_args(out, efn);
+        write!(out, ") : new {}(", self_type);
+        write_cxx_function_call_args(out, efn);
+        write!(out, ")");
+    } else {
+        match &efn.receiver {
+            None => write!(out, "{}$(", efn.name.rust),
+            Some(_) => write!(out, "(self.*{}$)(", efn.name.rust),
         }
-        write_type(out, &arg.ty);
+        write_cxx_function_call_args(out, efn);
+        write!(out, ")");
+        write_cxx_function_return_conversion(out, efn, indirect_return);
     }
-    write!(out, ")");
-    if let Some(receiver) = &efn.receiver {
-        if !receiver.mutable {
-            write!(out, " const");
+    write_cxx_function_call_end(out, efn, indirect_return);
+    writeln!(out, "}}");
+    for arg in &efn.args {
+        if let Type::Fn(f) = &arg.ty {
+            let var = &arg.name;
+            write_function_pointer_trampoline(out, efn, var, f);
+        } else if let Type::DynFn(f) = &arg.ty {
+            let var = &arg.name;
+            write_function_pointer_trampoline(out, efn, var, &f.sig);
         }
     }
-    write!(out, " = ");
-    match &efn.receiver {
-        None => write!(out, "{}", efn.name.to_fully_qualified()),
-        Some(receiver) => write!(
-            out,
-            "&{}::{}",
-            out.types.resolve(&receiver.ty).name.to_fully_qualified(),
-            efn.name.cxx,
-        ),
+    out.end_block(Block::ExternC);
+    out.end_cfg(&efn.cfg);
+}
+
+fn write_cxx_function_shim_arg(out: &mut OutFile, arg: &Var) {
+    if arg.ty == RustString {
+        write_type_space(out, &arg.ty);
+        write!(out, "const *{}", arg.name.cxx);
+    } else if let Type::RustVec(_) = arg.ty {
+        write_type_space(out, &arg.ty);
+        write!(out, "const *{}", arg.name.cxx);
+    } else {
+        write_extern_arg(out, arg);
     }
-    writeln!(out, ";");
+}
+
+fn write_cxx_function_call_begin(out: &mut OutFile, sig: &Signature, indirect_return: bool) {
     write!(out, "  ");
-    if efn.throws {
+    if returns_expected(out, sig) {
+        if sig.error.is_none() {
+            out.builtin.ptr_len = true;
+            writeln!(out, "::rust::repr::PtrLen throw$;");
+            write!(out, "  ");
+        }
+        write_signature_return_type(out, sig);
+        write!(out, "expected$ = ");
+        return;
+    }
+    if sig.throws {
         out.builtin.ptr_len = true;
         out.builtin.trycatch = true;
         writeln!(out, "::rust::repr::PtrLen throw$;");
@@ -790,15 +1339,19 @@ fn write_cxx_function_shim<'a>(out: &mut OutFile<'a>, efn: &'a ExternFn) {
         writeln!(out, "      [&] {{");
         write!(out, "        ");
     }
+    write_cxx_function_return_prefix(out, sig, indirect_return);
+}
+
+fn write_cxx_function_return_prefix(out: &mut OutFile, sig: &Signature, indirect_return: bool) {
     if indirect_return {
         out.include.new = true;
         write!(out, "new (return$) ");
-        write_indirect_return_type(out, efn.ret.as_ref().unwrap());
+        write_indirect_return_type(out, sig.ret.as_ref().unwrap());
         write!(out, "(");
-    } else if efn.ret.is_some() {
+    } else if sig.ret.is_some() {
         write!(out, "return ");
     }
-    match &efn.ret {
+    match &sig.ret {
         Some(Type::Ref(_)) => write!(out, "&"),
         Some(Type::Str(_)) if !indirect_return => {
             out.builtin.rust_str_repr = true;
@@ -812,15 +1365,78 @@ fn write_cxx_function_shim<'a>(out: &mut OutFile<'a>, efn: &'a ExternFn) {
         }
         _ => {}
     }
-    match &efn.receiver {
-        None => write!(out, "{}$(", efn.name.rust),
-        Some(_) => write!(out, "(self.*{}$)(", efn.name.rust),
+}
+
+fn write_cxx_function_return_conversion(out: &mut OutFile, sig: &Signature, indirect_return: bool) {
+    if returns_expected(out, sig) {
+        // Converted by write_cxx_function_call_end once the error is ruled out.
+        return;
+    }
+    match &sig.ret {
+        Some(Type::RustBox(_)) | Some(Type::RustArc(_)) => write!(out, ".into_raw()"),
+        Some(Type::UniquePtr(_)) => write!(out, ".release()"),
+        Some(Type::Str(_)) | Some(Type::SliceRef(_)) if !indirect_return => write!(out, ")"),
+        _ => {}
     }
-    for (i, arg) in efn.args.iter().enumerate() {
+}
+
+fn write_cxx_function_call_end(out: &mut OutFile, sig: &Signature, indirect_return: bool) {
+    if returns_expected(out, sig) {
+        writeln!(out, ";");
+        writeln!(out, "  if (!expected$) {{");
+        if let Some(error) = &sig.error {
+            out.include.new = true;
+            out.include.utility = true;
+            write!(out, "    new (error$) ");
+            write_type(out, error);
+            writeln!(out, "(::std::move(expected$).error());");
+            writeln!(out, "    return false;");
+        } else {
+            out.builtin.fail = true;
+            writeln!(out, "    ::rust::detail::Fail fail$(throw$);");
+            writeln!(out, "    fail$(expected$.error().what());");
+            writeln!(out, "    return throw$;");
+        }
+        writeln!(out, "  }}");
+        if sig.ret.is_some() {
+            out.include.utility = true;
+            write!(out, "  ");
+            write_cxx_function_return_prefix(out, sig, indirect_return);
+            match &sig.ret {
+                Some(Type::RustBox(_)) | Some(Type::RustArc(_)) => {
+                    write!(out, "(*::std::move(expected$)).into_raw()");
+                }
+                Some(Type::UniquePtr(_)) => write!(out, "(*::std::move(expected$)).release()"),
+                _ => write!(out, "*::std::move(expected$)"),
+            }
+            writeln!(out, ");");
+        }
+        if sig.error.is_some() {
+            writeln!(out, "  return true;");
+        } else {
+            writeln!(out, "  throw$.ptr = nullptr;");
+            writeln!(out, "  return throw$;");
+        }
+        return;
+    }
+    if indirect_return {
+        write!(out, ")");
+    }
+    writeln!(out, ";");
+    if sig.throws {
+        writeln!(out, "        throw$.ptr = nullptr;");
+        writeln!(out, "      }},");
+        writeln!(out, "      ::rust::detail::Fail(throw$));");
+        writeln!(out, "  return throw$;");
+    }
+}
+
+fn write_cxx_function_call_args(out: &mut OutFile, sig: &Signature) {
+    for (i, arg) in sig.args.iter().enumerate() {
         if i > 0 {
             write!(out, ", ");
         }
-        if let Type::RustBox(_) = &arg.ty {
+        if let Type::RustBox(_) | Type::RustArc(_) = &arg.ty {
             write_type(out, &arg.ty);
             write!(out, "::from_raw({})", arg.name.cxx);
         } else if let Type::UniquePtr(_) = &arg.ty {
@@ -837,6 +1453,9 @@ fn write_cxx_function_shim<'a>(out: &mut OutFile<'a>, efn: &'a ExternFn) {
             out.builtin.unsafe_bitcopy = true;
             write_type(out, &arg.ty);
             write!(out, "(::rust::unsafe_bitcopy, *{})", arg.name.cxx);
+        } else if let Type::Tuple(_) = arg.ty {
+            out.include.utility = true;
+            write!(out, "::std::move(*{}).into()", arg.name.cxx);
         } else if out.types.needs_indirect_abi(&arg.ty) {
             out.include.utility = true;
             write!(out, "::std::move(*{})", arg.name.cxx);
@@ -844,57 +1463,83 @@ fn write_cxx_function_shim<'a>(out: &mut OutFile<'a>, efn: &'a ExternFn) {
             write!(out, "{}", arg.name.cxx);
         }
     }
-    write!(out, ")");
-    match &efn.ret {
-        Some(Type::RustBox(_)) => write!(out, ".into_raw()"),
-        Some(Type::UniquePtr(_)) => write!(out, ".release()"),
-        Some(Type::Str(_)) | Some(Type::SliceRef(_)) if !indirect_return => write!(out, ")"),
-        _ => {}
-    }
-    if indirect_return {
-        write!(out, ")");
-    }
-    writeln!(out, ";");
-    if efn.throws {
-        writeln!(out, "        throw$.ptr = nullptr;");
-        writeln!(out, "      }},");
-        writeln!(out, "      ::rust::detail::Fail(throw$));");
-        writeln!(out, "  return throw$;");
-    }
-    writeln!(out, "}}");
-    for arg in &efn.args {
-        if let Type::Fn(f) = &arg.ty {
-            let var = &arg.name;
-            write_function_pointer_trampoline(out, efn, var, f);
-        }
-    }
-    out.end_block(Block::ExternC);
 }
 
 fn write_function_pointer_trampoline(out: &mut OutFile, efn: &ExternFn, var: &Pair, f: &Signature) {
     let r_trampoline = mangle::r_trampoline(efn, var, out.types);
+    let catch_unwind = false;
     let indirect_call = true;
-    write_rust_function_decl_impl(out, &r_trampoline, f, indirect_call);
+    write_rust_function_decl_impl(out, &r_trampoline, f, catch_unwind, indirect_call);
 
     out.next_section();
     let c_trampoline = mangle::c_trampoline(efn, var, out.types).to_string();
     let doc = Doc::new();
-    write_rust_function_shim_impl(out, &c_trampoline, f, &doc, &r_trampoline, indirect_call);
+    write_rust_function_shim_impl(
+        out,
+        &c_trampoline,
+        f,
+        &doc,
+        &r_trampoline,
+        catch_unwind,
+        indirect_call,
+    );
 }
 
 fn write_rust_function_decl<'a>(out: &mut OutFile<'a>, efn: &'a ExternFn) {
     out.set_namespace(&efn.name.namespace);
+    out.begin_cfg(&efn.cfg);
     out.begin_block(Block::ExternC);
     let link_name = mangle::extern_fn(efn, out.types);
     let indirect_call = false;
-    write_rust_function_decl_impl(out, &link_name, efn, indirect_call);
+    write_rust_function_decl_impl(out, &link_name, efn, efn.catch_unwind, indirect_call);
+    for arg in &efn.args {
+        if let Type::DynFn(f) = &arg.ty {
+            write_closure_call(out, efn, &arg.name, &arg.ty, &f.sig);
+        }
+    }
     out.end_block(Block::ExternC);
+    out.end_cfg(&efn.cfg);
+}
+
+// Lets Rust invoke a C++ callable that was moved into a Box<dyn FnMut>.
+fn write_closure_call(out: &mut OutFile, efn: &ExternFn, var: &Pair, ty: &Type, sig: &Signature) {
+    out.next_section();
+    begin_function_definition(out);
+    if sig.throws {
+        out.builtin.ptr_len = true;
+        write!(out, "::rust::repr::PtrLen ");
+    } else {
+        write_extern_return_type_space(out, &sig.ret);
+    }
+    let mangled = mangle::closure_call(efn, var, out.types);
+    write!(out, "{}(", mangled);
+    write_type_space(out, ty);
+    write!(out, "&self");
+    for arg in &sig.args {
+        write!(out, ", ");
+        write_cxx_function_shim_arg(out, arg);
+    }
+    let indirect_return = indirect_return(sig, out.types);
+    if indirect_return {
+        write!(out, ", ");
+        write_indirect_return_type_space(out, sig.ret.as_ref().unwrap());
+        write!(out, "*return$");
+    }
+    writeln!(out, ") noexcept {{");
+    write_cxx_function_call_begin(out, sig, indirect_return);
+    write!(out, "self(");
+    write_cxx_function_call_args(out, sig);
+    write!(out, ")");
+    write_cxx_function_return_conversion(out, sig, indirect_return);
+    write_cxx_function_call_end(out, sig, indirect_return);
+    writeln!(out, "}}");
 }
 
 fn write_rust_function_decl_impl(
     out: &mut OutFile,
     link_name: &Symbol,
     sig: &Signature,
+    catch_unwind: bool,
     indirect_call: bool,
 ) {
     out.next_section();
@@ -937,11 +1582,23 @@ fn write_rust_function_decl_impl(
                 }
                 write!(out, "*");
             }
+            Type::Tuple(ret) => {
+                write_tuple_repr(out, ret);
+                write!(out, " ");
+            }
             ret => write_type_space(out, ret),
         }
         write!(out, "*return$");
         needs_comma = true;
     }
+    if catch_unwind {
+        out.builtin.repr_panic = true;
+        if needs_comma {
+            write!(out, ", ");
+        }
+        write!(out, "::rust::repr::Panic *panic$");
+        needs_comma = true;
+    }
     if indirect_call {
         if needs_comma {
             write!(out, ", ");
@@ -953,18 +1610,33 @@ fn write_rust_function_decl_impl(
 
 fn write_rust_function_shim<'a>(out: &mut OutFile<'a>, efn: &'a ExternFn) {
     out.set_namespace(&efn.name.namespace);
-    let local_name = match &efn.sig.receiver {
-        None => efn.name.cxx.to_string(),
-        Some(receiver) => format!(
+    out.begin_cfg(&efn.cfg);
+    let local_name = match (&efn.sig.receiver, &efn.sig.self_type) {
+        (Some(receiver), _) => format!(
             "{}::{}",
             out.types.resolve(&receiver.ty).name.cxx,
             efn.name.cxx,
         ),
+        (None, Some(self_type)) => format!(
+            "{}::{}",
+            out.types.resolve(self_type).name.cxx,
+            efn.name.cxx,
+        ),
+        (None, None) => efn.name.cxx.to_string(),
     };
     let doc = &efn.doc;
     let invoke = mangle::extern_fn(efn, out.types);
     let indirect_call = false;
-    write_rust_function_shim_impl(out, &local_name, efn, doc, &invoke, indirect_call);
+    write_rust_function_shim_impl(
+        out,
+        &local_name,
+        efn,
+        doc,
+        &invoke,
+        efn.catch_unwind,
+        indirect_call,
+    );
+    out.end_cfg(&efn.cfg);
 }
 
 fn write_rust_function_shim_decl(
@@ -974,7 +1646,7 @@ fn write_rust_function_shim_decl(
     indirect_call: bool,
 ) {
     begin_function_definition(out);
-    write_return_type(out, &sig.ret);
+    write_signature_return_type(out, sig);
     write!(out, "{}(", local_name);
     for (i, arg) in sig.args.iter().enumerate() {
         if i > 0 {
@@ -995,7 +1667,7 @@ fn write_rust_function_shim_decl(
             write!(out, " const");
         }
     }
-    if !sig.throws {
+    if !sig.throws || !out.opt.exceptions {
         write!(out, " noexcept");
     }
 }
@@ -1006,13 +1678,15 @@ fn write_rust_function_shim_impl(
     sig: &Signature,
     doc: &Doc,
     invoke: &Symbol,
+    catch_unwind: bool,
     indirect_call: bool,
 ) {
-    if out.header && sig.receiver.is_some() {
+    let member = sig.receiver.is_some() || sig.self_type.is_some();
+    if out.header && member {
         // We've already defined this inside the struct.
         return;
     }
-    if sig.receiver.is_none() {
+    if !member {
         // Member functions already documented at their declaration.
         write_doc(out, "", doc);
     }
@@ -1027,10 +1701,17 @@ fn write_rust_function_shim_impl(
             out.include.utility = true;
             out.builtin.manually_drop = true;
             write!(out, "  ::rust::ManuallyDrop<");
-            write_type(out, &arg.ty);
+            match &arg.ty {
+                Type::Tuple(tuple) => write_tuple_repr(out, tuple),
+                ty => write_type(out, ty),
+            }
             writeln!(out, "> {}$(::std::move({0}));", arg.name.cxx);
         }
     }
+    if catch_unwind {
+        out.builtin.repr_panic = true;
+        writeln!(out, "  ::rust::repr::Panic panic${{}};");
+    }
     write!(out, "  ");
     let indirect_return = indirect_return(sig, out.types);
     if indirect_return {
@@ -1044,6 +1725,7 @@ fn write_rust_function_shim_impl(
                 }
                 write!(out, "*");
             }
+            Type::Tuple(ret) => write_tuple_repr(out, ret),
             ret => write_type(out, ret),
         }
         writeln!(out, "> return$;");
@@ -1051,7 +1733,7 @@ fn write_rust_function_shim_impl(
     } else if let Some(ret) = &sig.ret {
         write!(out, "return ");
         match ret {
-            Type::RustBox(_) => {
+            Type::RustBox(_) | Type::RustArc(_) => {
                 write_type(out, ret);
                 write!(out, "::from_raw(");
             }
@@ -1092,7 +1774,7 @@ fn write_rust_function_shim_impl(
         }
         write!(out, "{}", arg.name.cxx);
         match &arg.ty {
-            Type::RustBox(_) => write!(out, ".into_raw()"),
+            Type::RustBox(_) | Type::RustArc(_) => write!(out, ".into_raw()"),
             Type::UniquePtr(_) => write!(out, ".release()"),
             ty if ty != RustString && out.types.needs_indirect_abi(ty) => write!(out, "$.value"),
             _ => {}
@@ -1106,6 +1788,13 @@ fn write_rust_function_shim_impl(
         write!(out, "&return$.value");
         needs_comma = true;
     }
+    if catch_unwind {
+        if needs_comma {
+            write!(out, ", ");
+        }
+        write!(out, "&panic$");
+        needs_comma = true;
+    }
     if indirect_call {
         if needs_comma {
             write!(out, ", ");
@@ -1115,22 +1804,47 @@ fn write_rust_function_shim_impl(
     write!(out, ")");
     if !indirect_return {
         if let Some(ret) = &sig.ret {
-            if let Type::RustBox(_) | Type::UniquePtr(_) | Type::Str(_) | Type::SliceRef(_) = ret {
+            if let Type::RustBox(_)
+            | Type::RustArc(_)
+            | Type::UniquePtr(_)
+            | Type::Str(_)
+            | Type::SliceRef(_) = ret
+            {
                 write!(out, ")");
             }
         }
     }
     writeln!(out, ";");
+    if catch_unwind {
+        out.builtin.rust_panic = true;
+        writeln!(out, "  if (panic$.msg.ptr) {{");
+        writeln!(out, "    throw ::rust::impl<::rust::Panic>::panic(panic$);");
+        writeln!(out, "  }}");
+    }
     if sig.throws {
         out.builtin.rust_error = true;
         writeln!(out, "  if (error$.ptr) {{");
-        writeln!(out, "    throw ::rust::impl<::rust::Error>::error(error$);");
+        if out.opt.exceptions {
+            writeln!(out, "    throw ::rust::impl<::rust::Error>::error(error$);");
+        } else {
+            writeln!(
+                out,
+                "    return ::rust::impl<::rust::Error>::error(error$);"
+            );
+        }
         writeln!(out, "  }}");
+        if sig.ret.is_none() && !out.opt.exceptions {
+            writeln!(out, "  return {{}};");
+        }
     }
     if indirect_return {
         write!(out, "  return ");
         match sig.ret.as_ref().unwrap() {
             Type::Ref(_) => write!(out, "*return$.value"),
+            Type::Tuple(_) => {
+                out.include.utility = true;
+                write!(out, "::std::move(return$.value).into()");
+            }
             _ => {
                 out.include.utility = true;
                 write!(out, "::std::move(return$.value)");
@@ -1148,6 +1862,34 @@ fn write_return_type(out: &mut OutFile, ty: &Option<Type>) {
     }
 }
 
+// Without exceptions, a fallible function's C++ signature carries its error in
+// a rust::Expected<T> rather than throwing it. A typed error is carried the same
+// way, as rust::Result<T, E>, regardless of whether exceptions are enabled.
+fn write_signature_return_type(out: &mut OutFile, sig: &Signature) {
+    if returns_expected(out, sig) {
+        out.builtin.rust_expected = true;
+        match &sig.error {
+            None => write!(out, "::rust::Expected<"),
+            Some(_) => write!(out, "::rust::Result<"),
+        }
+        match &sig.ret {
+            None => write!(out, "void"),
+            Some(ty) => write_type(out, ty),
+        }
+        if let Some(error) = &sig.error {
+            write!(out, ", ");
+            write_type(out, error);
+        }
+        write!(out, "> ");
+    } else {
+        write_return_type(out, &sig.ret);
+    }
+}
+
+fn returns_expected(out: &OutFile, sig: &Signature) -> bool {
+    sig.throws && (sig.error.is_some() || !out.opt.exceptions)
+}
+
 fn indirect_return(sig: &Signature, types: &Types) -> bool {
     sig.ret
         .as_ref()
@@ -1160,6 +1902,10 @@ fn write_indirect_return_type(out: &mut OutFile, ty: &Type) {
             write_type_space(out, &ty.inner);
             write!(out, "*");
         }
+        Type::RustArc(ty) => {
+            write_type_space(out, &ty.inner);
+            write!(out, "const *");
+        }
         Type::Ref(ty) => {
             write_type_space(out, &ty.inner);
             if !ty.mutable {
@@ -1167,6 +1913,7 @@ fn write_indirect_return_type(out: &mut OutFile, ty: &Type) {
             }
             write!(out, "*");
         }
+        Type::Tuple(ty) => write_tuple_repr(out, ty),
         _ => write_type(out, ty),
     }
 }
@@ -1174,8 +1921,8 @@ fn write_indirect_return_type(out: &mut OutFile, ty: &Type) {
 fn write_indirect_return_type_space(out: &mut OutFile, ty: &Type) {
     write_indirect_return_type(out, ty);
     match ty {
-        Type::RustBox(_) | Type::UniquePtr(_) | Type::Ref(_) => {}
-        Type::Str(_) | Type::SliceRef(_) => write!(out, " "),
+        Type::RustBox(_) | Type::RustArc(_) | Type::UniquePtr(_) | Type::Ref(_) => {}
+        Type::Str(_) | Type::SliceRef(_) | Type::Tuple(_) => write!(out, " "),
         _ => write_space_after_type(out, ty),
     }
 }
@@ -1186,6 +1933,10 @@ fn write_extern_return_type_space(out: &mut OutFile, ty: &Option<Type>) {
             write_type_space(out, &ty.inner);
             write!(out, "*");
         }
+        Some(Type::RustArc(ty)) => {
+            write_type_space(out, &ty.inner);
+            write!(out, "const *");
+        }
         Some(Type::Ref(ty)) => {
             write_type_space(out, &ty.inner);
             if !ty.mutable {
@@ -1208,6 +1959,14 @@ fn write_extern_arg(out: &mut OutFile, arg: &Var) {
             write_type_space(out, &ty.inner);
             write!(out, "*");
         }
+        Type::RustArc(ty) => {
+            write_type_space(out, &ty.inner);
+            write!(out, "const *");
+        }
+        Type::Tuple(ty) => {
+            write_tuple_repr(out, ty);
+            write!(out, " ");
+        }
         _ => write_type_space(out, &arg.ty),
     }
     if out.types.needs_indirect_abi(&arg.ty) {
@@ -1231,6 +1990,11 @@ fn write_type(out: &mut OutFile, ty: &Type) {
             write_type(out, &ty.inner);
             write!(out, ">");
         }
+        Type::RustArc(ty) => {
+            write!(out, "::rust::Arc<");
+            write_type(out, &ty.inner);
+            write!(out, ">");
+        }
         Type::RustVec(ty) => {
             write!(out, "::rust::Vec<");
             write_type(out, &ty.inner);
@@ -1239,6 +2003,11 @@ fn write_type(out: &mut OutFile, ty: &Type) {
         Type::UniquePtr(ptr) => {
             write!(out, "::std::unique_ptr<");
             write_type(out, &ptr.inner);
+            if let Type::Ident(inner) = &ptr.inner {
+                if let Some(deleter) = unique_ptr_deleter(out.types, &inner.rust) {
+                    write!(out, ", {}", deleter);
+                }
+            }
             write!(out, ">");
         }
         Type::SharedPtr(ptr) => {
@@ -1283,28 +2052,67 @@ fn write_type(out: &mut OutFile, ty: &Type) {
         }
         Type::Fn(f) => {
             write!(out, "::rust::Fn<");
-            match &f.ret {
-                Some(ret) => write_type(out, ret),
-                None => write!(out, "void"),
+            write_function_type(out, f);
+            write!(out, ">");
+        }
+        Type::DynFn(f) => {
+            write!(out, "::rust::FnMut<");
+            write_function_type(out, &f.sig);
+            write!(out, ">");
+        }
+        Type::Array(a) => {
+            write!(out, "::std::array<");
+            write_type(out, &a.inner);
+            match &a.len_const {
+                Some(len_const) => {
+                    let konst = out.types.consts[len_const];
+                    write!(out, ", {}>", konst.name.to_fully_qualified());
+                }
+                None => write!(out, ", {}>", &a.len),
             }
-            write!(out, "(");
-            for (i, arg) in f.args.iter().enumerate() {
+        }
+        Type::Tuple(t) => {
+            write!(out, "::std::tuple<");
+            for (i, elem) in t.elems.iter().enumerate() {
                 if i > 0 {
                     write!(out, ", ");
                 }
-                write_type(out, &arg.ty);
+                write_type(out, elem);
             }
-            write!(out, ")>");
-        }
-        Type::Array(a) => {
-            write!(out, "::std::array<");
-            write_type(out, &a.inner);
-            write!(out, ", {}>", &a.len);
+            write!(out, ">");
         }
         Type::Void(_) => unreachable!(),
     }
 }
 
+fn write_function_type(out: &mut OutFile, sig: &Signature) {
+    match &sig.ret {
+        Some(ret) => write_type(out, ret),
+        None => write!(out, "void"),
+    }
+    write!(out, "(");
+    for (i, arg) in sig.args.iter().enumerate() {
+        if i > 0 {
+            write!(out, ", ");
+        }
+        write_type(out, &arg.ty);
+    }
+    write!(out, ")");
+}
+
+fn write_tuple_repr(out: &mut OutFile, tuple: &Tuple) {
+    let arity = tuple.elems.len();
+    out.builtin.tuple_repr.insert(arity);
+    write!(out, "::rust::repr::Tuple{}<", arity);
+    for (i, elem) in tuple.elems.iter().enumerate() {
+        if i > 0 {
+            write!(out, ", ");
+        }
+        write_type(out, elem);
+    }
+    write!(out, ">");
+}
+
 fn write_atom(out: &mut OutFile, atom: Atom) {
     match atom {
         Bool => write!(out, "bool"),
@@ -1335,6 +2143,7 @@ fn write_space_after_type(out: &mut OutFile, ty: &Type) {
     match ty {
         Type::Ident(_)
         | Type::RustBox(_)
+        | Type::RustArc(_)
         | Type::UniquePtr(_)
         | Type::SharedPtr(_)
         | Type::WeakPtr(_)
@@ -1343,7 +2152,9 @@ fn write_space_after_type(out: &mut OutFile, ty: &Type) {
         | Type::RustVec(_)
         | Type::SliceRef(_)
         | Type::Fn(_)
-        | Type::Array(_) => write!(out, " "),
+        | Type::Array(_)
+        | Type::Tuple(_)
+        | Type::DynFn(_) => write!(out, " "),
         Type::Ref(_) | Type::Ptr(_) => {}
         Type::Void(_) => unreachable!(),
     }
@@ -1397,7 +2208,12 @@ impl<'a> ToMangled for UniquePtr<'a> {
     }
 }
 
-fn write_generic_instantiations(out: &mut OutFile) {
+fn write_generic_instantiations<'a>(
+    out: &mut OutFile<'a>,
+    impl_cfgs: &'a Map<ImplKey<'a>, CfgExpr>,
+) {
+    let impl_cfg = |impl_key| impl_cfgs.get(impl_key).unwrap_or(&CfgExpr::Unconditional);
+
     if out.header {
         return;
     }
@@ -1407,27 +2223,33 @@ fn write_generic_instantiations(out: &mut OutFile) {
     out.begin_block(Block::ExternC);
     for impl_key in out.types.impls.keys() {
         out.next_section();
+        out.begin_cfg(impl_cfg(impl_key));
         match *impl_key {
             ImplKey::RustBox(ident) => write_rust_box_extern(out, ident),
+            ImplKey::RustArc(ident) => write_rust_arc_extern(out, ident),
             ImplKey::RustVec(ident) => write_rust_vec_extern(out, ident),
             ImplKey::UniquePtr(ident) => write_unique_ptr(out, ident),
             ImplKey::SharedPtr(ident) => write_shared_ptr(out, ident),
             ImplKey::WeakPtr(ident) => write_weak_ptr(out, ident),
             ImplKey::CxxVector(ident) => write_cxx_vector(out, ident),
         }
+        out.end_cfg(impl_cfg(impl_key));
     }
     out.end_block(Block::ExternC);
 
     out.begin_block(Block::Namespace("rust"));
-    out.begin_block(Block::InlineNamespace("cxxbridge1"));
+    out.begin_block(Block::InlineNamespace(CXXBRIDGE));
     for impl_key in out.types.impls.keys() {
+        out.begin_cfg(impl_cfg(impl_key));
         match *impl_key {
             ImplKey::RustBox(ident) => write_rust_box_impl(out, ident),
+            ImplKey::RustArc(ident) => write_rust_arc_impl(out, ident),
             ImplKey::RustVec(ident) => write_rust_vec_impl(out, ident),
             _ => {}
         }
+        out.end_cfg(impl_cfg(impl_key));
     }
-    out.end_block(Block::InlineNamespace("cxxbridge1"));
+    out.end_block(Block::InlineNamespace(CXXBRIDGE));
     out.end_block(Block::Namespace("rust"));
 }
 
@@ -1438,18 +2260,35 @@ fn write_rust_box_extern(out: &mut OutFile, key: NamedImplKey) {
 
     writeln!(
         out,
-        "{} *cxxbridge1$box${}$alloc() noexcept;",
-        inner, instance,
+        "{} *{}$box${}$alloc() noexcept;",
+        inner, CXXBRIDGE, instance,
     );
     writeln!(
         out,
-        "void cxxbridge1$box${}$dealloc({} *) noexcept;",
-        instance, inner,
+        "void {}$box${}$dealloc({} *) noexcept;",
+        CXXBRIDGE, instance, inner,
     );
     writeln!(
         out,
-        "void cxxbridge1$box${}$drop(::rust::Box<{}> *ptr) noexcept;",
-        instance, inner,
+        "void {}$box${}$drop(::rust::Box<{}> *ptr) noexcept;",
+        CXXBRIDGE, instance, inner,
+    );
+}
+
+fn write_rust_arc_extern(out: &mut OutFile, key: NamedImplKey) {
+    let resolve = out.types.resolve(&key);
+    let inner = resolve.name.to_fully_qualified();
+    let instance = resolve.name.to_symbol();
+
+    writeln!(
+        out,
+        "void {}$arc${}$increment({} const *) noexcept;",
+        CXXBRIDGE, instance, inner,
+    );
+    writeln!(
+        out,
+        "void {}$arc${}$drop({} const *) noexcept;",
+        CXXBRIDGE, instance, inner,
     );
 }
 
@@ -1462,43 +2301,43 @@ fn write_rust_vec_extern(out: &mut OutFile, key: NamedImplKey) {
 
     writeln!(
         out,
-        "void cxxbridge1$rust_vec${}$new(::rust::Vec<{}> const *ptr) noexcept;",
-        instance, inner,
+        "void {}$rust_vec${}$new(::rust::Vec<{}> const *ptr) noexcept;",
+        CXXBRIDGE, instance, inner,
     );
     writeln!(
         out,
-        "void cxxbridge1$rust_vec${}$drop(::rust::Vec<{}> *ptr) noexcept;",
-        instance, inner,
+        "void {}$rust_vec${}$drop(::rust::Vec<{}> *ptr) noexcept;",
+        CXXBRIDGE, instance, inner,
     );
     writeln!(
         out,
-        "::std::size_t cxxbridge1$rust_vec${}$len(::rust::Vec<{}> const *ptr) noexcept;",
-        instance, inner,
+        "::std::size_t {}$rust_vec${}$len(::rust::Vec<{}> const *ptr) noexcept;",
+        CXXBRIDGE, instance, inner,
     );
     writeln!(
         out,
-        "::std::size_t cxxbridge1$rust_vec${}$capacity(::rust::Vec<{}> const *ptr) noexcept;",
-        instance, inner,
+        "::std::size_t {}$rust_vec${}$capacity(::rust::Vec<{}> const *ptr) noexcept;",
+        CXXBRIDGE, instance, inner,
     );
     writeln!(
         out,
-        "{} const *cxxbridge1$rust_vec${}$data(::rust::Vec<{0}> const *ptr) noexcept;",
-        inner, instance,
+        "{} const *{}$rust_vec${}$data(::rust::Vec<{0}> const *ptr) noexcept;",
+        inner, CXXBRIDGE, instance,
     );
     writeln!(
         out,
-        "void cxxbridge1$rust_vec${}$reserve_total(::rust::Vec<{}> *ptr, ::std::size_t new_cap) noexcept;",
-        instance, inner,
+        "void {}$rust_vec${}$reserve_total(::rust::Vec<{}> *ptr, ::std::size_t new_cap) noexcept;",
+        CXXBRIDGE, instance, inner,
     );
     writeln!(
         out,
-        "void cxxbridge1$rust_vec${}$set_len(::rust::Vec<{}> *ptr, ::std::size_t len) noexcept;",
-        instance, inner,
+        "void {}$rust_vec${}$set_len(::rust::Vec<{}> *ptr, ::std::size_t len) noexcept;",
+        CXXBRIDGE, instance, inner,
     );
     writeln!(
         out,
-        "void cxxbridge1$rust_vec${}$truncate(::rust::Vec<{}> *ptr, ::std::size_t len) noexcept;",
-        instance, inner,
+        "void {}$rust_vec${}$truncate(::rust::Vec<{}> *ptr, ::std::size_t len) noexcept;",
+        CXXBRIDGE, instance, inner,
     );
 }
 
@@ -1514,7 +2353,7 @@ fn write_rust_box_impl(out: &mut OutFile, key: NamedImplKey) {
         "{} *Box<{}>::allocation::alloc() noexcept {{",
         inner, inner,
     );
-    writeln!(out, "  return cxxbridge1$box${}$alloc();", instance);
+    writeln!(out, "  return {}$box${}$alloc();", CXXBRIDGE, instance);
     writeln!(out, "}}");
 
     writeln!(out, "template <>");
@@ -1524,13 +2363,35 @@ fn write_rust_box_impl(out: &mut OutFile, key: NamedImplKey) {
         "void Box<{}>::allocation::dealloc({} *ptr) noexcept {{",
         inner, inner,
     );
-    writeln!(out, "  cxxbridge1$box${}$dealloc(ptr);", instance);
+    writeln!(out, "  {}$box${}$dealloc(ptr);", CXXBRIDGE, instance);
     writeln!(out, "}}");
 
     writeln!(out, "template <>");
     begin_function_definition(out);
     writeln!(out, "void Box<{}>::drop() noexcept {{", inner);
-    writeln!(out, "  cxxbridge1$box${}$drop(this);", instance);
+    writeln!(out, "  {}$box${}$drop(this);", CXXBRIDGE, instance);
+    writeln!(out, "}}");
+}
+
+fn write_rust_arc_impl(out: &mut OutFile, key: NamedImplKey) {
+    let resolve = out.types.resolve(&key);
+    let inner = resolve.name.to_fully_qualified();
+    let instance = resolve.name.to_symbol();
+
+    writeln!(out, "template <>");
+    begin_function_definition(out);
+    writeln!(out, "void Arc<{}>::inc_ref() noexcept {{", inner);
+    writeln!(
+        out,
+        "  {}$arc${}$increment(this->ptr);",
+        CXXBRIDGE, instance
+    );
+    writeln!(out, "}}");
+
+    writeln!(out, "template <>");
+    begin_function_definition(out);
+    writeln!(out, "void Arc<{}>::drop() noexcept {{", inner);
+    writeln!(out, "  {}$arc${}$drop(this->ptr);", CXXBRIDGE, instance);
     writeln!(out, "}}");
 }
 
@@ -1544,13 +2405,17 @@ fn write_rust_vec_impl(out: &mut OutFile, key: NamedImplKey) {
     writeln!(out, "template <>");
     begin_function_definition(out);
     writeln!(out, "Vec<{}>::Vec() noexcept {{", inner);
-    writeln!(out, "  cxxbridge1$rust_vec${}$new(this);", instance);
+    writeln!(out, "  {}$rust_vec${}$new(this);", CXXBRIDGE, instance);
     writeln!(out, "}}");
 
     writeln!(out, "template <>");
     begin_function_definition(out);
     writeln!(out, "void Vec<{}>::drop() noexcept {{", inner);
-    writeln!(out, "  return cxxbridge1$rust_vec${}$drop(this);", instance);
+    writeln!(
+        out,
+        "  return {}$rust_vec${}$drop(this);",
+        CXXBRIDGE, instance
+    );
     writeln!(out, "}}");
 
     writeln!(out, "template <>");
@@ -1560,7 +2425,11 @@ fn write_rust_vec_impl(out: &mut OutFile, key: NamedImplKey) {
         "::std::size_t Vec<{}>::size() const noexcept {{",
         inner,
     );
-    writeln!(out, "  return cxxbridge1$rust_vec${}$len(this);", instance);
+    writeln!(
+        out,
+        "  return {}$rust_vec${}$len(this);",
+        CXXBRIDGE, instance
+    );
     writeln!(out, "}}");
 
     writeln!(out, "template <>");
@@ -1572,15 +2441,19 @@ fn write_rust_vec_impl(out: &mut OutFile, key: NamedImplKey) {
     );
     writeln!(
         out,
-        "  return cxxbridge1$rust_vec${}$capacity(this);",
-        instance,
+        "  return {}$rust_vec${}$capacity(this);",
+        CXXBRIDGE, instance,
     );
     writeln!(out, "}}");
 
     writeln!(out, "template <>");
     begin_function_definition(out);
     writeln!(out, "{} const *Vec<{0}>::data() const noexcept {{", inner);
-    writeln!(out, "  return cxxbridge1$rust_vec${}$data(this);", instance);
+    writeln!(
+        out,
+        "  return {}$rust_vec${}$data(this);",
+        CXXBRIDGE, instance
+    );
     writeln!(out, "}}");
 
     writeln!(out, "template <>");
@@ -1592,8 +2465,8 @@ fn write_rust_vec_impl(out: &mut OutFile, key: NamedImplKey) {
     );
     writeln!(
         out,
-        "  return cxxbridge1$rust_vec${}$reserve_total(this, new_cap);",
-        instance,
+        "  return {}$rust_vec${}$reserve_total(this, new_cap);",
+        CXXBRIDGE, instance,
     );
     writeln!(out, "}}");
 
@@ -1606,8 +2479,8 @@ fn write_rust_vec_impl(out: &mut OutFile, key: NamedImplKey) {
     );
     writeln!(
         out,
-        "  return cxxbridge1$rust_vec${}$set_len(this, len);",
-        instance,
+        "  return {}$rust_vec${}$set_len(this, len);",
+        CXXBRIDGE, instance,
     );
     writeln!(out, "}}");
 
@@ -1616,8 +2489,8 @@ fn write_rust_vec_impl(out: &mut OutFile, key: NamedImplKey) {
     writeln!(out, "void Vec<{}>::truncate(::std::size_t len) {{", inner,);
     writeln!(
         out,
-        "  return cxxbridge1$rust_vec${}$truncate(this, len);",
-        instance,
+        "  return {}$rust_vec${}$truncate(this, len);",
+        CXXBRIDGE, instance,
     );
     writeln!(out, "}}");
 }
@@ -1639,13 +2512,28 @@ fn write_unique_ptr_common(out: &mut OutFile, ty: UniquePtr) {
         // know at code generation time, so we generate both C++ and Rust side
         // bindings for a "new" method anyway. But the Rust code can't be called
         // for Opaque types because the 'new' method is not implemented.
-        UniquePtr::Ident(ident) => out.types.is_maybe_trivial(ident),
+        // Objects owned through a non-default deleter cannot be allocated by
+        // us with `new`.
+        UniquePtr::Ident(ident) => {
+            out.types.is_maybe_trivial(ident) && !out.types.unique_ptr_deleters.contains_key(ident)
+        }
         UniquePtr::CxxVector(_) => false,
     };
 
+    let deleter = match ty {
+        UniquePtr::Ident(ident) => unique_ptr_deleter(out.types, ident),
+        UniquePtr::CxxVector(_) => None,
+    };
+    let unique_ptr = match &deleter {
+        Some(deleter) => format!("::std::unique_ptr<{}, {}>", inner, deleter),
+        None => format!("::std::unique_ptr<{}>", inner),
+    };
+
     let conditional_delete = match ty {
         UniquePtr::Ident(ident) => {
-            !out.types.structs.contains_key(ident) && !out.types.enums.contains_key(ident)
+            !out.types.structs.contains_key(ident)
+                && !out.types.enums.contains_key(ident)
+                && deleter.is_none()
         }
         UniquePtr::CxxVector(_) => false,
     };
@@ -1662,70 +2550,74 @@ fn write_unique_ptr_common(out: &mut OutFile, ty: UniquePtr) {
             inner, definition,
         );
     }
+    let size_message = match &deleter {
+        Some(deleter) => format!("{} must be a stateless deleter", deleter),
+        None => String::new(),
+    };
     writeln!(
         out,
-        "static_assert(sizeof(::std::unique_ptr<{}>) == sizeof(void *), \"\");",
-        inner,
+        "static_assert(sizeof({}) == sizeof(void *), \"{}\");",
+        unique_ptr, size_message,
     );
     writeln!(
         out,
-        "static_assert(alignof(::std::unique_ptr<{}>) == alignof(void *), \"\");",
-        inner,
+        "static_assert(alignof({}) == alignof(void *), \"\");",
+        unique_ptr,
     );
     begin_function_definition(out);
     writeln!(
         out,
-        "void cxxbridge1$unique_ptr${}$null(::std::unique_ptr<{}> *ptr) noexcept {{",
-        instance, inner,
+        "void {}$unique_ptr${}$null({} *ptr) noexcept {{",
+        CXXBRIDGE, instance, unique_ptr,
     );
-    writeln!(out, "  ::new (ptr) ::std::unique_ptr<{}>();", inner);
+    writeln!(out, "  ::new (ptr) {}();", unique_ptr);
     writeln!(out, "}}");
     if can_construct_from_value {
         out.builtin.maybe_uninit = true;
         begin_function_definition(out);
         writeln!(
             out,
-            "{} *cxxbridge1$unique_ptr${}$uninit(::std::unique_ptr<{}> *ptr) noexcept {{",
-            inner, instance, inner,
+            "{} *{}$unique_ptr${}$uninit({} *ptr) noexcept {{",
+            inner, CXXBRIDGE, instance, unique_ptr,
         );
         writeln!(
             out,
             "  {} *uninit = reinterpret_cast<{} *>(new ::rust::MaybeUninit<{}>);",
             inner, inner, inner,
         );
-        writeln!(out, "  ::new (ptr) ::std::unique_ptr<{}>(uninit);", inner);
+        writeln!(out, "  ::new (ptr) {}(uninit);", unique_ptr);
         writeln!(out, "  return uninit;");
         writeln!(out, "}}");
     }
     begin_function_definition(out);
     writeln!(
         out,
-        "void cxxbridge1$unique_ptr${}$raw(::std::unique_ptr<{}> *ptr, {} *raw) noexcept {{",
-        instance, inner, inner,
+        "void {}$unique_ptr${}$raw({} *ptr, {} *raw) noexcept {{",
+        CXXBRIDGE, instance, unique_ptr, inner,
     );
-    writeln!(out, "  ::new (ptr) ::std::unique_ptr<{}>(raw);", inner);
+    writeln!(out, "  ::new (ptr) {}(raw);", unique_ptr);
     writeln!(out, "}}");
     begin_function_definition(out);
     writeln!(
         out,
-        "{} const *cxxbridge1$unique_ptr${}$get(::std::unique_ptr<{}> const &ptr) noexcept {{",
-        inner, instance, inner,
+        "{} const *{}$unique_ptr${}$get({} const &ptr) noexcept {{",
+        inner, CXXBRIDGE, instance, unique_ptr,
     );
     writeln!(out, "  return ptr.get();");
     writeln!(out, "}}");
     begin_function_definition(out);
     writeln!(
         out,
-        "{} *cxxbridge1$unique_ptr${}$release(::std::unique_ptr<{}> &ptr) noexcept {{",
-        inner, instance, inner,
+        "{} *{}$unique_ptr${}$release({} &ptr) noexcept {{",
+        inner, CXXBRIDGE, instance, unique_ptr,
     );
     writeln!(out, "  return ptr.release();");
     writeln!(out, "}}");
     begin_function_definition(out);
     writeln!(
         out,
-        "void cxxbridge1$unique_ptr${}$drop(::std::unique_ptr<{}> *ptr) noexcept {{",
-        instance, inner,
+        "void {}$unique_ptr${}$drop({} *ptr) noexcept {{",
+        CXXBRIDGE, instance, unique_ptr,
     );
     if conditional_delete {
         out.builtin.deleter_if = true;
@@ -1740,12 +2632,26 @@ fn write_unique_ptr_common(out: &mut OutFile, ty: UniquePtr) {
     writeln!(out, "}}");
 }
 
+// UniquePtr of an opaque Rust type owns a Box allocation, released on the C++
+// side through rust::BoxDeleter rather than std::default_delete. Other types
+// may have had a deleter declared by `impl UniquePtr<T, Deleter> {}`.
+fn unique_ptr_deleter(types: &Types, inner: &Ident) -> Option<String> {
+    if types.rust.contains(inner) {
+        Some("::rust::BoxDeleter".to_owned())
+    } else if let Some(Type::Ident(deleter)) = types.unique_ptr_deleters.get(inner) {
+        Some(types.resolve(deleter).name.to_fully_qualified())
+    } else {
+        None
+    }
+}
+
 fn write_shared_ptr(out: &mut OutFile, key: NamedImplKey) {
     let ident = key.rust;
     let resolve = out.types.resolve(ident);
     let inner = resolve.name.to_fully_qualified();
     let instance = resolve.name.to_symbol();
 
+    out.include.cstddef = true;
     out.include.new = true;
     out.include.utility = true;
 
@@ -1768,8 +2674,8 @@ fn write_shared_ptr(out: &mut OutFile, key: NamedImplKey) {
     begin_function_definition(out);
     writeln!(
         out,
-        "void cxxbridge1$shared_ptr${}$null(::std::shared_ptr<{}> *ptr) noexcept {{",
-        instance, inner,
+        "void {}$shared_ptr${}$null(::std::shared_ptr<{}> *ptr) noexcept {{",
+        CXXBRIDGE, instance, inner,
     );
     writeln!(out, "  ::new (ptr) ::std::shared_ptr<{}>();", inner);
     writeln!(out, "}}");
@@ -1778,8 +2684,8 @@ fn write_shared_ptr(out: &mut OutFile, key: NamedImplKey) {
         begin_function_definition(out);
         writeln!(
             out,
-            "{} *cxxbridge1$shared_ptr${}$uninit(::std::shared_ptr<{}> *ptr) noexcept {{",
-            inner, instance, inner,
+            "{} *{}$shared_ptr${}$uninit(::std::shared_ptr<{}> *ptr) noexcept {{",
+            inner, CXXBRIDGE, instance, inner,
         );
         writeln!(
             out,
@@ -1793,7 +2699,8 @@ fn write_shared_ptr(out: &mut OutFile, key: NamedImplKey) {
     begin_function_definition(out);
     writeln!(
         out,
-        "void cxxbridge1$shared_ptr${}$clone(::std::shared_ptr<{}> const &self, ::std::shared_ptr<{}> *ptr) noexcept {{",
+        "void {}$shared_ptr${}$clone(::std::shared_ptr<{}> const &self, ::std::shared_ptr<{}> *ptr) noexcept {{",
+        CXXBRIDGE,
         instance, inner, inner,
     );
     writeln!(out, "  ::new (ptr) ::std::shared_ptr<{}>(self);", inner);
@@ -1801,19 +2708,78 @@ fn write_shared_ptr(out: &mut OutFile, key: NamedImplKey) {
     begin_function_definition(out);
     writeln!(
         out,
-        "{} const *cxxbridge1$shared_ptr${}$get(::std::shared_ptr<{}> const &self) noexcept {{",
-        inner, instance, inner,
+        "{} const *{}$shared_ptr${}$get(::std::shared_ptr<{}> const &self) noexcept {{",
+        inner, CXXBRIDGE, instance, inner,
     );
     writeln!(out, "  return self.get();");
     writeln!(out, "}}");
     begin_function_definition(out);
     writeln!(
         out,
-        "void cxxbridge1$shared_ptr${}$drop(::std::shared_ptr<{}> *self) noexcept {{",
-        instance, inner,
+        "void {}$shared_ptr${}$drop(::std::shared_ptr<{}> *self) noexcept {{",
+        CXXBRIDGE, instance, inner,
     );
     writeln!(out, "  self->~shared_ptr();");
     writeln!(out, "}}");
+    begin_function_definition(out);
+    writeln!(
+        out,
+        "::std::size_t {}$shared_ptr${}$use_count(::std::shared_ptr<{}> const &self) noexcept {{",
+        CXXBRIDGE, instance, inner,
+    );
+    writeln!(
+        out,
+        "  return static_cast<::std::size_t>(self.use_count());",
+    );
+    writeln!(out, "}}");
+    begin_function_definition(out);
+    writeln!(
+        out,
+        "bool {}$shared_ptr${}$owner_before(::std::shared_ptr<{}> const &self, ::std::shared_ptr<void> *alias) noexcept {{",
+        CXXBRIDGE,
+        instance, inner,
+    );
+    writeln!(out, "  bool before = self.owner_before(*alias);");
+    writeln!(out, "  alias->~shared_ptr();");
+    writeln!(out, "  return before;");
+    writeln!(out, "}}");
+    begin_function_definition(out);
+    writeln!(
+        out,
+        "void {}$shared_ptr${}$alias(::std::shared_ptr<{}> const &self, void *ptr, ::std::shared_ptr<void> *alias) noexcept {{",
+        CXXBRIDGE,
+        instance, inner,
+    );
+    writeln!(out, "  ::new (alias) ::std::shared_ptr<void>(self, ptr);");
+    writeln!(out, "}}");
+    begin_function_definition(out);
+    writeln!(
+        out,
+        "void {}$shared_ptr${}$from_alias(::std::shared_ptr<void> *alias, ::std::shared_ptr<{}> *ptr) noexcept {{",
+        CXXBRIDGE,
+        instance, inner,
+    );
+    writeln!(
+        out,
+        "  ::new (ptr) ::std::shared_ptr<{}>(*alias, static_cast<{} *>(alias->get()));",
+        inner, inner,
+    );
+    writeln!(out, "  alias->~shared_ptr();");
+    writeln!(out, "}}");
+    out.builtin.shared_from_this = true;
+    begin_function_definition(out);
+    writeln!(
+        out,
+        "void {}$shared_ptr${}$shared_from_this({} const &self, ::std::shared_ptr<{}> *ptr) noexcept {{",
+        CXXBRIDGE,
+        instance, inner, inner,
+    );
+    writeln!(
+        out,
+        "  ::new (ptr) ::std::shared_ptr<{}>(::rust::detail::shared_from_this(self, 0));",
+        inner,
+    );
+    writeln!(out, "}}");
 }
 
 fn write_weak_ptr(out: &mut OutFile, key: NamedImplKey) {
@@ -1821,6 +2787,7 @@ fn write_weak_ptr(out: &mut OutFile, key: NamedImplKey) {
     let inner = resolve.name.to_fully_qualified();
     let instance = resolve.name.to_symbol();
 
+    out.include.cstddef = true;
     out.include.new = true;
     out.include.utility = true;
 
@@ -1836,15 +2803,16 @@ fn write_weak_ptr(out: &mut OutFile, key: NamedImplKey) {
     );
     writeln!(
         out,
-        "void cxxbridge1$weak_ptr${}$null(::std::weak_ptr<{}> *ptr) noexcept {{",
-        instance, inner,
+        "void {}$weak_ptr${}$null(::std::weak_ptr<{}> *ptr) noexcept {{",
+        CXXBRIDGE, instance, inner,
     );
     writeln!(out, "  ::new (ptr) ::std::weak_ptr<{}>();", inner);
     writeln!(out, "}}");
     begin_function_definition(out);
     writeln!(
         out,
-        "void cxxbridge1$weak_ptr${}$clone(::std::weak_ptr<{}> const &self, ::std::weak_ptr<{}> *ptr) noexcept {{",
+        "void {}$weak_ptr${}$clone(::std::weak_ptr<{}> const &self, ::std::weak_ptr<{}> *ptr) noexcept {{",
+        CXXBRIDGE,
         instance, inner, inner,
     );
     writeln!(out, "  ::new (ptr) ::std::weak_ptr<{}>(self);", inner);
@@ -1852,7 +2820,8 @@ fn write_weak_ptr(out: &mut OutFile, key: NamedImplKey) {
     begin_function_definition(out);
     writeln!(
         out,
-        "void cxxbridge1$weak_ptr${}$downgrade(::std::shared_ptr<{}> const &shared, ::std::weak_ptr<{}> *weak) noexcept {{",
+        "void {}$weak_ptr${}$downgrade(::std::shared_ptr<{}> const &shared, ::std::weak_ptr<{}> *weak) noexcept {{",
+        CXXBRIDGE,
         instance, inner, inner,
     );
     writeln!(out, "  ::new (weak) ::std::weak_ptr<{}>(shared);", inner);
@@ -1860,7 +2829,8 @@ fn write_weak_ptr(out: &mut OutFile, key: NamedImplKey) {
     begin_function_definition(out);
     writeln!(
         out,
-        "void cxxbridge1$weak_ptr${}$upgrade(::std::weak_ptr<{}> const &weak, ::std::shared_ptr<{}> *shared) noexcept {{",
+        "void {}$weak_ptr${}$upgrade(::std::weak_ptr<{}> const &weak, ::std::shared_ptr<{}> *shared) noexcept {{",
+        CXXBRIDGE,
         instance, inner, inner,
     );
     writeln!(
@@ -1872,11 +2842,107 @@ fn write_weak_ptr(out: &mut OutFile, key: NamedImplKey) {
     begin_function_definition(out);
     writeln!(
         out,
-        "void cxxbridge1$weak_ptr${}$drop(::std::weak_ptr<{}> *self) noexcept {{",
-        instance, inner,
+        "void {}$weak_ptr${}$drop(::std::weak_ptr<{}> *self) noexcept {{",
+        CXXBRIDGE, instance, inner,
     );
     writeln!(out, "  self->~weak_ptr();");
     writeln!(out, "}}");
+    begin_function_definition(out);
+    writeln!(
+        out,
+        "::std::size_t {}$weak_ptr${}$use_count(::std::weak_ptr<{}> const &self) noexcept {{",
+        CXXBRIDGE, instance, inner,
+    );
+    writeln!(
+        out,
+        "  return static_cast<::std::size_t>(self.use_count());",
+    );
+    writeln!(out, "}}");
+}
+
+fn write_cxx_type_bases<'a>(out: &mut OutFile<'a>, ety: &'a ExternType) {
+    if ety.bases.is_empty() {
+        return;
+    }
+
+    out.set_namespace(Default::default());
+    out.begin_cfg(&ety.cfg);
+    out.begin_block(Block::ExternC);
+    let resolve = out.types.resolve(&ety.name.rust);
+    let derived = resolve.name.to_fully_qualified();
+    let derived_instance = resolve.name.to_symbol();
+
+    for base in &ety.bases {
+        let resolve = out.types.resolve(base);
+        let base = resolve.name.to_fully_qualified();
+        let instance = format!("{}${}", derived_instance, resolve.name.to_symbol());
+
+        out.next_section();
+        out.include.memory = true;
+        out.include.new = true;
+        out.include.type_traits = true;
+        out.builtin.base_cast = true;
+        writeln!(
+            out,
+            "{} const *{}${}$upcast({} const &self) noexcept {{",
+            base, CXXBRIDGE, instance, derived,
+        );
+        writeln!(out, "  return &self;");
+        writeln!(out, "}}");
+        writeln!(
+            out,
+            "{} *{}${}$upcast_mut({} &self) noexcept {{",
+            base, CXXBRIDGE, instance, derived,
+        );
+        writeln!(out, "  return &self;");
+        writeln!(out, "}}");
+        writeln!(
+            out,
+            "{} *{}$unique_ptr${}$upcast({} *self) noexcept {{",
+            base, CXXBRIDGE, instance, derived,
+        );
+        writeln!(
+            out,
+            "  return ::rust::detail::upcast_unique<{}>(self, ::std::has_virtual_destructor<{}>{{}});",
+            base, base,
+        );
+        writeln!(out, "}}");
+        writeln!(
+            out,
+            "void {}$shared_ptr${}$upcast(::std::shared_ptr<{}> const &self, ::std::shared_ptr<{}> *ptr) noexcept {{",
+            CXXBRIDGE,
+            instance, derived, base,
+        );
+        writeln!(out, "  ::new (ptr) ::std::shared_ptr<{}>(self);", base);
+        writeln!(out, "}}");
+        writeln!(
+            out,
+            "void {}$shared_ptr${}$downcast(::std::shared_ptr<{}> const &self, ::std::shared_ptr<{}> *ptr) noexcept {{",
+            CXXBRIDGE,
+            instance, base, derived,
+        );
+        writeln!(
+            out,
+            "  ::new (ptr) ::std::shared_ptr<{}>(::rust::detail::dynamic_pointer_cast<{}>(self, ::std::is_polymorphic<{}>{{}}));",
+            derived, derived, base,
+        );
+        writeln!(out, "}}");
+        writeln!(
+            out,
+            "void {}$shared_ptr${}$static_downcast(::std::shared_ptr<{}> const &self, ::std::shared_ptr<{}> *ptr) noexcept {{",
+            CXXBRIDGE,
+            instance, base, derived,
+        );
+        writeln!(
+            out,
+            "  ::new (ptr) ::std::shared_ptr<{}>(::rust::detail::static_pointer_cast<{}>(self, 0));",
+            derived, derived,
+        );
+        writeln!(out, "}}");
+    }
+
+    out.end_block(Block::ExternC);
+    out.end_cfg(&ety.cfg);
 }
 
 fn write_cxx_vector(out: &mut OutFile, key: NamedImplKey) {
@@ -1890,8 +2956,8 @@ fn write_cxx_vector(out: &mut OutFile, key: NamedImplKey) {
 
     writeln!(
         out,
-        "::std::size_t cxxbridge1$std$vector${}$size(::std::vector<{}> const &s) noexcept {{",
-        instance, inner,
+        "::std::size_t {}$std$vector${}$size(::std::vector<{}> const &s) noexcept {{",
+        CXXBRIDGE, instance, inner,
     );
     writeln!(out, "  return s.size();");
     writeln!(out, "}}");
@@ -1899,8 +2965,8 @@ fn write_cxx_vector(out: &mut OutFile, key: NamedImplKey) {
     begin_function_definition(out);
     writeln!(
         out,
-        "{} *cxxbridge1$std$vector${}$get_unchecked(::std::vector<{}> *s, ::std::size_t pos) noexcept {{",
-        inner, instance, inner,
+        "{} *{}$std$vector${}$get_unchecked(::std::vector<{}> *s, ::std::size_t pos) noexcept {{",
+        inner, CXXBRIDGE, instance, inner,
     );
     writeln!(out, "  return &(*s)[pos];");
     writeln!(out, "}}");
@@ -1909,8 +2975,8 @@ fn write_cxx_vector(out: &mut OutFile, key: NamedImplKey) {
         begin_function_definition(out);
         writeln!(
             out,
-            "void cxxbridge1$std$vector${}$push_back(::std::vector<{}> *v, {} *value) noexcept {{",
-            instance, inner, inner,
+            "void {}$std$vector${}$push_back(::std::vector<{}> *v, {} *value) noexcept {{",
+            CXXBRIDGE, instance, inner, inner,
         );
         writeln!(out, "  v->push_back(::std::move(*value));");
         writeln!(out, "  ::rust::destroy(value);");
@@ -1919,8 +2985,8 @@ fn write_cxx_vector(out: &mut OutFile, key: NamedImplKey) {
         begin_function_definition(out);
         writeln!(
             out,
-            "void cxxbridge1$std$vector${}$pop_back(::std::vector<{}> *v, {} *out) noexcept {{",
-            instance, inner, inner,
+            "void {}$std$vector${}$pop_back(::std::vector<{}> *v, {} *out) noexcept {{",
+            CXXBRIDGE, instance, inner, inner,
         );
         writeln!(out, "  ::new (out) {}(::std::move(v->back()));", inner);
         writeln!(out, "  v->pop_back();");
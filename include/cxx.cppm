@@ -0,0 +1,51 @@
+// C++20 module interface unit for the `rust` runtime types of "rust/cxx.h",
+// imported by the module interfaces that cxxbridge generates for each bridge.
+// Compile it with the directory containing rust/cxx.h on the include path.
+module;
+#include "rust/cxx.h"
+
+export module cxx;
+
+export namespace rust {
+using rust::String;
+using rust::Str;
+using rust::Slice;
+using rust::Box;
+using rust::BoxDeleter;
+using rust::Arc;
+using rust::Vec;
+using rust::Fn;
+using rust::FnMut;
+using rust::Error;
+using rust::Panic;
+using rust::Expected;
+using rust::Result;
+using rust::isize;
+using rust::Opaque;
+using rust::size_of;
+using rust::align_of;
+using rust::IsRelocatable;
+using rust::operator<<;
+
+using rust::u8;
+using rust::u16;
+using rust::u32;
+using rust::u64;
+using rust::usize;
+using rust::i8;
+using rust::i16;
+using rust::i32;
+using rust::i64;
+using rust::f32;
+using rust::f64;
+
+using rust::string;
+using rust::str;
+using rust::slice;
+using rust::box;
+using rust::arc;
+using rust::vec;
+using rust::error;
+using rust::fn;
+using rust::is_relocatable;
+} // namespace rust
@@ -19,6 +19,12 @@
 #else
 #include <sys/types.h>
 #endif
+#if !defined(CXXBRIDGE1_RUST_FNMUT)
+#include <functional>
+#endif
+#if !defined(CXXBRIDGE1_RUST_BOX) || !defined(CXXBRIDGE1_RUST_FNMUT)
+#include <memory>
+#endif
 
 #ifndef CXX_EXPORT
 #ifdef _MSC_VER
@@ -264,6 +270,8 @@ private:
 #endif // CXXBRIDGE1_RUST_SLICE
 
 #ifndef CXXBRIDGE1_RUST_BOX
+struct BoxDeleter;
+
 // https://cxx.rs/binding/box.html
 template <typename T>
 class Box final {
@@ -298,6 +306,13 @@ public:
 
   T *into_raw() noexcept;
 
+  // Transfers ownership of the allocation to a std::unique_ptr, which frees
+  // it through Rust's Box when it goes out of scope.
+  std::unique_ptr<T, BoxDeleter> into_unique_ptr() noexcept;
+
+  // Important: requires that the unique_ptr is not null.
+  static Box from_unique_ptr(std::unique_ptr<T, BoxDeleter>) noexcept;
+
   /* Deprecated */ using value_type = element_type;
 
 private:
@@ -310,8 +325,53 @@ private:
 
   T *ptr;
 };
+
+// Deleter for a std::unique_ptr that owns an allocation made by rust::Box.
+// Deliberately not final, so that std::unique_ptr stays pointer-sized.
+struct BoxDeleter {
+  template <typename T>
+  void operator()(T *) const noexcept;
+};
 #endif // CXXBRIDGE1_RUST_BOX
 
+#ifndef CXXBRIDGE1_RUST_ARC
+// https://cxx.rs/binding/arc.html
+template <typename T>
+class Arc final {
+public:
+  using element_type = T;
+
+  Arc() = delete;
+  Arc(const Arc &) noexcept;
+  Arc(Arc &&) noexcept;
+  ~Arc() noexcept;
+
+  Arc &operator=(const Arc &) &noexcept;
+  Arc &operator=(Arc &&) &noexcept;
+
+  const T *operator->() const noexcept;
+  const T &operator*() const noexcept;
+
+  void swap(Arc &) noexcept;
+
+  // Important: requires that `raw` came from an into_raw call. Do not pass a
+  // pointer from `new` or any other source.
+  static Arc from_raw(const T *) noexcept;
+
+  const T *into_raw() noexcept;
+
+private:
+  class uninit;
+  Arc(uninit) noexcept;
+  void inc_ref() noexcept;
+  void drop() noexcept;
+
+  friend void swap(Arc &lhs, Arc &rhs) noexcept { lhs.swap(rhs); }
+
+  const T *ptr;
+};
+#endif // CXXBRIDGE1_RUST_ARC
+
 #ifndef CXXBRIDGE1_RUST_VEC
 // https://cxx.rs/binding/vec.html
 template <typename T>
@@ -396,11 +456,39 @@ private:
 };
 #endif // CXXBRIDGE1_RUST_FN
 
+#ifndef CXXBRIDGE1_RUST_FNMUT
+// https://cxx.rs/binding/fn.html
+template <typename Signature>
+class FnMut;
+
+template <typename Ret, typename... Args>
+class FnMut<Ret(Args...)> final {
+public:
+  template <typename F,
+            typename = typename std::enable_if<!std::is_same<
+                typename std::decay<F>::type, FnMut>::value>::type>
+  FnMut(F &&f);
+  FnMut(FnMut &&) noexcept;
+  ~FnMut() noexcept;
+
+  FnMut &operator=(FnMut &&) &noexcept;
+
+  Ret operator()(Args... args);
+  operator std::function<Ret(Args...)>() &&;
+
+private:
+  Ret (*trampoline)(Args..., void *fn);
+  void (*drop)(void *fn) noexcept;
+  void *fn;
+};
+#endif // CXXBRIDGE1_RUST_FNMUT
+
 #ifndef CXXBRIDGE1_RUST_ERROR
 #define CXXBRIDGE1_RUST_ERROR
 // https://cxx.rs/binding/result.html
 class Error final : public std::exception {
 public:
+  explicit Error(const char *);
   Error(const Error &);
   Error(Error &&) noexcept;
   ~Error() noexcept override;
@@ -418,6 +506,153 @@ private:
 };
 #endif // CXXBRIDGE1_RUST_ERROR
 
+#ifndef CXXBRIDGE1_RUST_PANIC
+#define CXXBRIDGE1_RUST_PANIC
+// https://cxx.rs/binding/result.html#catching-panics
+class Panic final : public std::exception {
+public:
+  Panic(const Panic &);
+  Panic(Panic &&) noexcept;
+  ~Panic() noexcept override;
+
+  Panic &operator=(const Panic &) &;
+  Panic &operator=(Panic &&) &noexcept;
+
+  const char *what() const noexcept override;
+  const char *file() const noexcept;
+  std::uint32_t line() const noexcept;
+  std::uint32_t column() const noexcept;
+
+private:
+  Panic() noexcept = default;
+  friend impl<Panic>;
+  const char *msg;
+  std::size_t len;
+  const char *file_;
+  std::size_t file_len;
+  std::uint32_t line_;
+  std::uint32_t column_;
+};
+#endif // CXXBRIDGE1_RUST_PANIC
+
+#ifndef CXXBRIDGE1_RUST_EXPECTED
+// https://cxx.rs/binding/result.html#without-exceptions
+template <typename T, typename E = Error>
+class Expected final {
+public:
+  using value_type = T;
+  using error_type = E;
+
+  Expected(const T &);
+  Expected(T &&) noexcept;
+  Expected(E) noexcept;
+  Expected(const Expected &);
+  Expected(Expected &&) noexcept;
+  ~Expected() noexcept;
+
+  Expected &operator=(const Expected &) &;
+  Expected &operator=(Expected &&) &noexcept;
+
+  bool has_value() const noexcept;
+  explicit operator bool() const noexcept;
+
+  T &value() &;
+  const T &value() const &;
+  T &&value() &&;
+
+  T &operator*() & noexcept;
+  const T &operator*() const & noexcept;
+  T &&operator*() && noexcept;
+  T *operator->() noexcept;
+  const T *operator->() const noexcept;
+
+  E &error() & noexcept;
+  const E &error() const & noexcept;
+  E &&error() && noexcept;
+
+private:
+  void destroy() noexcept;
+
+  bool ok;
+  union {
+    T val;
+    E err;
+  };
+};
+
+template <typename T, typename E>
+class Expected<T &, E> final {
+public:
+  using value_type = T &;
+  using error_type = E;
+
+  Expected(T &) noexcept;
+  Expected(E) noexcept;
+  Expected(const Expected &);
+  Expected(Expected &&) noexcept;
+  ~Expected() noexcept;
+
+  Expected &operator=(const Expected &) &;
+  Expected &operator=(Expected &&) &noexcept;
+
+  bool has_value() const noexcept;
+  explicit operator bool() const noexcept;
+
+  T &value() const;
+  T &operator*() const noexcept;
+  T *operator->() const noexcept;
+
+  E &error() & noexcept;
+  const E &error() const & noexcept;
+  E &&error() && noexcept;
+
+private:
+  void destroy() noexcept;
+
+  bool ok;
+  union {
+    T *ptr;
+    E err;
+  };
+};
+
+template <typename E>
+class Expected<void, E> final {
+public:
+  using value_type = void;
+  using error_type = E;
+
+  Expected() noexcept;
+  Expected(E) noexcept;
+  Expected(const Expected &);
+  Expected(Expected &&) noexcept;
+  ~Expected() noexcept;
+
+  Expected &operator=(const Expected &) &;
+  Expected &operator=(Expected &&) &noexcept;
+
+  bool has_value() const noexcept;
+  explicit operator bool() const noexcept;
+
+  void value() const;
+
+  E &error() & noexcept;
+  const E &error() const & noexcept;
+  E &&error() && noexcept;
+
+private:
+  void destroy() noexcept;
+
+  bool ok;
+  union {
+    E err;
+  };
+};
+
+template <typename T, typename E>
+using Result = Expected<T, E>;
+#endif // CXXBRIDGE1_RUST_EXPECTED
+
 #ifndef CXXBRIDGE1_RUST_ISIZE
 #define CXXBRIDGE1_RUST_ISIZE
 #if defined(_WIN32)
@@ -487,6 +722,8 @@ using slice = Slice<T>;
 template <typename T>
 using box = Box<T>;
 template <typename T>
+using arc = Arc<T>;
+template <typename T>
 using vec = Vec<T>;
 using error = Error;
 template <typename Signature>
@@ -518,6 +755,382 @@ Fn<Ret(Args...)> Fn<Ret(Args...)>::operator*() const noexcept {
 }
 #endif // CXXBRIDGE1_RUST_FN
 
+#ifndef CXXBRIDGE1_RUST_FNMUT
+#define CXXBRIDGE1_RUST_FNMUT
+template <typename Ret, typename... Args>
+template <typename F, typename>
+FnMut<Ret(Args...)>::FnMut(F &&f)
+    : trampoline([](Args... args, void *fn) -> Ret {
+        using Callable = typename std::decay<F>::type;
+        return (*static_cast<Callable *>(fn))(std::forward<Args>(args)...);
+      }),
+      drop([](void *fn) noexcept {
+        using Callable = typename std::decay<F>::type;
+        delete static_cast<Callable *>(fn);
+      }),
+      fn(new typename std::decay<F>::type(std::forward<F>(f))) {}
+
+template <typename Ret, typename... Args>
+FnMut<Ret(Args...)>::FnMut(FnMut &&other) noexcept
+    : trampoline(other.trampoline), drop(other.drop), fn(other.fn) {
+  other.fn = nullptr;
+}
+
+template <typename Ret, typename... Args>
+FnMut<Ret(Args...)>::~FnMut() noexcept {
+  if (this->fn != nullptr) {
+    this->drop(this->fn);
+  }
+}
+
+template <typename Ret, typename... Args>
+FnMut<Ret(Args...)> &
+FnMut<Ret(Args...)>::operator=(FnMut &&other) &noexcept {
+  if (this != &other) {
+    if (this->fn != nullptr) {
+      this->drop(this->fn);
+    }
+    this->trampoline = other.trampoline;
+    this->drop = other.drop;
+    this->fn = other.fn;
+    other.fn = nullptr;
+  }
+  return *this;
+}
+
+template <typename Ret, typename... Args>
+Ret FnMut<Ret(Args...)>::operator()(Args... args) {
+  return (*this->trampoline)(std::forward<Args>(args)..., this->fn);
+}
+
+template <typename Ret, typename... Args>
+FnMut<Ret(Args...)>::operator std::function<Ret(Args...)>() && {
+  // std::function requires a copyable target, so share ownership of the one
+  // underlying callable among all copies.
+  auto shared = std::make_shared<FnMut>(std::move(*this));
+  return [shared](Args... args) -> Ret {
+    return (*shared)(std::forward<Args>(args)...);
+  };
+}
+#endif // CXXBRIDGE1_RUST_FNMUT
+
+#ifndef CXXBRIDGE1_RUST_EXPECTED
+#define CXXBRIDGE1_RUST_EXPECTED
+template <typename T, typename E>
+Expected<T, E>::Expected(const T &value) : ok(true), val(value) {}
+
+template <typename T, typename E>
+Expected<T, E>::Expected(T &&value) noexcept : ok(true), val(std::move(value)) {}
+
+template <typename T, typename E>
+Expected<T, E>::Expected(E error) noexcept : ok(false), err(std::move(error)) {}
+
+template <typename T, typename E>
+Expected<T, E>::Expected(const Expected &other) : ok(other.ok) {
+  if (ok) {
+    new (&val) T(other.val);
+  } else {
+    new (&err) E(other.err);
+  }
+}
+
+template <typename T, typename E>
+Expected<T, E>::Expected(Expected &&other) noexcept : ok(other.ok) {
+  if (ok) {
+    new (&val) T(std::move(other.val));
+  } else {
+    new (&err) E(std::move(other.err));
+  }
+}
+
+template <typename T, typename E>
+Expected<T, E>::~Expected() noexcept {
+  this->destroy();
+}
+
+template <typename T, typename E>
+Expected<T, E> &Expected<T, E>::operator=(const Expected &other) & {
+  if (this != &other) {
+    Expected copy(other);
+    *this = std::move(copy);
+  }
+  return *this;
+}
+
+template <typename T, typename E>
+Expected<T, E> &Expected<T, E>::operator=(Expected &&other) &noexcept {
+  if (this != &other) {
+    this->destroy();
+    new (this) Expected(std::move(other));
+  }
+  return *this;
+}
+
+template <typename T, typename E>
+bool Expected<T, E>::has_value() const noexcept {
+  return this->ok;
+}
+
+template <typename T, typename E>
+Expected<T, E>::operator bool() const noexcept {
+  return this->ok;
+}
+
+template <typename T, typename E>
+T &Expected<T, E>::value() & {
+  if (!this->ok) {
+    panic<std::logic_error>("rust::Expected::value() on an error");
+  }
+  return this->val;
+}
+
+template <typename T, typename E>
+const T &Expected<T, E>::value() const & {
+  if (!this->ok) {
+    panic<std::logic_error>("rust::Expected::value() on an error");
+  }
+  return this->val;
+}
+
+template <typename T, typename E>
+T &&Expected<T, E>::value() && {
+  if (!this->ok) {
+    panic<std::logic_error>("rust::Expected::value() on an error");
+  }
+  return std::move(this->val);
+}
+
+template <typename T, typename E>
+T &Expected<T, E>::operator*() & noexcept {
+  return this->val;
+}
+
+template <typename T, typename E>
+const T &Expected<T, E>::operator*() const & noexcept {
+  return this->val;
+}
+
+template <typename T, typename E>
+T &&Expected<T, E>::operator*() && noexcept {
+  return std::move(this->val);
+}
+
+template <typename T, typename E>
+T *Expected<T, E>::operator->() noexcept {
+  return &this->val;
+}
+
+template <typename T, typename E>
+const T *Expected<T, E>::operator->() const noexcept {
+  return &this->val;
+}
+
+template <typename T, typename E>
+E &Expected<T, E>::error() & noexcept {
+  return this->err;
+}
+
+template <typename T, typename E>
+const E &Expected<T, E>::error() const & noexcept {
+  return this->err;
+}
+
+template <typename T, typename E>
+E &&Expected<T, E>::error() && noexcept {
+  return std::move(this->err);
+}
+
+template <typename T, typename E>
+void Expected<T, E>::destroy() noexcept {
+  if (this->ok) {
+    this->val.~T();
+  } else {
+    this->err.~E();
+  }
+}
+
+template <typename T, typename E>
+Expected<T &, E>::Expected(T &value) noexcept : ok(true), ptr(&value) {}
+
+template <typename T, typename E>
+Expected<T &, E>::Expected(E error) noexcept
+    : ok(false), err(std::move(error)) {}
+
+template <typename T, typename E>
+Expected<T &, E>::Expected(const Expected &other) : ok(other.ok) {
+  if (ok) {
+    this->ptr = other.ptr;
+  } else {
+    new (&err) E(other.err);
+  }
+}
+
+template <typename T, typename E>
+Expected<T &, E>::Expected(Expected &&other) noexcept : ok(other.ok) {
+  if (ok) {
+    this->ptr = other.ptr;
+  } else {
+    new (&err) E(std::move(other.err));
+  }
+}
+
+template <typename T, typename E>
+Expected<T &, E>::~Expected() noexcept {
+  this->destroy();
+}
+
+template <typename T, typename E>
+Expected<T &, E> &Expected<T &, E>::operator=(const Expected &other) & {
+  if (this != &other) {
+    Expected copy(other);
+    *this = std::move(copy);
+  }
+  return *this;
+}
+
+template <typename T, typename E>
+Expected<T &, E> &Expected<T &, E>::operator=(Expected &&other) &noexcept {
+  if (this != &other) {
+    this->destroy();
+    new (this) Expected(std::move(other));
+  }
+  return *this;
+}
+
+template <typename T, typename E>
+bool Expected<T &, E>::has_value() const noexcept {
+  return this->ok;
+}
+
+template <typename T, typename E>
+Expected<T &, E>::operator bool() const noexcept {
+  return this->ok;
+}
+
+template <typename T, typename E>
+T &Expected<T &, E>::value() const {
+  if (!this->ok) {
+    panic<std::logic_error>("rust::Expected::value() on an error");
+  }
+  return *this->ptr;
+}
+
+template <typename T, typename E>
+T &Expected<T &, E>::operator*() const noexcept {
+  return *this->ptr;
+}
+
+template <typename T, typename E>
+T *Expected<T &, E>::operator->() const noexcept {
+  return this->ptr;
+}
+
+template <typename T, typename E>
+E &Expected<T &, E>::error() & noexcept {
+  return this->err;
+}
+
+template <typename T, typename E>
+const E &Expected<T &, E>::error() const & noexcept {
+  return this->err;
+}
+
+template <typename T, typename E>
+E &&Expected<T &, E>::error() && noexcept {
+  return std::move(this->err);
+}
+
+template <typename T, typename E>
+void Expected<T &, E>::destroy() noexcept {
+  if (!this->ok) {
+    this->err.~E();
+  }
+}
+
+template <typename E>
+Expected<void, E>::Expected() noexcept : ok(true) {}
+
+template <typename E>
+Expected<void, E>::Expected(E error) noexcept
+    : ok(false), err(std::move(error)) {}
+
+template <typename E>
+Expected<void, E>::Expected(const Expected &other) : ok(other.ok) {
+  if (!ok) {
+    new (&err) E(other.err);
+  }
+}
+
+template <typename E>
+Expected<void, E>::Expected(Expected &&other) noexcept : ok(other.ok) {
+  if (!ok) {
+    new (&err) E(std::move(other.err));
+  }
+}
+
+template <typename E>
+Expected<void, E>::~Expected() noexcept {
+  this->destroy();
+}
+
+template <typename E>
+Expected<void, E> &Expected<void, E>::operator=(const Expected &other) & {
+  if (this != &other) {
+    Expected copy(other);
+    *this = std::move(copy);
+  }
+  return *this;
+}
+
+template <typename E>
+Expected<void, E> &Expected<void, E>::operator=(Expected &&other) &noexcept {
+  if (this != &other) {
+    this->destroy();
+    new (this) Expected(std::move(other));
+  }
+  return *this;
+}
+
+template <typename E>
+bool Expected<void, E>::has_value() const noexcept {
+  return this->ok;
+}
+
+template <typename E>
+Expected<void, E>::operator bool() const noexcept {
+  return this->ok;
+}
+
+template <typename E>
+void Expected<void, E>::value() const {
+  if (!this->ok) {
+    panic<std::logic_error>("rust::Expected::value() on an error");
+  }
+}
+
+template <typename E>
+E &Expected<void, E>::error() & noexcept {
+  return this->err;
+}
+
+template <typename E>
+const E &Expected<void, E>::error() const & noexcept {
+  return this->err;
+}
+
+template <typename E>
+E &&Expected<void, E>::error() && noexcept {
+  return std::move(this->err);
+}
+
+template <typename E>
+void Expected<void, E>::destroy() noexcept {
+  if (!this->ok) {
+    this->err.~E();
+  }
+}
+#endif // CXXBRIDGE1_RUST_EXPECTED
+
 #ifndef CXXBRIDGE1_RUST_BITCOPY_T
 #define CXXBRIDGE1_RUST_BITCOPY_T
 struct unsafe_bitcopy_t final {
@@ -836,10 +1449,100 @@ T *Box<T>::into_raw() noexcept {
   return raw;
 }
 
+template <typename T>
+std::unique_ptr<T, BoxDeleter> Box<T>::into_unique_ptr() noexcept {
+  return std::unique_ptr<T, BoxDeleter>(this->into_raw());
+}
+
+template <typename T>
+Box<T> Box<T>::from_unique_ptr(std::unique_ptr<T, BoxDeleter> ptr) noexcept {
+  return from_raw(ptr.release());
+}
+
 template <typename T>
 Box<T>::Box(uninit) noexcept {}
+
+template <typename T>
+void BoxDeleter::operator()(T *ptr) const noexcept {
+  Box<T>::from_raw(ptr);
+}
 #endif // CXXBRIDGE1_RUST_BOX
 
+#ifndef CXXBRIDGE1_RUST_ARC
+#define CXXBRIDGE1_RUST_ARC
+template <typename T>
+class Arc<T>::uninit {};
+
+template <typename T>
+Arc<T>::Arc(const Arc &other) noexcept : ptr(other.ptr) {
+  if (this->ptr) {
+    this->inc_ref();
+  }
+}
+
+template <typename T>
+Arc<T>::Arc(Arc &&other) noexcept : ptr(other.ptr) {
+  other.ptr = nullptr;
+}
+
+template <typename T>
+Arc<T>::~Arc() noexcept {
+  if (this->ptr) {
+    this->drop();
+  }
+}
+
+template <typename T>
+Arc<T> &Arc<T>::operator=(const Arc &other) &noexcept {
+  Arc copy(other);
+  this->swap(copy);
+  return *this;
+}
+
+template <typename T>
+Arc<T> &Arc<T>::operator=(Arc &&other) &noexcept {
+  if (this->ptr) {
+    this->drop();
+  }
+  this->ptr = other.ptr;
+  other.ptr = nullptr;
+  return *this;
+}
+
+template <typename T>
+const T *Arc<T>::operator->() const noexcept {
+  return this->ptr;
+}
+
+template <typename T>
+const T &Arc<T>::operator*() const noexcept {
+  return *this->ptr;
+}
+
+template <typename T>
+void Arc<T>::swap(Arc &rhs) noexcept {
+  using std::swap;
+  swap(this->ptr, rhs.ptr);
+}
+
+template <typename T>
+Arc<T> Arc<T>::from_raw(const T *raw) noexcept {
+  Arc arc = uninit{};
+  arc.ptr = raw;
+  return arc;
+}
+
+template <typename T>
+const T *Arc<T>::into_raw() noexcept {
+  const T *raw = this->ptr;
+  this->ptr = nullptr;
+  return raw;
+}
+
+template <typename T>
+Arc<T>::Arc(uninit) noexcept {}
+#endif // CXXBRIDGE1_RUST_ARC
+
 #ifndef CXXBRIDGE1_RUST_VEC
 #define CXXBRIDGE1_RUST_VEC
 template <typename T>
@@ -1,4 +1,4 @@
-use crate::syntax::{derive, Enum, Struct, Trait};
+use crate::syntax::{derive, mangle, Enum, ExternType, Struct, Trait};
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, quote_spanned, ToTokens};
 
@@ -11,10 +11,12 @@ pub fn expand_struct(strct: &Struct, actual_derives: &mut Option<TokenStream>) -
     for derive in &strct.derives {
         let span = derive.span;
         match derive.what {
+            Trait::Add => unreachable!(),
             Trait::Copy => expanded.extend(struct_copy(strct, span)),
             Trait::Clone => expanded.extend(struct_clone(strct, span)),
             Trait::Debug => expanded.extend(struct_debug(strct, span)),
             Trait::Default => expanded.extend(struct_default(strct, span)),
+            Trait::Deref => unreachable!(),
             Trait::Eq => traits.push(quote_spanned!(span=> ::cxx::core::cmp::Eq)),
             Trait::ExternType => unreachable!(),
             Trait::Hash => traits.push(quote_spanned!(span=> ::cxx::core::hash::Hash)),
@@ -46,6 +48,7 @@ pub fn expand_enum(enm: &Enum, actual_derives: &mut Option<TokenStream>) -> Toke
     for derive in &enm.derives {
         let span = derive.span;
         match derive.what {
+            Trait::Add => unreachable!(),
             Trait::Copy => {
                 expanded.extend(enum_copy(enm, span));
                 has_copy = true;
@@ -55,7 +58,7 @@ pub fn expand_enum(enm: &Enum, actual_derives: &mut Option<TokenStream>) -> Toke
                 has_clone = true;
             }
             Trait::Debug => expanded.extend(enum_debug(enm, span)),
-            Trait::Default => unreachable!(),
+            Trait::Default | Trait::Deref => unreachable!(),
             Trait::Eq => {
                 traits.push(quote_spanned!(span=> ::cxx::core::cmp::Eq));
                 has_eq = true;
@@ -94,6 +97,32 @@ pub fn expand_enum(enm: &Enum, actual_derives: &mut Option<TokenStream>) -> Toke
     expanded
 }
 
+pub fn expand_cxx_type(ety: &ExternType) -> TokenStream {
+    let mut expanded = TokenStream::new();
+
+    for derive in &ety.derives {
+        let span = derive.span;
+        match derive.what {
+            Trait::Add => expanded.extend(cxx_type_add(ety, span)),
+            Trait::Clone => expanded.extend(cxx_type_clone(ety, span)),
+            Trait::Debug => expanded.extend(cxx_type_debug(ety, span)),
+            Trait::Deref => expanded.extend(cxx_type_deref(ety, span)),
+            Trait::Eq => expanded.extend(cxx_type_eq(ety, span)),
+            Trait::Hash => expanded.extend(cxx_type_hash(ety, span)),
+            Trait::Ord => expanded.extend(cxx_type_ord(ety, span)),
+            Trait::PartialEq => expanded.extend(cxx_type_partial_eq(ety, span)),
+            Trait::PartialOrd => expanded.extend(cxx_type_partial_ord(ety, span)),
+            Trait::Copy
+            | Trait::Default
+            | Trait::ExternType
+            | Trait::Serialize
+            | Trait::Deserialize => unreachable!(),
+        }
+    }
+
+    expanded
+}
+
 fn struct_copy(strct: &Struct, span: Span) -> TokenStream {
     let ident = &strct.name.rust;
     let generics = &strct.generics;
@@ -286,3 +315,206 @@ fn enum_partial_ord(enm: &Enum, span: Span) -> TokenStream {
         }
     }
 }
+
+fn cxx_type_partial_eq(ety: &ExternType, span: Span) -> TokenStream {
+    let ident = &ety.name.rust;
+    let generics = &ety.generics;
+    let link_name = mangle::operator(&ety.name, "eq");
+
+    quote_spanned! {span=>
+        impl #generics ::cxx::core::cmp::PartialEq for #ident #generics {
+            fn eq(&self, other: &Self) -> bool {
+                extern "C" {
+                    #[link_name = #link_name]
+                    fn __operator_eq(lhs: *const ::cxx::core::ffi::c_void, rhs: *const ::cxx::core::ffi::c_void) -> bool;
+                }
+                let lhs = self as *const Self as *const ::cxx::core::ffi::c_void;
+                let rhs = other as *const Self as *const ::cxx::core::ffi::c_void;
+                unsafe { __operator_eq(lhs, rhs) }
+            }
+        }
+    }
+}
+
+fn cxx_type_eq(ety: &ExternType, span: Span) -> TokenStream {
+    let ident = &ety.name.rust;
+    let generics = &ety.generics;
+
+    quote_spanned! {span=>
+        impl #generics ::cxx::core::cmp::Eq for #ident #generics {}
+    }
+}
+
+fn cxx_type_lt(ety: &ExternType) -> TokenStream {
+    let link_name = mangle::operator(&ety.name, "lt");
+
+    quote! {
+        extern "C" {
+            #[link_name = #link_name]
+            fn __operator_lt(lhs: *const ::cxx::core::ffi::c_void, rhs: *const ::cxx::core::ffi::c_void) -> bool;
+        }
+        let lhs = self as *const Self as *const ::cxx::core::ffi::c_void;
+        let rhs = other as *const Self as *const ::cxx::core::ffi::c_void;
+    }
+}
+
+fn cxx_type_partial_ord(ety: &ExternType, span: Span) -> TokenStream {
+    let ident = &ety.name.rust;
+    let generics = &ety.generics;
+
+    let body = if derive::contains(&ety.derives, Trait::Ord) {
+        quote! {
+            ::cxx::core::option::Option::Some(::cxx::core::cmp::Ord::cmp(self, other))
+        }
+    } else {
+        let lt = cxx_type_lt(ety);
+        quote! {
+            #lt
+            if unsafe { __operator_lt(lhs, rhs) } {
+                ::cxx::core::option::Option::Some(::cxx::core::cmp::Ordering::Less)
+            } else if unsafe { __operator_lt(rhs, lhs) } {
+                ::cxx::core::option::Option::Some(::cxx::core::cmp::Ordering::Greater)
+            } else if *self == *other {
+                ::cxx::core::option::Option::Some(::cxx::core::cmp::Ordering::Equal)
+            } else {
+                ::cxx::core::option::Option::None
+            }
+        }
+    };
+
+    quote_spanned! {span=>
+        impl #generics ::cxx::core::cmp::PartialOrd for #ident #generics {
+            fn partial_cmp(&self, other: &Self) -> ::cxx::core::option::Option<::cxx::core::cmp::Ordering> {
+                #body
+            }
+        }
+    }
+}
+
+fn cxx_type_ord(ety: &ExternType, span: Span) -> TokenStream {
+    let ident = &ety.name.rust;
+    let generics = &ety.generics;
+    let lt = cxx_type_lt(ety);
+
+    quote_spanned! {span=>
+        impl #generics ::cxx::core::cmp::Ord for #ident #generics {
+            fn cmp(&self, other: &Self) -> ::cxx::core::cmp::Ordering {
+                #lt
+                if unsafe { __operator_lt(lhs, rhs) } {
+                    ::cxx::core::cmp::Ordering::Less
+                } else if unsafe { __operator_lt(rhs, lhs) } {
+                    ::cxx::core::cmp::Ordering::Greater
+                } else {
+                    ::cxx::core::cmp::Ordering::Equal
+                }
+            }
+        }
+    }
+}
+
+fn cxx_type_hash(ety: &ExternType, span: Span) -> TokenStream {
+    let ident = &ety.name.rust;
+    let generics = &ety.generics;
+    let link_name = mangle::operator(&ety.name, "hash");
+
+    quote_spanned! {span=>
+        impl #generics ::cxx::core::hash::Hash for #ident #generics {
+            fn hash<__H: ::cxx::core::hash::Hasher>(&self, state: &mut __H) {
+                extern "C" {
+                    #[link_name = #link_name]
+                    fn __operator_hash(this: *const ::cxx::core::ffi::c_void) -> usize;
+                }
+                let this = self as *const Self as *const ::cxx::core::ffi::c_void;
+                let hash = unsafe { __operator_hash(this) };
+                ::cxx::core::hash::Hash::hash(&hash, state);
+            }
+        }
+    }
+}
+
+fn cxx_type_debug(ety: &ExternType, span: Span) -> TokenStream {
+    let ident = &ety.name.rust;
+    let generics = &ety.generics;
+    let link_name = mangle::operator(&ety.name, "debug");
+
+    quote_spanned! {span=>
+        impl #generics ::cxx::core::fmt::Debug for #ident #generics {
+            fn fmt(&self, formatter: &mut ::cxx::core::fmt::Formatter<'_>) -> ::cxx::core::fmt::Result {
+                extern "C" {
+                    #[link_name = #link_name]
+                    fn __operator_debug(this: *const ::cxx::core::ffi::c_void, repr: *mut ::cxx::private::RustString) -> bool;
+                }
+                let this = self as *const Self as *const ::cxx::core::ffi::c_void;
+                let mut repr = ::cxx::core::mem::MaybeUninit::<::cxx::private::RustString>::uninit();
+                if !unsafe { __operator_debug(this, repr.as_mut_ptr()) } {
+                    return ::cxx::core::result::Result::Err(::cxx::core::fmt::Error);
+                }
+                let repr = unsafe { repr.assume_init().into_string() };
+                formatter.write_str(&repr)
+            }
+        }
+    }
+}
+
+fn cxx_type_deref(ety: &ExternType, span: Span) -> TokenStream {
+    let ident = &ety.name.rust;
+    let generics = &ety.generics;
+    let base = &ety.bases[0];
+
+    quote_spanned! {span=>
+        impl #generics ::cxx::core::ops::Deref for #ident #generics {
+            type Target = #base;
+
+            fn deref(&self) -> &Self::Target {
+                <Self as ::cxx::private::Upcast<#base>>::upcast(self)
+            }
+        }
+    }
+}
+
+fn cxx_type_clone(ety: &ExternType, span: Span) -> TokenStream {
+    let ident = &ety.name.rust;
+    let generics = &ety.generics;
+    let link_clone = mangle::operator(&ety.name, "clone");
+    let link_clone_shared = mangle::operator(&ety.name, "clone_shared");
+
+    quote_spanned! {span=>
+        unsafe impl #generics ::cxx::private::CloneTarget for #ident #generics {
+            unsafe fn __clone(this: *const Self) -> *mut Self {
+                extern "C" {
+                    #[link_name = #link_clone]
+                    fn __clone(this: *const ::cxx::core::ffi::c_void) -> *mut ::cxx::core::ffi::c_void;
+                }
+                unsafe { __clone(this.cast()).cast() }
+            }
+            unsafe fn __clone_shared(this: *const Self, new: *mut ::cxx::core::ffi::c_void) {
+                extern "C" {
+                    #[link_name = #link_clone_shared]
+                    fn __clone_shared(this: *const ::cxx::core::ffi::c_void, new: *mut ::cxx::core::ffi::c_void);
+                }
+                unsafe { __clone_shared(this.cast(), new) }
+            }
+        }
+    }
+}
+
+fn cxx_type_add(ety: &ExternType, span: Span) -> TokenStream {
+    let ident = &ety.name.rust;
+    let generics = &ety.generics;
+    let link_name = mangle::operator(&ety.name, "add");
+
+    quote_spanned! {span=>
+        impl #generics ::cxx::core::ops::Add for &#ident #generics {
+            type Output = ::cxx::UniquePtr<#ident #generics>;
+            fn add(self, other: Self) -> Self::Output {
+                extern "C" {
+                    #[link_name = #link_name]
+                    fn __operator_add(lhs: *const ::cxx::core::ffi::c_void, rhs: *const ::cxx::core::ffi::c_void) -> *mut ::cxx::core::ffi::c_void;
+                }
+                let lhs = self as *const #ident #generics as *const ::cxx::core::ffi::c_void;
+                let rhs = other as *const #ident #generics as *const ::cxx::core::ffi::c_void;
+                unsafe { ::cxx::UniquePtr::from_raw(__operator_add(lhs, rhs).cast()) }
+            }
+        }
+    }
+}
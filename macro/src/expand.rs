@@ -3,19 +3,20 @@ use crate::syntax::attrs::{self, OtherAttrs};
 use crate::syntax::cfg::CfgExpr;
 use crate::syntax::file::Module;
 use crate::syntax::instantiate::{ImplKey, NamedImplKey};
+use crate::syntax::mangle::CXXBRIDGE;
 use crate::syntax::qualified::QualifiedName;
 use crate::syntax::report::Errors;
 use crate::syntax::symbol::Symbol;
 use crate::syntax::{
-    self, check, mangle, Api, Doc, Enum, ExternFn, ExternType, Impl, Lifetimes, Pair, Signature,
-    Struct, Trait, Type, TypeAlias, Types,
+    self, check, mangle, Api, Const, Doc, DynFn, Enum, ExternFn, ExternType, Impl, Lifetimes, Pair,
+    Signature, Struct, Trait, Tuple, Type, TypeAlias, Types, Var,
 };
 use crate::type_id::Crate;
 use crate::{derive, generics};
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned, ToTokens};
 use std::mem;
-use syn::{parse_quote, punctuated, Generics, Lifetime, Result, Token};
+use syn::{parse_quote, punctuated, Generics, Lifetime, LitByteStr, Result, Token};
 
 pub fn bridge(mut ffi: Module) -> Result<TokenStream> {
     let ref mut errors = Errors::new();
@@ -73,6 +74,8 @@ fn expand(ffi: Module, doc: Doc, attrs: OtherAttrs, apis: &[Api], types: &Types)
                 let ident = &ety.name.rust;
                 if !types.structs.contains_key(ident) && !types.enums.contains_key(ident) {
                     expanded.extend(expand_cxx_type(ety));
+                    expanded.extend(derive::expand_cxx_type(ety));
+                    expanded.extend(expand_cxx_type_bases(ety, types));
                     hidden.extend(expand_cxx_type_assert_pinned(ety, types));
                 }
             }
@@ -88,14 +91,33 @@ fn expand(ffi: Module, doc: Doc, attrs: OtherAttrs, apis: &[Api], types: &Types)
                 expanded.extend(expand_type_alias(alias));
                 hidden.extend(expand_type_alias_verify(alias, types));
             }
+            Api::Const(konst) | Api::CxxConst(konst) => expanded.extend(expand_const(konst)),
         }
     }
 
+    let mut libraries: Vec<(&Ident, Vec<&ExternFn>)> = Vec::new();
+    for api in apis {
+        if let Api::CxxFunction(efn) = api {
+            if let Some(library) = &efn.dlopen {
+                match libraries.iter_mut().find(|(ident, _)| *ident == library) {
+                    Some((_, efns)) => efns.push(efn),
+                    None => libraries.push((library, vec![efn])),
+                }
+            }
+        }
+    }
+    for (library, efns) in &libraries {
+        expanded.extend(expand_dlopen_library(library, efns, types));
+    }
+
     for (impl_key, &explicit_impl) in &types.impls {
         match *impl_key {
             ImplKey::RustBox(ident) => {
                 hidden.extend(expand_rust_box(ident, types, explicit_impl));
             }
+            ImplKey::RustArc(ident) => {
+                hidden.extend(expand_rust_arc(ident, types, explicit_impl));
+            }
             ImplKey::RustVec(ident) => {
                 hidden.extend(expand_rust_vec(ident, types, explicit_impl));
             }
@@ -144,6 +166,7 @@ fn expand(ffi: Module, doc: Doc, attrs: OtherAttrs, apis: &[Api], types: &Types)
             non_snake_case,
             clippy::extra_unused_type_parameters,
             clippy::ptr_as_ptr,
+            clippy::type_complexity,
             clippy::upper_case_acronyms,
             clippy::use_self,
         )]
@@ -163,7 +186,15 @@ fn expand_struct(strct: &Struct) -> TokenStream {
         // This span on the pub makes "private type in public interface" errors
         // appear in the right place.
         let vis = field.visibility;
-        quote!(#doc #attrs #vis #field)
+        match &field.ty {
+            Type::Tuple(tuple) => {
+                let name = &field.name.rust;
+                let colon = field.colon_token;
+                let repr = expand_tuple_repr(tuple, true);
+                quote!(#doc #attrs #vis #name #colon #repr)
+            }
+            _ => quote!(#doc #attrs #vis #field),
+        }
     });
     let mut derives = None;
     let derived_traits = derive::expand_struct(strct, &mut derives);
@@ -411,6 +442,73 @@ fn expand_cxx_type(ety: &ExternType) -> TokenStream {
     }
 }
 
+fn expand_cxx_type_bases(ety: &ExternType, types: &Types) -> TokenStream {
+    let ident = &ety.name.rust;
+    let generics = &ety.generics;
+    let derived = types.resolve(ident).name.to_symbol();
+    let mut expanded = TokenStream::new();
+
+    for base in &ety.bases {
+        let instance = format!("{}${}", derived, types.resolve(base).name.to_symbol());
+        let link_upcast = format!("{}${}$upcast", CXXBRIDGE, instance);
+        let link_upcast_mut = format!("{}${}$upcast_mut", CXXBRIDGE, instance);
+        let link_upcast_unique = format!("{}$unique_ptr${}$upcast", CXXBRIDGE, instance);
+        let link_upcast_shared = format!("{}$shared_ptr${}$upcast", CXXBRIDGE, instance);
+        let link_downcast_shared = format!("{}$shared_ptr${}$downcast", CXXBRIDGE, instance);
+        let link_static_downcast_shared =
+            format!("{}$shared_ptr${}$static_downcast", CXXBRIDGE, instance);
+        let span = base.span();
+        expanded.extend(quote_spanned! {span=>
+            unsafe impl #generics ::cxx::private::Upcast<#base> for #ident #generics {
+                unsafe fn __upcast(this: *const Self) -> *const #base {
+                    extern "C" {
+                        #[link_name = #link_upcast]
+                        fn __upcast(this: *const ::cxx::core::ffi::c_void) -> *const ::cxx::core::ffi::c_void;
+                    }
+                    unsafe { __upcast(this.cast()).cast() }
+                }
+                unsafe fn __upcast_mut(this: *mut Self) -> *mut #base {
+                    extern "C" {
+                        #[link_name = #link_upcast_mut]
+                        fn __upcast_mut(this: *mut ::cxx::core::ffi::c_void) -> *mut ::cxx::core::ffi::c_void;
+                    }
+                    unsafe { __upcast_mut(this.cast()).cast() }
+                }
+                unsafe fn __upcast_unique(this: *mut Self) -> *mut #base {
+                    extern "C" {
+                        #[link_name = #link_upcast_unique]
+                        fn __upcast_unique(this: *mut ::cxx::core::ffi::c_void) -> *mut ::cxx::core::ffi::c_void;
+                    }
+                    unsafe { __upcast_unique(this.cast()).cast() }
+                }
+                unsafe fn __upcast_shared(this: *const ::cxx::core::ffi::c_void, new: *mut ::cxx::core::ffi::c_void) {
+                    extern "C" {
+                        #[link_name = #link_upcast_shared]
+                        fn __upcast_shared(this: *const ::cxx::core::ffi::c_void, new: *mut ::cxx::core::ffi::c_void);
+                    }
+                    unsafe { __upcast_shared(this, new) }
+                }
+                unsafe fn __downcast_shared(this: *const ::cxx::core::ffi::c_void, new: *mut ::cxx::core::ffi::c_void) {
+                    extern "C" {
+                        #[link_name = #link_downcast_shared]
+                        fn __downcast_shared(this: *const ::cxx::core::ffi::c_void, new: *mut ::cxx::core::ffi::c_void);
+                    }
+                    unsafe { __downcast_shared(this, new) }
+                }
+                unsafe fn __static_downcast_shared(this: *const ::cxx::core::ffi::c_void, new: *mut ::cxx::core::ffi::c_void) {
+                    extern "C" {
+                        #[link_name = #link_static_downcast_shared]
+                        fn __static_downcast_shared(this: *const ::cxx::core::ffi::c_void, new: *mut ::cxx::core::ffi::c_void);
+                    }
+                    unsafe { __static_downcast_shared(this, new) }
+                }
+            }
+        });
+    }
+
+    expanded
+}
+
 fn expand_cxx_type_assert_pinned(ety: &ExternType, types: &Types) -> TokenStream {
     let ident = &ety.name.rust;
     let infer = Token![_](ident.span());
@@ -449,43 +547,174 @@ fn expand_cxx_type_assert_pinned(ety: &ExternType, types: &Types) -> TokenStream
 
 fn expand_cxx_function_decl(efn: &ExternFn, types: &Types) -> TokenStream {
     let generics = &efn.generics;
+    let (args, ret) = expand_cxx_function_decl_signature(efn, types);
+    let link_name = mangle::extern_fn(efn, types);
+    let local_name = format_ident!("__{}", efn.name.rust);
+    quote! {
+        #[link_name = #link_name]
+        fn #local_name #generics(#args) #ret;
+    }
+}
+
+fn expand_cxx_function_decl_signature(efn: &ExternFn, types: &Types) -> (TokenStream, TokenStream) {
     let receiver = efn.receiver.iter().map(|receiver| {
         let receiver_type = receiver.ty();
         quote!(_: #receiver_type)
     });
-    let args = efn.args.iter().map(|arg| {
-        let var = &arg.name.rust;
-        let colon = arg.colon_token;
-        let ty = expand_extern_type(&arg.ty, types, true);
-        if arg.ty == RustString {
-            quote!(#var #colon *const #ty)
-        } else if let Type::RustVec(_) = arg.ty {
-            quote!(#var #colon *const #ty)
-        } else if let Type::Fn(_) = arg.ty {
-            quote!(#var #colon ::cxx::private::FatFunction)
-        } else if types.needs_indirect_abi(&arg.ty) {
-            quote!(#var #colon *mut #ty)
-        } else {
-            quote!(#var #colon #ty)
-        }
-    });
+    let args = efn
+        .args
+        .iter()
+        .map(|arg| expand_cxx_function_decl_arg(arg, types));
     let all_args = receiver.chain(args);
-    let ret = if efn.throws {
+    let (ret, outparam) = expand_cxx_function_decl_ret(efn, types);
+    let slot = if efn.constructor {
+        Some(quote!(__slot: *mut ::cxx::core::ffi::c_void,))
+    } else {
+        None
+    };
+    (quote!(#(#all_args,)* #slot #outparam), ret)
+}
+
+// In place of an extern "C" declaration, functions of a #[dlopen] extern block
+// look up their symbol in the library at runtime, caching the address.
+fn expand_cxx_function_dlopen(efn: &ExternFn, types: &Types, library: &Ident) -> TokenStream {
+    let (args, ret) = expand_cxx_function_decl_signature(efn, types);
+    let symbol = dlopen_symbol(efn, types);
+    let local_name = format_ident!("__{}", efn.name.rust);
+    quote! {
+        static __SYMBOL: ::cxx::private::Symbol = ::cxx::private::Symbol::new();
+        let #local_name = ::cxx::core::mem::transmute::<
+            *mut ::cxx::core::ffi::c_void,
+            unsafe extern "C" fn(#args) #ret,
+        >(#library::__cxxbridge_library().symbol(&__SYMBOL, #symbol));
+    }
+}
+
+fn dlopen_symbol(efn: &ExternFn, types: &Types) -> LitByteStr {
+    let link_name = mangle::extern_fn(efn, types);
+    let nul_terminated = format!("{}\0", link_name);
+    LitByteStr::new(nul_terminated.as_bytes(), efn.name.rust.span())
+}
+
+fn expand_dlopen_library(library: &Ident, efns: &[&ExternFn], types: &Types) -> TokenStream {
+    let name = library.to_string();
+    let symbols = efns.iter().map(|efn| {
+        let cfg = expand_cfg(&efn.cfg);
+        let symbol = dlopen_symbol(efn, types);
+        quote!(#cfg symbols.push(#symbol);)
+    });
+    let span = library.span();
+    quote_spanned! {span=>
+        /// Loader of the shared library defining the functions of the
+        /// `#[dlopen]` extern "C++" blocks.
+        pub struct #library {
+            _private: (),
+        }
+
+        impl #library {
+            /// Loads the shared library at `path` and checks that it defines
+            /// every function of the `#[dlopen]` extern "C++" blocks. Those
+            /// functions panic if called before the library has been loaded.
+            ///
+            /// Once loaded, the library stays loaded for the rest of the
+            /// process, and loading it again is a no-op.
+            pub fn load(
+                path: impl ::cxx::core::convert::AsRef<::cxx::std::path::Path>,
+            ) -> ::cxx::core::result::Result<Self, ::cxx::LoadError> {
+                #[allow(unused_mut)]
+                let mut symbols: ::cxx::alloc::vec::Vec<&'static [u8]> =
+                    ::cxx::alloc::vec::Vec::new();
+                #(#symbols)*
+                Self::__cxxbridge_library().load(path.as_ref(), &symbols)?;
+                ::cxx::core::result::Result::Ok(#library { _private: () })
+            }
+
+            /// Whether the shared library has been loaded.
+            pub fn is_loaded() -> bool {
+                Self::__cxxbridge_library().is_loaded()
+            }
+
+            #[doc(hidden)]
+            fn __cxxbridge_library() -> &'static ::cxx::private::Library {
+                static LIBRARY: ::cxx::private::Library = ::cxx::private::Library::new(#name);
+                &LIBRARY
+            }
+        }
+    }
+}
+
+fn expand_cfg(cfg: &CfgExpr) -> Option<TokenStream> {
+    fn expand_predicate(cfg: &CfgExpr) -> TokenStream {
+        match cfg {
+            CfgExpr::Unconditional => quote!(all()),
+            CfgExpr::Eq(ident, None) => quote!(#ident),
+            CfgExpr::Eq(ident, Some(string)) => quote!(#ident = #string),
+            CfgExpr::All(list) => {
+                let list = list.iter().map(expand_predicate);
+                quote!(all(#(#list),*))
+            }
+            CfgExpr::Any(list) => {
+                let list = list.iter().map(expand_predicate);
+                quote!(any(#(#list),*))
+            }
+            CfgExpr::Not(inner) => {
+                let inner = expand_predicate(inner);
+                quote!(not(#inner))
+            }
+        }
+    }
+    match cfg {
+        CfgExpr::Unconditional => None,
+        cfg => {
+            let predicate = expand_predicate(cfg);
+            Some(quote!(#[cfg(#predicate)]))
+        }
+    }
+}
+
+fn expand_cxx_function_decl_arg(arg: &Var, types: &Types) -> TokenStream {
+    let var = &arg.name.rust;
+    let colon = arg.colon_token;
+    let ty = expand_extern_type(&arg.ty, types, true);
+    if arg.ty == RustString {
+        quote!(#var #colon *const #ty)
+    } else if let Type::RustVec(_) = arg.ty {
+        quote!(#var #colon *const #ty)
+    } else if let Type::Fn(_) = arg.ty {
+        quote!(#var #colon ::cxx::private::FatFunction)
+    } else if let Type::Tuple(_) = arg.ty {
+        quote!(#var #colon *mut ::cxx::core::ffi::c_void)
+    } else if types.needs_indirect_abi(&arg.ty) {
+        quote!(#var #colon *mut #ty)
+    } else {
+        quote!(#var #colon #ty)
+    }
+}
+
+fn expand_cxx_function_decl_ret(
+    sig: &Signature,
+    types: &Types,
+) -> (TokenStream, Option<TokenStream>) {
+    let ret = if sig.error.is_some() {
+        quote!(-> bool)
+    } else if sig.throws {
         quote!(-> ::cxx::private::Result)
     } else {
-        expand_extern_return_type(&efn.ret, types, true)
+        expand_extern_return_type(&sig.ret, types, true)
     };
     let mut outparam = None;
-    if indirect_return(efn, types) {
-        let ret = expand_extern_type(efn.ret.as_ref().unwrap(), types, true);
-        outparam = Some(quote!(__return: *mut #ret));
+    if indirect_return(sig, types) {
+        let ret = match sig.ret.as_ref().unwrap() {
+            Type::Tuple(_) => quote!(::cxx::core::ffi::c_void),
+            ret => expand_extern_type(ret, types, true),
+        };
+        outparam = Some(quote!(__return: *mut #ret,));
     }
-    let link_name = mangle::extern_fn(efn, types);
-    let local_name = format_ident!("__{}", efn.name.rust);
-    quote! {
-        #[link_name = #link_name]
-        fn #local_name #generics(#(#all_args,)* #outparam) #ret;
+    if let Some(error) = &sig.error {
+        let error = expand_extern_type(error, types, true);
+        outparam = Some(quote!(#outparam __error: *mut #error,));
     }
+    (ret, outparam)
 }
 
 fn expand_cxx_function_shim(efn: &ExternFn, types: &Types) -> TokenStream {
@@ -512,81 +741,28 @@ fn expand_cxx_function_shim(efn: &ExternFn, types: &Types) -> TokenStream {
             Some(ret) => quote!(#ret),
             None => quote!(()),
         };
-        quote!(-> ::cxx::core::result::Result<#ok, ::cxx::Exception>)
+        let error = match &efn.error {
+            Some(error) => quote!(#error),
+            None => quote!(::cxx::Exception),
+        };
+        quote!(-> ::cxx::core::result::Result<#ok, #error>)
     } else {
         expand_return_type(&efn.ret)
     };
-    let indirect_return = indirect_return(efn, types);
     let receiver_var = efn
         .receiver
         .iter()
         .map(|receiver| receiver.var.to_token_stream());
-    let arg_vars = efn.args.iter().map(|arg| {
-        let var = &arg.name.rust;
-        let span = var.span();
-        match &arg.ty {
-            Type::Ident(ident) if ident.rust == RustString => {
-                quote_spanned!(span=> #var.as_mut_ptr() as *const ::cxx::private::RustString)
-            }
-            Type::RustBox(ty) => {
-                if types.is_considered_improper_ctype(&ty.inner) {
-                    quote_spanned!(span=> ::cxx::alloc::boxed::Box::into_raw(#var).cast())
-                } else {
-                    quote_spanned!(span=> ::cxx::alloc::boxed::Box::into_raw(#var))
-                }
-            }
-            Type::UniquePtr(ty) => {
-                if types.is_considered_improper_ctype(&ty.inner) {
-                    quote_spanned!(span=> ::cxx::UniquePtr::into_raw(#var).cast())
-                } else {
-                    quote_spanned!(span=> ::cxx::UniquePtr::into_raw(#var))
-                }
-            }
-            Type::RustVec(_) => quote_spanned!(span=> #var.as_mut_ptr() as *const ::cxx::private::RustVec<_>),
-            Type::Ref(ty) => match &ty.inner {
-                Type::Ident(ident) if ident.rust == RustString => match ty.mutable {
-                    false => quote_spanned!(span=> ::cxx::private::RustString::from_ref(#var)),
-                    true => quote_spanned!(span=> ::cxx::private::RustString::from_mut(#var)),
-                },
-                Type::RustVec(vec) if vec.inner == RustString => match ty.mutable {
-                    false => quote_spanned!(span=> ::cxx::private::RustVec::from_ref_vec_string(#var)),
-                    true => quote_spanned!(span=> ::cxx::private::RustVec::from_mut_vec_string(#var)),
-                },
-                Type::RustVec(_) => match ty.mutable {
-                    false => quote_spanned!(span=> ::cxx::private::RustVec::from_ref(#var)),
-                    true => quote_spanned!(span=> ::cxx::private::RustVec::from_mut(#var)),
-                },
-                inner if types.is_considered_improper_ctype(inner) => {
-                    let var = match ty.pinned {
-                        false => quote!(#var),
-                        true => quote_spanned!(span=> ::cxx::core::pin::Pin::into_inner_unchecked(#var)),
-                    };
-                    match ty.mutable {
-                        false => {
-                            quote_spanned!(span=> #var as *const #inner as *const ::cxx::core::ffi::c_void)
-                        }
-                        true => quote_spanned!(span=> #var as *mut #inner as *mut ::cxx::core::ffi::c_void),
-                    }
-                }
-                _ => quote!(#var),
-            },
-            Type::Ptr(ty) => {
-                if types.is_considered_improper_ctype(&ty.inner) {
-                    quote_spanned!(span=> #var.cast())
-                } else {
-                    quote!(#var)
-                }
-            }
-            Type::Str(_) => quote_spanned!(span=> ::cxx::private::RustStr::from(#var)),
-            Type::SliceRef(ty) => match ty.mutable {
-                false => quote_spanned!(span=> ::cxx::private::RustSlice::from_ref(#var)),
-                true => quote_spanned!(span=> ::cxx::private::RustSlice::from_mut(#var)),
-            },
-            ty if types.needs_indirect_abi(ty) => quote_spanned!(span=> #var.as_mut_ptr()),
-            _ => quote!(#var),
-        }
-    });
-    let vars = receiver_var.chain(arg_vars);
+    let arg_vars = efn
+        .args
+        .iter()
+        .map(|arg| expand_cxx_function_arg_var(arg, types));
+    let slot_var = if efn.constructor {
+        Some(quote!(__slot))
+    } else {
+        None
+    };
+    let vars = receiver_var.chain(arg_vars).chain(slot_var);
     let trampolines = efn
         .args
         .iter()
@@ -594,13 +770,217 @@ fn expand_cxx_function_shim(efn: &ExternFn, types: &Types) -> TokenStream {
             if let Type::Fn(f) = &arg.ty {
                 let var = &arg.name;
                 Some(expand_function_pointer_trampoline(efn, var, f, types))
+            } else if let Type::DynFn(f) = &arg.ty {
+                let var = &arg.name;
+                Some(expand_closure_trampoline(efn, var, f, types))
             } else {
                 None
             }
         })
         .collect::<TokenStream>();
-    let mut setup = efn
-        .args
+    let mut setup = expand_cxx_function_arg_setup(efn, types);
+    let mut extern_decl = Some(quote!(extern "C" { #decl }));
+    if let Some(library) = &efn.dlopen {
+        let lookup = expand_cxx_function_dlopen(efn, types, library);
+        setup = quote!(#lookup #setup);
+        extern_decl = None;
+    }
+    let local_name = format_ident!("__{}", efn.name.rust);
+    let span = efn.semi_token.span;
+    let call = expand_cxx_function_call(efn, types, &local_name, vars, &mut setup, span);
+    let in_place = match &efn.self_type {
+        Some(self_type) if efn.constructor && types.aliases.contains_key(&self_type.rust) => Some(
+            expand_cxx_constructor_in_place(efn, types, &decl, &setup, &call),
+        ),
+        _ => None,
+    };
+    if efn.constructor {
+        setup = quote_spanned! {span=>
+            let __slot = ::cxx::core::ptr::null_mut();
+            #setup
+        };
+    }
+    let expr = expand_cxx_function_return(efn, types, call, span);
+    let mut dispatch = quote!(#setup #expr);
+    let visibility = efn.visibility;
+    let unsafety = &efn.sig.unsafety;
+    if unsafety.is_none() {
+        dispatch = quote_spanned!(span=> unsafe { #dispatch });
+    }
+    let fn_token = efn.sig.fn_token;
+    let ident = &efn.name.rust;
+    let generics = &efn.generics;
+    let arg_list = quote_spanned!(efn.sig.paren_token.span=> (#(#all_args,)*));
+    let fn_body = quote_spanned!(span=> {
+        #extern_decl
+        #trampolines
+        #dispatch
+    });
+    match &efn.receiver {
+        None => match &efn.self_type {
+            None => quote! {
+                #doc
+                #attrs
+                #visibility #unsafety #fn_token #ident #generics #arg_list #ret #fn_body
+            },
+            Some(self_type) => {
+                let self_type_ident = &self_type.rust;
+                quote_spanned! {ident.span()=>
+                    impl #generics #self_type_ident {
+                        #doc
+                        #attrs
+                        #visibility #unsafety #fn_token #ident #arg_list #ret #fn_body
+                        #in_place
+                    }
+                }
+            }
+        },
+        Some(receiver) => {
+            let elided_generics;
+            let receiver_ident = &receiver.ty.rust;
+            let resolve = types.resolve(&receiver.ty);
+            let receiver_generics = if receiver.ty.generics.lt_token.is_some() {
+                &receiver.ty.generics
+            } else {
+                elided_generics = Lifetimes {
+                    lt_token: resolve.generics.lt_token,
+                    lifetimes: resolve
+                        .generics
+                        .lifetimes
+                        .pairs()
+                        .map(|pair| {
+                            let lifetime = Lifetime::new("'_", pair.value().apostrophe);
+                            let punct = pair.punct().map(|&&comma| comma);
+                            punctuated::Pair::new(lifetime, punct)
+                        })
+                        .collect(),
+                    gt_token: resolve.generics.gt_token,
+                };
+                &elided_generics
+            };
+            let index_operator = if efn.name.cxx == *"operator[]" {
+                expand_index_operator(efn, receiver_generics)
+            } else {
+                TokenStream::new()
+            };
+            quote_spanned! {ident.span()=>
+                impl #generics #receiver_ident #receiver_generics {
+                    #doc
+                    #attrs
+                    #visibility #unsafety #fn_token #ident #arg_list #ret #fn_body
+                }
+                #index_operator
+            }
+        }
+    }
+}
+
+fn expand_index_operator(efn: &ExternFn, receiver_generics: &Lifetimes) -> TokenStream {
+    let receiver_ident = &efn.receiver.as_ref().unwrap().ty.rust;
+    let ident = &efn.name.rust;
+    let index = &efn.args[0];
+    let var = &index.name.rust;
+    let ty = &index.ty;
+    let output = match &efn.ret {
+        Some(Type::Ref(output)) => &output.inner,
+        _ => unreachable!(),
+    };
+    let cfg = expand_cfg(&efn.cfg);
+    let span = ident.span();
+
+    quote_spanned! {span=>
+        #cfg
+        impl ::cxx::core::ops::Index<#ty> for #receiver_ident #receiver_generics {
+            type Output = #output;
+            fn index(&self, #var: #ty) -> &Self::Output {
+                <#receiver_ident #receiver_generics>::#ident(self, #var)
+            }
+        }
+    }
+}
+
+fn expand_cxx_function_arg_var(arg: &Var, types: &Types) -> TokenStream {
+    let var = &arg.name.rust;
+    let span = var.span();
+    match &arg.ty {
+        Type::Ident(ident) if ident.rust == RustString => {
+            quote_spanned!(span=> #var.as_mut_ptr() as *const ::cxx::private::RustString)
+        }
+        Type::RustBox(ty) => {
+            if types.is_considered_improper_ctype(&ty.inner) {
+                quote_spanned!(span=> ::cxx::alloc::boxed::Box::into_raw(#var).cast())
+            } else {
+                quote_spanned!(span=> ::cxx::alloc::boxed::Box::into_raw(#var))
+            }
+        }
+        Type::RustArc(ty) => {
+            if types.is_considered_improper_ctype(&ty.inner) {
+                quote_spanned!(span=> ::cxx::alloc::sync::Arc::into_raw(#var).cast())
+            } else {
+                quote_spanned!(span=> ::cxx::alloc::sync::Arc::into_raw(#var))
+            }
+        }
+        Type::UniquePtr(ty) => {
+            if types.is_considered_improper_ctype(&ty.inner) {
+                quote_spanned!(span=> ::cxx::UniquePtr::into_raw(#var).cast())
+            } else {
+                quote_spanned!(span=> ::cxx::UniquePtr::into_raw(#var))
+            }
+        }
+        Type::RustVec(_) => {
+            quote_spanned!(span=> #var.as_mut_ptr() as *const ::cxx::private::RustVec<_>)
+        }
+        Type::Ref(ty) => match &ty.inner {
+            Type::Ident(ident) if ident.rust == RustString => match ty.mutable {
+                false => quote_spanned!(span=> ::cxx::private::RustString::from_ref(#var)),
+                true => quote_spanned!(span=> ::cxx::private::RustString::from_mut(#var)),
+            },
+            Type::RustVec(vec) if vec.inner == RustString => match ty.mutable {
+                false => quote_spanned!(span=> ::cxx::private::RustVec::from_ref_vec_string(#var)),
+                true => quote_spanned!(span=> ::cxx::private::RustVec::from_mut_vec_string(#var)),
+            },
+            Type::RustVec(_) => match ty.mutable {
+                false => quote_spanned!(span=> ::cxx::private::RustVec::from_ref(#var)),
+                true => quote_spanned!(span=> ::cxx::private::RustVec::from_mut(#var)),
+            },
+            inner if types.is_considered_improper_ctype(inner) => {
+                let var = match ty.pinned {
+                    false => quote!(#var),
+                    true => {
+                        quote_spanned!(span=> ::cxx::core::pin::Pin::into_inner_unchecked(#var))
+                    }
+                };
+                match ty.mutable {
+                    false => {
+                        quote_spanned!(span=> #var as *const #inner as *const ::cxx::core::ffi::c_void)
+                    }
+                    true => {
+                        quote_spanned!(span=> #var as *mut #inner as *mut ::cxx::core::ffi::c_void)
+                    }
+                }
+            }
+            _ => quote!(#var),
+        },
+        Type::Ptr(ty) => {
+            if types.is_considered_improper_ctype(&ty.inner) {
+                quote_spanned!(span=> #var.cast())
+            } else {
+                quote!(#var)
+            }
+        }
+        Type::Str(_) => quote_spanned!(span=> ::cxx::private::RustStr::from(#var)),
+        Type::SliceRef(ty) => match ty.mutable {
+            false => quote_spanned!(span=> ::cxx::private::RustSlice::from_ref(#var)),
+            true => quote_spanned!(span=> ::cxx::private::RustSlice::from_mut(#var)),
+        },
+        Type::Tuple(_) => quote_spanned!(span=> #var.as_mut_ptr().cast()),
+        ty if types.needs_indirect_abi(ty) => quote_spanned!(span=> #var.as_mut_ptr()),
+        _ => quote!(#var),
+    }
+}
+
+fn expand_cxx_function_arg_setup(sig: &Signature, types: &Types) -> TokenStream {
+    sig.args
         .iter()
         .filter(|arg| types.needs_indirect_abi(&arg.ty))
         .map(|arg| {
@@ -608,29 +988,76 @@ fn expand_cxx_function_shim(efn: &ExternFn, types: &Types) -> TokenStream {
             let span = var.span();
             // These are arguments for which C++ has taken ownership of the data
             // behind the mut reference it received.
-            quote_spanned! {span=>
-                let mut #var = ::cxx::core::mem::MaybeUninit::new(#var);
+            match &arg.ty {
+                Type::Tuple(tuple) => {
+                    let repr = expand_tuple_repr(tuple, false);
+                    quote_spanned! {span=>
+                        let mut #var = ::cxx::core::mem::MaybeUninit::new(#repr::from(#var));
+                    }
+                }
+                _ => quote_spanned! {span=>
+                    let mut #var = ::cxx::core::mem::MaybeUninit::new(#var);
+                },
             }
         })
-        .collect::<TokenStream>();
-    let local_name = format_ident!("__{}", efn.name.rust);
-    let span = efn.semi_token.span;
-    let call = if indirect_return {
-        let ret = expand_extern_type(efn.ret.as_ref().unwrap(), types, true);
+        .collect()
+}
+
+fn expand_cxx_function_call(
+    sig: &Signature,
+    types: &Types,
+    local_name: &Ident,
+    vars: impl Iterator<Item = TokenStream>,
+    setup: &mut TokenStream,
+    span: Span,
+) -> TokenStream {
+    if let Some(error) = &sig.error {
+        let error = expand_extern_type(error, types, true);
+        setup.extend(quote_spanned! {span=>
+            let mut __error = ::cxx::core::mem::MaybeUninit::<#error>::uninit();
+        });
+        let mut out = None;
+        if indirect_return(sig, types) {
+            let ret = expand_extern_type(sig.ret.as_ref().unwrap(), types, true);
+            setup.extend(quote_spanned! {span=>
+                let mut __return = ::cxx::core::mem::MaybeUninit::<#ret>::uninit();
+            });
+            out = Some(match sig.ret.as_ref().unwrap() {
+                Type::Tuple(_) => quote_spanned!(span=> __return.as_mut_ptr().cast(),),
+                _ => quote_spanned!(span=> __return.as_mut_ptr(),),
+            });
+        }
+        setup.extend(quote_spanned! {span=>
+            if !#local_name(#(#vars,)* #out __error.as_mut_ptr()) {
+                return ::cxx::core::result::Result::Err(__error.assume_init());
+            }
+        });
+        return if sig.ret.is_some() {
+            quote_spanned!(span=> __return.assume_init())
+        } else {
+            quote_spanned!(span=> ::cxx::core::result::Result::Ok(()))
+        };
+    }
+    if indirect_return(sig, types) {
+        let ret = expand_extern_type(sig.ret.as_ref().unwrap(), types, true);
         setup.extend(quote_spanned! {span=>
             let mut __return = ::cxx::core::mem::MaybeUninit::<#ret>::uninit();
         });
-        setup.extend(if efn.throws {
+        let out = match sig.ret.as_ref().unwrap() {
+            Type::Tuple(_) => quote_spanned!(span=> __return.as_mut_ptr().cast()),
+            _ => quote_spanned!(span=> __return.as_mut_ptr()),
+        };
+        setup.extend(if sig.throws {
             quote_spanned! {span=>
-                #local_name(#(#vars,)* __return.as_mut_ptr()).exception()?;
+                #local_name(#(#vars,)* #out).exception()?;
             }
         } else {
             quote_spanned! {span=>
-                #local_name(#(#vars,)* __return.as_mut_ptr());
+                #local_name(#(#vars,)* #out);
             }
         });
         quote_spanned!(span=> __return.assume_init())
-    } else if efn.throws {
+    } else if sig.throws {
         quote_spanned! {span=>
             #local_name(#(#vars),*).exception()
         }
@@ -638,12 +1065,20 @@ fn expand_cxx_function_shim(efn: &ExternFn, types: &Types) -> TokenStream {
         quote_spanned! {span=>
             #local_name(#(#vars),*)
         }
-    };
+    }
+}
+
+fn expand_cxx_function_return(
+    sig: &Signature,
+    types: &Types,
+    call: TokenStream,
+    span: Span,
+) -> TokenStream {
     let mut expr;
-    if efn.throws && efn.sig.ret.is_none() {
+    if sig.throws && sig.ret.is_none() {
         expr = call;
     } else {
-        expr = match &efn.ret {
+        expr = match &sig.ret {
             None => call,
             Some(ret) => match ret {
                 Type::Ident(ident) if ident.rust == RustString => {
@@ -656,6 +1091,13 @@ fn expand_cxx_function_shim(efn: &ExternFn, types: &Types) -> TokenStream {
                         quote_spanned!(span=> ::cxx::alloc::boxed::Box::from_raw(#call))
                     }
                 }
+                Type::RustArc(ty) => {
+                    if types.is_considered_improper_ctype(&ty.inner) {
+                        quote_spanned!(span=> ::cxx::alloc::sync::Arc::from_raw(#call.cast()))
+                    } else {
+                        quote_spanned!(span=> ::cxx::alloc::sync::Arc::from_raw(#call))
+                    }
+                }
                 Type::RustVec(vec) => {
                     if vec.inner == RustString {
                         quote_spanned!(span=> #call.into_vec_string())
@@ -710,68 +1152,74 @@ fn expand_cxx_function_shim(efn: &ExternFn, types: &Types) -> TokenStream {
                         true => quote_spanned!(span=> #call.as_mut_slice::<#inner>()),
                     }
                 }
+                Type::Tuple(_) => quote_spanned!(span=> #call.into_tuple()),
                 _ => call,
             },
         };
-        if efn.throws {
+        if sig.throws {
             expr = quote_spanned!(span=> ::cxx::core::result::Result::Ok(#expr));
         }
+    }
+    expr
+}
+
+fn expand_cxx_constructor_in_place(
+    efn: &ExternFn,
+    types: &Types,
+    decl: &TokenStream,
+    setup: &TokenStream,
+    call: &TokenStream,
+) -> TokenStream {
+    let doc = &efn.doc;
+    let attrs = &efn.attrs;
+    let self_type = &efn.self_type.as_ref().unwrap().rust;
+    let resolve = types.resolve(self_type);
+    let link_sizeof = mangle::operator(resolve.name, "sizeof");
+    let link_alignof = mangle::operator(resolve.name, "alignof");
+    let link_drop = mangle::operator(resolve.name, "drop");
+    let args = efn.args.iter().map(|arg| quote!(#arg));
+    let span = efn.semi_token.span;
+    let ret = if efn.throws {
+        quote!(::cxx::core::result::Result<__R, ::cxx::Exception>)
+    } else {
+        quote!(__R)
+    };
+    let result = if efn.throws {
+        quote_spanned!(span=> ::cxx::core::result::Result::Ok(__f(__storage.init(__drop))))
+    } else {
+        quote_spanned!(span=> __f(__storage.init(__drop)))
+    };
+    let mut dispatch = quote_spanned! {span=>
+        let mut __storage = ::cxx::private::StackSlot::<#self_type>::new(__sizeof(), __alignof());
+        let __slot = __storage.as_mut_ptr();
+        #setup
+        let _ = #call;
+        #result
     };
-    let mut dispatch = quote!(#setup #expr);
     let visibility = efn.visibility;
     let unsafety = &efn.sig.unsafety;
     if unsafety.is_none() {
         dispatch = quote_spanned!(span=> unsafe { #dispatch });
     }
     let fn_token = efn.sig.fn_token;
-    let ident = &efn.name.rust;
-    let generics = &efn.generics;
-    let arg_list = quote_spanned!(efn.sig.paren_token.span=> (#(#all_args,)*));
-    let fn_body = quote_spanned!(span=> {
-        extern "C" {
-            #decl
-        }
-        #trampolines
-        #dispatch
-    });
-    match &efn.receiver {
-        None => {
-            quote! {
-                #doc
-                #attrs
-                #visibility #unsafety #fn_token #ident #generics #arg_list #ret #fn_body
-            }
-        }
-        Some(receiver) => {
-            let elided_generics;
-            let receiver_ident = &receiver.ty.rust;
-            let resolve = types.resolve(&receiver.ty);
-            let receiver_generics = if receiver.ty.generics.lt_token.is_some() {
-                &receiver.ty.generics
-            } else {
-                elided_generics = Lifetimes {
-                    lt_token: resolve.generics.lt_token,
-                    lifetimes: resolve
-                        .generics
-                        .lifetimes
-                        .pairs()
-                        .map(|pair| {
-                            let lifetime = Lifetime::new("'_", pair.value().apostrophe);
-                            let punct = pair.punct().map(|&&comma| comma);
-                            punctuated::Pair::new(lifetime, punct)
-                        })
-                        .collect(),
-                    gt_token: resolve.generics.gt_token,
-                };
-                &elided_generics
-            };
-            quote_spanned! {ident.span()=>
-                impl #generics #receiver_ident #receiver_generics {
-                    #doc
-                    #attrs
-                    #visibility #unsafety #fn_token #ident #arg_list #ret #fn_body
-                }
-            }
+    let ident = format_ident!("{}_in_place", efn.name.rust);
+    quote_spanned! {span=>
+        #doc
+        #attrs
+        #visibility #unsafety #fn_token #ident<__F, __R>(#(#args,)* __f: __F) -> #ret
+        where
+            __F: ::cxx::core::ops::FnOnce(::cxx::core::pin::Pin<&mut #self_type>) -> __R,
+        {
+            extern "C" {
+                #decl
+                #[link_name = #link_sizeof]
+                fn __sizeof() -> usize;
+                #[link_name = #link_alignof]
+                fn __alignof() -> usize;
+                #[link_name = #link_drop]
+                fn __drop(this: *mut ::cxx::core::ffi::c_void);
+            }
+            #dispatch
         }
     }
 }
@@ -781,6 +1229,38 @@ fn expand_function_pointer_trampoline(
     var: &Pair,
     sig: &Signature,
     types: &Types,
+) -> TokenStream {
+    let trampoline = expand_trampoline(efn, var, sig, Invoke::FunctionPointer, types);
+    let var = &var.rust;
+
+    quote! {
+        let #var = ::cxx::private::FatFunction {
+            trampoline: #trampoline,
+            ptr: #var as usize as *const ::cxx::core::ffi::c_void,
+        };
+    }
+}
+
+fn expand_closure_trampoline(
+    efn: &ExternFn,
+    var: &Pair,
+    closure: &DynFn,
+    types: &Types,
+) -> TokenStream {
+    let trampoline = expand_trampoline(efn, var, &closure.sig, Invoke::Closure(closure), types);
+    let var = &var.rust;
+
+    quote! {
+        let #var = ::cxx::private::CxxFnMut::new(#trampoline, #var);
+    }
+}
+
+fn expand_trampoline(
+    efn: &ExternFn,
+    var: &Pair,
+    sig: &Signature,
+    invoke: Invoke,
+    types: &Types,
 ) -> TokenStream {
     let c_trampoline = mangle::c_trampoline(efn, var, types);
     let r_trampoline = mangle::r_trampoline(efn, var, types);
@@ -793,25 +1273,97 @@ fn expand_function_pointer_trampoline(
         &r_trampoline,
         local_name,
         prevent_unwind_label,
-        None,
+        invoke,
         Some(&efn.generics),
         &efn.attrs,
         body_span,
     );
-    let var = &var.rust;
 
     quote! {
-        let #var = ::cxx::private::FatFunction {
-            trampoline: {
-                extern "C" {
-                    #[link_name = #c_trampoline]
-                    fn trampoline();
-                }
-                #shim
-                trampoline as usize as *const ::cxx::core::ffi::c_void
-            },
-            ptr: #var as usize as *const ::cxx::core::ffi::c_void,
+        {
+            extern "C" {
+                #[link_name = #c_trampoline]
+                fn trampoline();
+            }
+            #shim
+            trampoline as usize as *const ::cxx::core::ffi::c_void
+        }
+    }
+}
+
+// A C++ callable passed into Rust by value, wrapped up as the Box<dyn FnMut>
+// that the Rust function signature calls for.
+fn expand_closure_from_cxx(
+    efn: &ExternFn,
+    var: &Pair,
+    closure: &DynFn,
+    types: &Types,
+) -> TokenStream {
+    let sig = &closure.sig;
+    let link_name = mangle::closure_call(efn, var, types);
+    let local_name = format_ident!("__call");
+    let span = efn.semi_token.span;
+
+    let args = sig
+        .args
+        .iter()
+        .map(|arg| expand_cxx_function_decl_arg(arg, types));
+    let (ret, outparam) = expand_cxx_function_decl_ret(sig, types);
+
+    let params = sig.args.iter().map(|arg| quote!(#arg));
+    let self_var = quote_spanned!(span=> __self);
+    let arg_vars = sig
+        .args
+        .iter()
+        .map(|arg| expand_cxx_function_arg_var(arg, types));
+    let vars = Some(self_var).into_iter().chain(arg_vars);
+    let mut setup = expand_cxx_function_arg_setup(sig, types);
+    let call = expand_cxx_function_call(sig, types, &local_name, vars, &mut setup, span);
+    let expr = expand_cxx_function_return(sig, types, call, span);
+
+    let body = if sig.throws {
+        let ok = match &sig.ret {
+            Some(ret) => quote!(#ret),
+            None => quote!(()),
         };
+        quote_spanned! {span=>
+            let __result = (|| -> ::cxx::core::result::Result<#ok, ::cxx::Exception> {
+                unsafe { #setup #expr }
+            })();
+            ::cxx::core::result::Result::map_err(__result, |__error| {
+                ::cxx::alloc::string::String::from(__error.what())
+            })
+        }
+    } else {
+        quote_spanned! {span=>
+            unsafe { #setup #expr }
+        }
+    };
+
+    let var = &var.rust;
+    let (callable, self_ptr) = if closure.send.is_some() {
+        (
+            quote_spanned!(span=> ::cxx::private::SendCxxFnMut::new(::cxx::core::ptr::read(#var))),
+            quote_spanned!(span=> __callable.as_mut_ptr()),
+        )
+    } else {
+        (
+            quote_spanned!(span=> ::cxx::core::ptr::read(#var)),
+            quote_spanned!(span=> &mut __callable as *mut ::cxx::private::CxxFnMut),
+        )
+    };
+    quote_spanned! {span=>
+        {
+            extern "C" {
+                #[link_name = #link_name]
+                fn #local_name(__self: *mut ::cxx::private::CxxFnMut, #(#args,)* #outparam) #ret;
+            }
+            let mut __callable = #callable;
+            ::cxx::alloc::boxed::Box::new(move |#(#params),*| {
+                let __self = #self_ptr;
+                #body
+            })
+        }
     }
 }
 
@@ -928,15 +1480,19 @@ fn expand_forbid(impls: TokenStream) -> TokenStream {
 
 fn expand_rust_function_shim(efn: &ExternFn, types: &Types) -> TokenStream {
     let link_name = mangle::extern_fn(efn, types);
-    let local_name = match &efn.receiver {
+    let self_type = match &efn.receiver {
+        None => efn.self_type.as_ref(),
+        Some(receiver) => Some(&receiver.ty),
+    };
+    let local_name = match self_type {
         None => format_ident!("__{}", efn.name.rust),
-        Some(receiver) => format_ident!("__{}__{}", receiver.ty.rust, efn.name.rust),
+        Some(self_type) => format_ident!("__{}__{}", self_type.rust, efn.name.rust),
     };
-    let prevent_unwind_label = match &efn.receiver {
+    let prevent_unwind_label = match self_type {
         None => format!("::{}", efn.name.rust),
-        Some(receiver) => format!("::{}::{}", receiver.ty.rust, efn.name.rust),
+        Some(self_type) => format!("::{}::{}", self_type.rust, efn.name.rust),
     };
-    let invoke = Some(&efn.name.rust);
+    let invoke = Invoke::Function(efn);
     let body_span = efn.semi_token.span;
     expand_rust_function_shim_impl(
         efn,
@@ -951,13 +1507,23 @@ fn expand_rust_function_shim(efn: &ExternFn, types: &Types) -> TokenStream {
     )
 }
 
+// What an exported Rust function shim ends up calling into.
+enum Invoke<'a> {
+    // The user's extern "Rust" function by the same name.
+    Function(&'a ExternFn),
+    // A Rust function pointer that C++ passes back in as the final argument.
+    FunctionPointer,
+    // A boxed Rust closure that C++ passes back in as the final argument.
+    Closure(&'a DynFn),
+}
+
 fn expand_rust_function_shim_impl(
     sig: &Signature,
     types: &Types,
     link_name: &Symbol,
     local_name: Ident,
     prevent_unwind_label: String,
-    invoke: Option<&Ident>,
+    invoke: Invoke,
     outer_generics: Option<&Generics>,
     attrs: &OtherAttrs,
     body_span: Span,
@@ -992,6 +1558,7 @@ fn expand_rust_function_shim_impl(
                 quote_spanned!(span=> ::cxx::core::mem::take((*#var).as_mut_string()))
             }
             Type::RustBox(_) => quote_spanned!(span=> ::cxx::alloc::boxed::Box::from_raw(#var)),
+            Type::RustArc(_) => quote_spanned!(span=> ::cxx::alloc::sync::Arc::from_raw(#var)),
             Type::RustVec(vec) => {
                 if vec.inner == RustString {
                     quote_spanned!(span=> ::cxx::core::mem::take((*#var).as_mut_vec_string()))
@@ -1023,6 +1590,12 @@ fn expand_rust_function_shim_impl(
                     true => quote_spanned!(span=> #var.as_mut_slice::<#inner>()),
                 }
             }
+            Type::Tuple(_) => quote_spanned!(span=> ::cxx::core::ptr::read(#var).into_tuple()),
+            Type::DynFn(closure) => match invoke {
+                Invoke::Function(efn) => expand_closure_from_cxx(efn, &arg.name, closure, types),
+                // Rejected by check_type_fn and check_type_dyn_fn.
+                Invoke::FunctionPointer | Invoke::Closure(_) => unreachable!(),
+            },
             ty if types.needs_indirect_abi(ty) => {
                 quote_spanned!(span=> ::cxx::core::ptr::read(#var))
             }
@@ -1031,18 +1604,29 @@ fn expand_rust_function_shim_impl(
     });
     let vars: Vec<_> = receiver_var.into_iter().chain(arg_vars).collect();
 
-    let wrap_super = invoke.map(|invoke| expand_rust_function_shim_super(sig, &local_name, invoke));
+    let wrap_super = match invoke {
+        Invoke::Function(efn) => Some(expand_rust_function_shim_super(
+            sig,
+            &local_name,
+            &efn.name.rust,
+        )),
+        Invoke::FunctionPointer | Invoke::Closure(_) => None,
+    };
 
     let mut requires_closure;
     let mut call = match invoke {
-        Some(_) => {
+        Invoke::Function(_) => {
             requires_closure = false;
             quote!(#local_name)
         }
-        None => {
+        Invoke::FunctionPointer => {
             requires_closure = true;
             quote!(::cxx::core::mem::transmute::<*const (), #sig>(__extern))
         }
+        Invoke::Closure(closure) => {
+            requires_closure = true;
+            quote!((*(__extern as *mut #closure)))
+        }
     };
     requires_closure |= !vars.is_empty();
     call.extend(quote! { (#(#vars),*) });
@@ -1053,6 +1637,7 @@ fn expand_rust_function_shim_impl(
             Some(quote_spanned!(span=> ::cxx::private::RustString::from))
         }
         Type::RustBox(_) => Some(quote_spanned!(span=> ::cxx::alloc::boxed::Box::into_raw)),
+        Type::RustArc(_) => Some(quote_spanned!(span=> ::cxx::alloc::sync::Arc::into_raw)),
         Type::RustVec(vec) => {
             if vec.inner == RustString {
                 Some(quote_spanned!(span=> ::cxx::private::RustVec::from_vec_string))
@@ -1081,6 +1666,10 @@ fn expand_rust_function_shim_impl(
             false => Some(quote_spanned!(span=> ::cxx::private::RustSlice::from_ref)),
             true => Some(quote_spanned!(span=> ::cxx::private::RustSlice::from_mut)),
         },
+        Type::Tuple(tuple) => {
+            let repr = expand_tuple_repr(tuple, false);
+            Some(quote_spanned!(span=> #repr::from))
+        }
         _ => None,
     });
 
@@ -1114,6 +1703,17 @@ fn expand_rust_function_shim_impl(
         expr = quote_spanned!(span=> ::cxx::core::ptr::write(__return, #expr));
     }
 
+    let catch_unwind = match invoke {
+        Invoke::Function(efn) => efn.catch_unwind,
+        Invoke::FunctionPointer | Invoke::Closure(_) => false,
+    };
+    let mut panicparam = None;
+    if catch_unwind {
+        panicparam = Some(quote_spanned!(span=> __panic: *mut ::cxx::private::Panic,));
+        requires_closure = true;
+        expr = quote_spanned!(span=> ::cxx::private::catch_unwind(__panic, move || #expr));
+    }
+
     let closure = if requires_closure {
         quote_spanned!(span=> move || #expr)
     } else {
@@ -1129,15 +1729,17 @@ fn expand_rust_function_shim_impl(
     };
 
     let pointer = match invoke {
-        None => Some(quote_spanned!(span=> __extern: *const ())),
-        Some(_) => None,
+        Invoke::Function(_) => None,
+        Invoke::FunctionPointer | Invoke::Closure(_) => {
+            Some(quote_spanned!(span=> __extern: *const ()))
+        }
     };
 
     quote_spanned! {span=>
         #attrs
         #[doc(hidden)]
         #[export_name = #link_name]
-        unsafe extern "C" fn #local_name #generics(#(#all_args,)* #outparam #pointer) #ret {
+        unsafe extern "C" fn #local_name #generics(#(#all_args,)* #outparam #panicparam #pointer) #ret {
             let __fn = ::cxx::private::concat!(::cxx::private::module_path!(), #prevent_unwind_label);
             #wrap_super
             #expr
@@ -1184,12 +1786,16 @@ fn expand_rust_function_shim_super(
     let vars = receiver_var.iter().chain(arg_vars);
 
     let span = invoke.span();
-    let call = match &sig.receiver {
-        None => quote_spanned!(span=> super::#invoke),
-        Some(receiver) => {
+    let call = match (&sig.receiver, &sig.self_type) {
+        (Some(receiver), _) => {
             let receiver_type = &receiver.ty.rust;
             quote_spanned!(span=> #receiver_type::#invoke)
         }
+        (None, Some(self_type)) => {
+            let self_type = &self_type.rust;
+            quote_spanned!(span=> #self_type::#invoke)
+        }
+        (None, None) => quote_spanned!(span=> super::#invoke),
     };
 
     quote_spanned! {span=>
@@ -1217,6 +1823,25 @@ fn expand_type_alias(alias: &TypeAlias) -> TokenStream {
     }
 }
 
+fn expand_const(konst: &Const) -> TokenStream {
+    let doc = &konst.doc;
+    let attrs = &konst.attrs;
+    let visibility = konst.visibility;
+    let const_token = konst.const_token;
+    let ident = &konst.name.rust;
+    let colon_token = konst.colon_token;
+    let ty = &konst.ty;
+    let eq_token = konst.eq_token;
+    let value = &konst.value;
+    let semi_token = konst.semi_token;
+
+    quote! {
+        #doc
+        #attrs
+        #visibility #const_token #ident #colon_token #ty #eq_token #value #semi_token
+    }
+}
+
 fn expand_type_alias_verify(alias: &TypeAlias, types: &Types) -> TokenStream {
     let ident = &alias.name.rust;
     let type_id = type_id(&alias.name);
@@ -1251,7 +1876,7 @@ fn type_id(name: &Pair) -> TokenStream {
 fn expand_rust_box(key: NamedImplKey, types: &Types, explicit_impl: Option<&Impl>) -> TokenStream {
     let ident = key.rust;
     let resolve = types.resolve(ident);
-    let link_prefix = format!("cxxbridge1$box${}$", resolve.name.to_symbol());
+    let link_prefix = format!("{}$box${}$", CXXBRIDGE, resolve.name.to_symbol());
     let link_alloc = format!("{}alloc", link_prefix);
     let link_dealloc = format!("{}dealloc", link_prefix);
     let link_drop = format!("{}drop", link_prefix);
@@ -1296,10 +1921,46 @@ fn expand_rust_box(key: NamedImplKey, types: &Types, explicit_impl: Option<&Impl
     }
 }
 
+fn expand_rust_arc(key: NamedImplKey, types: &Types, explicit_impl: Option<&Impl>) -> TokenStream {
+    let ident = key.rust;
+    let resolve = types.resolve(ident);
+    let link_prefix = format!("{}$arc${}$", CXXBRIDGE, resolve.name.to_symbol());
+    let link_increment = format!("{}increment", link_prefix);
+    let link_drop = format!("{}drop", link_prefix);
+
+    let local_prefix = format_ident!("{}__arc_", ident);
+    let local_increment = format_ident!("{}increment", local_prefix);
+    let local_drop = format_ident!("{}drop", local_prefix);
+
+    let (impl_generics, ty_generics) = generics::split_for_impl(key, explicit_impl, resolve);
+
+    let begin_span = explicit_impl.map_or(key.begin_span, |explicit| explicit.impl_token.span);
+    let end_span = explicit_impl.map_or(key.end_span, |explicit| explicit.brace_token.span.join());
+    let unsafe_token = format_ident!("unsafe", span = begin_span);
+    let prevent_unwind_drop_label = format!("::{} as Drop>::drop", ident);
+
+    quote_spanned! {end_span=>
+        #[doc(hidden)]
+        #unsafe_token impl #impl_generics ::cxx::private::ImplArc for #ident #ty_generics {}
+        #[doc(hidden)]
+        #[export_name = #link_increment]
+        unsafe extern "C" fn #local_increment #impl_generics(ptr: *const #ident #ty_generics) {
+            let arc = ::cxx::core::mem::ManuallyDrop::new(::cxx::alloc::sync::Arc::from_raw(ptr));
+            let _ = ::cxx::core::mem::ManuallyDrop::new(::cxx::alloc::sync::Arc::clone(&arc));
+        }
+        #[doc(hidden)]
+        #[export_name = #link_drop]
+        unsafe extern "C" fn #local_drop #impl_generics(ptr: *const #ident #ty_generics) {
+            let __fn = concat!("<", module_path!(), #prevent_unwind_drop_label);
+            ::cxx::private::prevent_unwind(__fn, || ::cxx::core::mem::drop(::cxx::alloc::sync::Arc::from_raw(ptr)));
+        }
+    }
+}
+
 fn expand_rust_vec(key: NamedImplKey, types: &Types, explicit_impl: Option<&Impl>) -> TokenStream {
     let elem = key.rust;
     let resolve = types.resolve(elem);
-    let link_prefix = format!("cxxbridge1$rust_vec${}$", resolve.name.to_symbol());
+    let link_prefix = format!("{}$rust_vec${}$", CXXBRIDGE, resolve.name.to_symbol());
     let link_new = format!("{}new", link_prefix);
     let link_drop = format!("{}drop", link_prefix);
     let link_len = format!("{}len", link_prefix);
@@ -1388,7 +2049,7 @@ fn expand_unique_ptr(
     let ident = key.rust;
     let name = ident.to_string();
     let resolve = types.resolve(ident);
-    let prefix = format!("cxxbridge1$unique_ptr${}$", resolve.name.to_symbol());
+    let prefix = format!("{}$unique_ptr${}$", CXXBRIDGE, resolve.name.to_symbol());
     let link_null = format!("{}null", prefix);
     let link_uninit = format!("{}uninit", prefix);
     let link_raw = format!("{}raw", prefix);
@@ -1398,7 +2059,8 @@ fn expand_unique_ptr(
 
     let (impl_generics, ty_generics) = generics::split_for_impl(key, explicit_impl, resolve);
 
-    let can_construct_from_value = types.is_maybe_trivial(ident);
+    let can_construct_from_value =
+        types.is_maybe_trivial(ident) && !types.unique_ptr_deleters.contains_key(ident);
     let new_method = if can_construct_from_value {
         Some(quote! {
             fn __new(value: Self) -> ::cxx::core::mem::MaybeUninit<*mut ::cxx::core::ffi::c_void> {
@@ -1419,7 +2081,17 @@ fn expand_unique_ptr(
     let end_span = explicit_impl.map_or(key.end_span, |explicit| explicit.brace_token.span.join());
     let unsafe_token = format_ident!("unsafe", span = begin_span);
 
+    let box_deleter = if types.rust.contains(ident) {
+        Some(quote_spanned! {end_span=>
+            #[doc(hidden)]
+            #unsafe_token impl #impl_generics ::cxx::private::ImplBoxDeleter for #ident #ty_generics {}
+        })
+    } else {
+        None
+    };
+
     quote_spanned! {end_span=>
+        #box_deleter
         #unsafe_token impl #impl_generics ::cxx::private::UniquePtrTarget for #ident #ty_generics {
             fn __typename(f: &mut ::cxx::core::fmt::Formatter<'_>) -> ::cxx::core::fmt::Result {
                 f.write_str(#name)
@@ -1476,12 +2148,17 @@ fn expand_shared_ptr(
     let ident = key.rust;
     let name = ident.to_string();
     let resolve = types.resolve(ident);
-    let prefix = format!("cxxbridge1$shared_ptr${}$", resolve.name.to_symbol());
+    let prefix = format!("{}$shared_ptr${}$", CXXBRIDGE, resolve.name.to_symbol());
     let link_null = format!("{}null", prefix);
     let link_uninit = format!("{}uninit", prefix);
     let link_clone = format!("{}clone", prefix);
     let link_get = format!("{}get", prefix);
     let link_drop = format!("{}drop", prefix);
+    let link_use_count = format!("{}use_count", prefix);
+    let link_owner_before = format!("{}owner_before", prefix);
+    let link_alias = format!("{}alias", prefix);
+    let link_from_alias = format!("{}from_alias", prefix);
+    let link_shared_from_this = format!("{}shared_from_this", prefix);
 
     let (impl_generics, ty_generics) = generics::split_for_impl(key, explicit_impl, resolve);
 
@@ -1538,6 +2215,41 @@ fn expand_shared_ptr(
                 }
                 __drop(this);
             }
+            unsafe fn __use_count(this: *const ::cxx::core::ffi::c_void) -> usize {
+                extern "C" {
+                    #[link_name = #link_use_count]
+                    fn __use_count(this: *const ::cxx::core::ffi::c_void) -> usize;
+                }
+                __use_count(this)
+            }
+            unsafe fn __owner_before(this: *const ::cxx::core::ffi::c_void, alias: *mut ::cxx::core::ffi::c_void) -> bool {
+                extern "C" {
+                    #[link_name = #link_owner_before]
+                    fn __owner_before(this: *const ::cxx::core::ffi::c_void, alias: *mut ::cxx::core::ffi::c_void) -> bool;
+                }
+                __owner_before(this, alias)
+            }
+            unsafe fn __alias(this: *const ::cxx::core::ffi::c_void, ptr: *mut ::cxx::core::ffi::c_void, alias: *mut ::cxx::core::ffi::c_void) {
+                extern "C" {
+                    #[link_name = #link_alias]
+                    fn __alias(this: *const ::cxx::core::ffi::c_void, ptr: *mut ::cxx::core::ffi::c_void, alias: *mut ::cxx::core::ffi::c_void);
+                }
+                __alias(this, ptr, alias);
+            }
+            unsafe fn __from_alias(alias: *mut ::cxx::core::ffi::c_void, new: *mut ::cxx::core::ffi::c_void) {
+                extern "C" {
+                    #[link_name = #link_from_alias]
+                    fn __from_alias(alias: *mut ::cxx::core::ffi::c_void, new: *mut ::cxx::core::ffi::c_void);
+                }
+                __from_alias(alias, new);
+            }
+            unsafe fn __shared_from_this(value: *const Self, new: *mut ::cxx::core::ffi::c_void) {
+                extern "C" {
+                    #[link_name = #link_shared_from_this]
+                    fn __shared_from_this(value: *const ::cxx::core::ffi::c_void, new: *mut ::cxx::core::ffi::c_void);
+                }
+                __shared_from_this(value.cast(), new);
+            }
         }
     }
 }
@@ -1546,12 +2258,13 @@ fn expand_weak_ptr(key: NamedImplKey, types: &Types, explicit_impl: Option<&Impl
     let ident = key.rust;
     let name = ident.to_string();
     let resolve = types.resolve(ident);
-    let prefix = format!("cxxbridge1$weak_ptr${}$", resolve.name.to_symbol());
+    let prefix = format!("{}$weak_ptr${}$", CXXBRIDGE, resolve.name.to_symbol());
     let link_null = format!("{}null", prefix);
     let link_clone = format!("{}clone", prefix);
     let link_downgrade = format!("{}downgrade", prefix);
     let link_upgrade = format!("{}upgrade", prefix);
     let link_drop = format!("{}drop", prefix);
+    let link_use_count = format!("{}use_count", prefix);
 
     let (impl_generics, ty_generics) = generics::split_for_impl(key, explicit_impl, resolve);
 
@@ -1599,6 +2312,13 @@ fn expand_weak_ptr(key: NamedImplKey, types: &Types, explicit_impl: Option<&Impl
                 }
                 __drop(this);
             }
+            unsafe fn __use_count(this: *const ::cxx::core::ffi::c_void) -> usize {
+                extern "C" {
+                    #[link_name = #link_use_count]
+                    fn __use_count(this: *const ::cxx::core::ffi::c_void) -> usize;
+                }
+                __use_count(this)
+            }
         }
     }
 }
@@ -1611,13 +2331,14 @@ fn expand_cxx_vector(
     let elem = key.rust;
     let name = elem.to_string();
     let resolve = types.resolve(elem);
-    let prefix = format!("cxxbridge1$std$vector${}$", resolve.name.to_symbol());
+    let prefix = format!("{}$std$vector${}$", CXXBRIDGE, resolve.name.to_symbol());
     let link_size = format!("{}size", prefix);
     let link_get_unchecked = format!("{}get_unchecked", prefix);
     let link_push_back = format!("{}push_back", prefix);
     let link_pop_back = format!("{}pop_back", prefix);
     let unique_ptr_prefix = format!(
-        "cxxbridge1$unique_ptr$std$vector${}$",
+        "{}$unique_ptr$std$vector${}$",
+        CXXBRIDGE,
         resolve.name.to_symbol(),
     );
     let link_unique_ptr_null = format!("{}null", unique_ptr_prefix);
@@ -1760,6 +2481,15 @@ fn expand_extern_type(ty: &Type, types: &Types, proper: bool) -> TokenStream {
                 quote_spanned!(span=> *mut #inner)
             }
         }
+        Type::RustArc(ty) => {
+            let span = ty.name.span();
+            if proper && types.is_considered_improper_ctype(&ty.inner) {
+                quote_spanned!(span=> *const ::cxx::core::ffi::c_void)
+            } else {
+                let inner = expand_extern_type(&ty.inner, types, proper);
+                quote_spanned!(span=> *const #inner)
+            }
+        }
         Type::RustVec(ty) => {
             let span = ty.name.span();
             let langle = ty.langle;
@@ -1813,10 +2543,26 @@ fn expand_extern_type(ty: &Type, types: &Types, proper: bool) -> TokenStream {
             let rust_slice = Ident::new("RustSlice", ty.bracket.span.join());
             quote_spanned!(span=> ::cxx::private::#rust_slice)
         }
+        Type::Tuple(tuple) => expand_tuple_repr(tuple, true),
+        Type::DynFn(closure) => {
+            let span = closure.box_token.span();
+            quote_spanned!(span=> ::cxx::private::CxxFnMut)
+        }
         _ => quote!(#ty),
     }
 }
 
+fn expand_tuple_repr(tuple: &Tuple, with_elems: bool) -> TokenStream {
+    let span = tuple.paren_token.span.join();
+    let repr = format_ident!("Tuple{}", tuple.elems.len(), span = span);
+    if with_elems {
+        let elems = tuple.elems.iter();
+        quote_spanned!(span=> ::cxx::tuple::#repr<#(#elems),*>)
+    } else {
+        quote_spanned!(span=> ::cxx::tuple::#repr)
+    }
+}
+
 fn expand_extern_return_type(ret: &Option<Type>, types: &Types, proper: bool) -> TokenStream {
     let ret = match ret {
         Some(ret) if !types.needs_indirect_abi(ret) => ret,
@@ -0,0 +1,20 @@
+use core::ffi::c_void;
+
+/// Trait bound for C++ types which can be copied through their copy
+/// constructor.
+///
+/// Implemented by the `#[cxx::bridge]` macro for opaque C++ types declared with
+/// `#[derive(Clone)]`. It makes [`UniquePtr<T>`] implement `Clone` and enables
+/// [`SharedPtr::deep_clone`].
+///
+/// This trait has no publicly callable or implementable methods. Implementing
+/// it outside of the CXX codebase is not supported.
+///
+/// [`UniquePtr<T>`]: crate::UniquePtr
+/// [`SharedPtr::deep_clone`]: crate::SharedPtr::deep_clone
+pub unsafe trait CloneTarget {
+    #[doc(hidden)]
+    unsafe fn __clone(this: *const Self) -> *mut Self;
+    #[doc(hidden)]
+    unsafe fn __clone_shared(this: *const Self, new: *mut c_void);
+}
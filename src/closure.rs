@@ -0,0 +1,59 @@
+#![cfg(feature = "alloc")]
+#![allow(missing_docs)]
+
+use crate::unwind::prevent_unwind;
+use alloc::boxed::Box;
+use core::ffi::c_void;
+
+// ABI compatible with C++ rust::FnMut<Ret(Args...)>.
+//
+// Whichever side created the callable supplies `trampoline` to invoke it and
+// `drop` to destroy it; the other side only ever goes through those two.
+#[repr(C)]
+pub struct CxxFnMut {
+    pub trampoline: *const c_void,
+    pub drop: unsafe extern "C" fn(*mut c_void),
+    pub fun: *mut c_void,
+}
+
+impl CxxFnMut {
+    pub fn new<F>(trampoline: *const c_void, closure: F) -> Self {
+        unsafe extern "C" fn drop<F>(fun: *mut c_void) {
+            prevent_unwind("drop of closure passed to C++", || {
+                let _ = unsafe { Box::from_raw(fun.cast::<F>()) };
+            });
+        }
+
+        CxxFnMut {
+            trampoline,
+            drop: drop::<F>,
+            fun: Box::into_raw(Box::new(closure)).cast(),
+        }
+    }
+}
+
+impl Drop for CxxFnMut {
+    fn drop(&mut self) {
+        unsafe { (self.drop)(self.fun) }
+    }
+}
+
+// A C++ callable moved into Rust as a `Box<dyn FnMut + Send>`. Only closure
+// signatures declared with `+ Send` in the bridge use this wrapper; the others
+// hold a plain CxxFnMut, which is not Send.
+pub struct SendCxxFnMut(CxxFnMut);
+
+// The signature in the bridge promises that the callable is safe to call from
+// another thread, and C++ is trusted to uphold that promise like the rest of
+// the signature.
+unsafe impl Send for SendCxxFnMut {}
+
+impl SendCxxFnMut {
+    pub unsafe fn new(callable: CxxFnMut) -> Self {
+        SendCxxFnMut(callable)
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut CxxFnMut {
+        &mut self.0
+    }
+}
@@ -98,6 +98,7 @@ void panic [[noreturn]] (const char *msg) {
 #endif
 }
 
+template void panic<std::logic_error>[[noreturn]] (const char *msg);
 template void panic<std::out_of_range>[[noreturn]] (const char *msg);
 
 template <typename T>
@@ -511,6 +512,9 @@ CXX_RS_EXPORT const char *cxxbridge1$error(const char *ptr,
 }
 } // extern "C"
 
+Error::Error(const char *msg)
+    : msg(errorCopy(msg, std::strlen(msg) + 1)), len(std::strlen(msg) + 1) {}
+
 Error::Error(const Error &other)
     : std::exception(other),
       msg(other.msg ? errorCopy(other.msg, other.len) : nullptr),
@@ -549,6 +553,74 @@ Error &Error::operator=(Error &&other) &noexcept {
 
 const char *Error::what() const noexcept { return this->msg; }
 
+Panic::Panic(const Panic &other)
+    : std::exception(other),
+      msg(other.msg ? errorCopy(other.msg, other.len) : nullptr),
+      len(other.len),
+      file_(other.file_ ? errorCopy(other.file_, other.file_len) : nullptr),
+      file_len(other.file_len), line_(other.line_), column_(other.column_) {}
+
+Panic::Panic(Panic &&other) noexcept
+    : std::exception(std::move(other)), msg(other.msg), len(other.len),
+      file_(other.file_), file_len(other.file_len), line_(other.line_),
+      column_(other.column_) {
+  other.msg = nullptr;
+  other.len = 0;
+  other.file_ = nullptr;
+  other.file_len = 0;
+}
+
+Panic::~Panic() noexcept {
+  delete[] this->msg;
+  delete[] this->file_;
+}
+
+Panic &Panic::operator=(const Panic &other) & {
+  if (this != &other) {
+    std::exception::operator=(other);
+    delete[] this->msg;
+    delete[] this->file_;
+    this->msg = nullptr;
+    this->file_ = nullptr;
+    if (other.msg) {
+      this->msg = errorCopy(other.msg, other.len);
+      this->len = other.len;
+    }
+    if (other.file_) {
+      this->file_ = errorCopy(other.file_, other.file_len);
+      this->file_len = other.file_len;
+    }
+    this->line_ = other.line_;
+    this->column_ = other.column_;
+  }
+  return *this;
+}
+
+Panic &Panic::operator=(Panic &&other) &noexcept {
+  std::exception::operator=(std::move(other));
+  delete[] this->msg;
+  delete[] this->file_;
+  this->msg = other.msg;
+  this->len = other.len;
+  this->file_ = other.file_;
+  this->file_len = other.file_len;
+  this->line_ = other.line_;
+  this->column_ = other.column_;
+  other.msg = nullptr;
+  other.len = 0;
+  other.file_ = nullptr;
+  other.file_len = 0;
+  return *this;
+}
+
+const char *Panic::what() const noexcept { return this->msg; }
+
+const char *Panic::file() const noexcept { return this->file_; }
+
+std::uint32_t Panic::line() const noexcept { return this->line_; }
+
+std::uint32_t Panic::column() const noexcept { return this->column_; }
+
 namespace {
 template <typename T>
 union MaybeUninit {
@@ -767,6 +839,32 @@ static_assert(sizeof(std::string) <= kMaxExpectedWordsInString * sizeof(void *),
       const std::shared_ptr<CXX_TYPE> *self) noexcept {                        \
     self->~shared_ptr();                                                       \
   }                                                                            \
+  CXX_RS_EXPORT std::size_t cxxbridge1$std$shared_ptr$##RUST_TYPE##$use_count( \
+      const std::shared_ptr<CXX_TYPE> &self) noexcept {                        \
+    return static_cast<std::size_t>(self.use_count());                         \
+  }                                                                            \
+  CXX_RS_EXPORT bool cxxbridge1$std$shared_ptr$##RUST_TYPE##$owner_before(     \
+      const std::shared_ptr<CXX_TYPE> &self,                                   \
+      std::shared_ptr<void> *alias) noexcept {                                 \
+    bool before = self.owner_before(*alias);                                   \
+    alias->~shared_ptr();                                                      \
+    return before;                                                             \
+  }                                                                            \
+  CXX_RS_EXPORT void cxxbridge1$std$shared_ptr$##RUST_TYPE##$alias(            \
+      const std::shared_ptr<CXX_TYPE> &self, void *ptr,                        \
+      std::shared_ptr<void> *alias) noexcept {                                 \
+    new (alias) std::shared_ptr<void>(self, ptr);                              \
+  }                                                                            \
+  CXX_RS_EXPORT void cxxbridge1$std$shared_ptr$##RUST_TYPE##$from_alias(       \
+      std::shared_ptr<void> *alias, std::shared_ptr<CXX_TYPE> *ptr) noexcept { \
+    new (ptr) std::shared_ptr<CXX_TYPE>(*alias,                                \
+                                        static_cast<CXX_TYPE *>(alias->get()));\
+    alias->~shared_ptr();                                                      \
+  }                                                                            \
+  CXX_RS_EXPORT void cxxbridge1$std$shared_ptr$##RUST_TYPE##$shared_from_this( \
+      const CXX_TYPE &, std::shared_ptr<CXX_TYPE> *ptr) noexcept {             \
+    new (ptr) std::shared_ptr<CXX_TYPE>();                                     \
+  }                                                                            \
   static_assert(sizeof(std::weak_ptr<CXX_TYPE>) == 2 * sizeof(void *), "");    \
   static_assert(alignof(std::weak_ptr<CXX_TYPE>) == alignof(void *), "");      \
   CXX_RS_EXPORT void cxxbridge1$std$weak_ptr$##RUST_TYPE##$null(               \
@@ -791,6 +889,10 @@ static_assert(sizeof(std::string) <= kMaxExpectedWordsInString * sizeof(void *),
   CXX_RS_EXPORT void cxxbridge1$std$weak_ptr$##RUST_TYPE##$drop(               \
       const std::weak_ptr<CXX_TYPE> *self) noexcept {                          \
     self->~weak_ptr();                                                         \
+  }                                                                            \
+  CXX_RS_EXPORT std::size_t cxxbridge1$std$weak_ptr$##RUST_TYPE##$use_count(   \
+      const std::weak_ptr<CXX_TYPE> &self) noexcept {                          \
+    return static_cast<std::size_t>(self.use_count());                         \
   }
 
 // Usize and isize are the same type as one of the below.
@@ -371,7 +371,7 @@ macro_rules! vector_element_by_value_methods {
         unsafe fn __push_back(v: Pin<&mut CxxVector<$ty>>, value: &mut ManuallyDrop<$ty>) {
             extern "C" {
                 attr! {
-                    #[link_name = concat!("cxxbridge1$std$vector$", $segment, "$push_back")]
+                    #[link_name = symbol!("std$vector$", $segment, "$push_back")]
                     fn __push_back(_: Pin<&mut CxxVector<$ty>>, _: &mut ManuallyDrop<$ty>);
                 }
             }
@@ -380,7 +380,7 @@ macro_rules! vector_element_by_value_methods {
         unsafe fn __pop_back(v: Pin<&mut CxxVector<$ty>>, out: &mut MaybeUninit<$ty>) {
             extern "C" {
                 attr! {
-                    #[link_name = concat!("cxxbridge1$std$vector$", $segment, "$pop_back")]
+                    #[link_name = symbol!("std$vector$", $segment, "$pop_back")]
                     fn __pop_back(_: Pin<&mut CxxVector<$ty>>, _: &mut MaybeUninit<$ty>);
                 }
             }
@@ -401,7 +401,7 @@ macro_rules! impl_vector_element {
             fn __vector_size(v: &CxxVector<$ty>) -> usize {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$std$vector$", $segment, "$size")]
+                        #[link_name = symbol!("std$vector$", $segment, "$size")]
                         fn __vector_size(_: &CxxVector<$ty>) -> usize;
                     }
                 }
@@ -410,7 +410,7 @@ macro_rules! impl_vector_element {
             unsafe fn __get_unchecked(v: *mut CxxVector<$ty>, pos: usize) -> *mut $ty {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$std$vector$", $segment, "$get_unchecked")]
+                        #[link_name = symbol!("std$vector$", $segment, "$get_unchecked")]
                         fn __get_unchecked(_: *mut CxxVector<$ty>, _: usize) -> *mut $ty;
                     }
                 }
@@ -420,7 +420,7 @@ macro_rules! impl_vector_element {
             fn __unique_ptr_null() -> MaybeUninit<*mut c_void> {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$unique_ptr$std$vector$", $segment, "$null")]
+                        #[link_name = symbol!("unique_ptr$std$vector$", $segment, "$null")]
                         fn __unique_ptr_null(this: *mut MaybeUninit<*mut c_void>);
                     }
                 }
@@ -431,7 +431,7 @@ macro_rules! impl_vector_element {
             unsafe fn __unique_ptr_raw(raw: *mut CxxVector<Self>) -> MaybeUninit<*mut c_void> {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$unique_ptr$std$vector$", $segment, "$raw")]
+                        #[link_name = symbol!("unique_ptr$std$vector$", $segment, "$raw")]
                         fn __unique_ptr_raw(this: *mut MaybeUninit<*mut c_void>, raw: *mut CxxVector<$ty>);
                     }
                 }
@@ -442,7 +442,7 @@ macro_rules! impl_vector_element {
             unsafe fn __unique_ptr_get(repr: MaybeUninit<*mut c_void>) -> *const CxxVector<Self> {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$unique_ptr$std$vector$", $segment, "$get")]
+                        #[link_name = symbol!("unique_ptr$std$vector$", $segment, "$get")]
                         fn __unique_ptr_get(this: *const MaybeUninit<*mut c_void>) -> *const CxxVector<$ty>;
                     }
                 }
@@ -451,7 +451,7 @@ macro_rules! impl_vector_element {
             unsafe fn __unique_ptr_release(mut repr: MaybeUninit<*mut c_void>) -> *mut CxxVector<Self> {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$unique_ptr$std$vector$", $segment, "$release")]
+                        #[link_name = symbol!("unique_ptr$std$vector$", $segment, "$release")]
                         fn __unique_ptr_release(this: *mut MaybeUninit<*mut c_void>) -> *mut CxxVector<$ty>;
                     }
                 }
@@ -460,7 +460,7 @@ macro_rules! impl_vector_element {
             unsafe fn __unique_ptr_drop(mut repr: MaybeUninit<*mut c_void>) {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$unique_ptr$std$vector$", $segment, "$drop")]
+                        #[link_name = symbol!("unique_ptr$std$vector$", $segment, "$drop")]
                         fn __unique_ptr_drop(this: *mut MaybeUninit<*mut c_void>);
                     }
                 }
@@ -0,0 +1,135 @@
+#![cfg(all(unix, feature = "std"))]
+
+use alloc::format;
+use alloc::string::String;
+use core::ffi::c_void;
+use core::fmt::{self, Display};
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+extern "C" {
+    fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    fn dlclose(handle: *mut c_void) -> c_int;
+    fn dlerror() -> *mut c_char;
+}
+
+// Same value on Linux, the BSDs and macOS.
+const RTLD_NOW: c_int = 2;
+
+/// Error loading the library of a `#[dlopen]` extern "C++" block.
+#[cfg_attr(doc_cfg, doc(cfg(all(unix, feature = "std"))))]
+#[derive(Debug)]
+pub struct LoadError {
+    msg: String,
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+#[doc(hidden)]
+pub struct Library {
+    name: &'static str,
+    handle: AtomicPtr<c_void>,
+}
+
+#[doc(hidden)]
+pub struct Symbol {
+    ptr: AtomicPtr<c_void>,
+}
+
+impl Library {
+    pub const fn new(name: &'static str) -> Self {
+        Library {
+            name,
+            handle: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    // Symbols are NUL-terminated link names. Once a library has been loaded,
+    // it stays loaded for the rest of the process.
+    pub fn load(&self, path: &Path, symbols: &[&'static [u8]]) -> Result<(), LoadError> {
+        if self.is_loaded() {
+            return Ok(());
+        }
+        let filename = match CString::new(path.as_os_str().as_bytes()) {
+            Ok(filename) => filename,
+            Err(_) => {
+                return Err(LoadError {
+                    msg: format!("{}: path contains a nul byte", path.display()),
+                });
+            }
+        };
+        let handle = unsafe { dlopen(filename.as_ptr(), RTLD_NOW) };
+        if handle.is_null() {
+            return Err(LoadError {
+                msg: last_error().unwrap_or_else(|| format!("{}: failed to load", path.display())),
+            });
+        }
+        for symbol in symbols {
+            if unsafe { dlsym(handle, symbol.as_ptr().cast()) }.is_null() {
+                unsafe { dlclose(handle) };
+                let symbol = String::from_utf8_lossy(&symbol[..symbol.len() - 1]);
+                return Err(LoadError {
+                    msg: format!("{}: undefined symbol: {}", path.display(), symbol),
+                });
+            }
+        }
+        if self
+            .handle
+            .compare_exchange(ptr::null_mut(), handle, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            // Loaded concurrently by another thread.
+            unsafe { dlclose(handle) };
+        }
+        Ok(())
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        !self.handle.load(Ordering::Acquire).is_null()
+    }
+
+    pub fn symbol(&self, symbol: &Symbol, name: &'static [u8]) -> *mut c_void {
+        let ptr = symbol.ptr.load(Ordering::Acquire);
+        if !ptr.is_null() {
+            return ptr;
+        }
+        let handle = self.handle.load(Ordering::Acquire);
+        if handle.is_null() {
+            panic!("{} is not loaded", self.name);
+        }
+        let ptr = unsafe { dlsym(handle, name.as_ptr().cast()) };
+        // Checked by Library::load.
+        assert!(!ptr.is_null());
+        symbol.ptr.store(ptr, Ordering::Release);
+        ptr
+    }
+}
+
+impl Symbol {
+    pub const fn new() -> Self {
+        Symbol {
+            ptr: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+}
+
+fn last_error() -> Option<String> {
+    let msg = unsafe { dlerror() };
+    if msg.is_null() {
+        None
+    } else {
+        let msg = unsafe { CStr::from_ptr(msg) };
+        Some(msg.to_string_lossy().into_owned())
+    }
+}
@@ -441,7 +441,10 @@ compile_error! {
 mod macros;
 
 mod c_char;
+mod clone_target;
+mod closure;
 mod cxx_vector;
+mod dlopen;
 mod exception;
 mod extern_type;
 mod fmt;
@@ -458,22 +461,29 @@ mod rust_type;
 mod rust_vec;
 mod shared_ptr;
 mod sip;
+mod stack_slot;
 #[path = "cxx_string.rs"]
 mod string;
 mod symbols;
+pub mod tuple;
 mod type_id;
 mod unique_ptr;
 mod unwind;
+mod upcast;
 pub mod vector;
 mod weak_ptr;
 
 pub use crate::cxx_vector::CxxVector;
+#[cfg(all(unix, feature = "std"))]
+pub use crate::dlopen::LoadError;
 #[cfg(feature = "alloc")]
 pub use crate::exception::Exception;
 pub use crate::extern_type::{kind, ExternType};
 pub use crate::shared_ptr::SharedPtr;
 pub use crate::string::CxxString;
 pub use crate::unique_ptr::UniquePtr;
+#[cfg(feature = "std")]
+pub use crate::unwind::install_panic_location_hook;
 pub use crate::weak_ptr::WeakPtr;
 pub use cxxbridge_macro::bridge;
 
@@ -495,24 +505,33 @@ pub type Vector<T> = CxxVector<T>;
 #[doc(hidden)]
 pub mod private {
     pub use crate::c_char::c_char;
+    pub use crate::clone_target::CloneTarget;
+    #[cfg(feature = "alloc")]
+    pub use crate::closure::{CxxFnMut, SendCxxFnMut};
     pub use crate::cxx_vector::VectorElement;
+    #[cfg(all(unix, feature = "std"))]
+    pub use crate::dlopen::{Library, Symbol};
     pub use crate::extern_type::{verify_extern_kind, verify_extern_type};
     pub use crate::function::FatFunction;
     pub use crate::hash::hash;
     pub use crate::opaque::Opaque;
+    #[cfg(feature = "std")]
+    pub use crate::result::catch_unwind;
     #[cfg(feature = "alloc")]
-    pub use crate::result::{r#try, Result};
+    pub use crate::result::{r#try, Panic, Result};
     pub use crate::rust_slice::RustSlice;
     pub use crate::rust_str::RustStr;
     #[cfg(feature = "alloc")]
     pub use crate::rust_string::RustString;
-    pub use crate::rust_type::{ImplBox, ImplVec, RustType};
+    pub use crate::rust_type::{ImplArc, ImplBox, ImplBoxDeleter, ImplVec, RustType};
     #[cfg(feature = "alloc")]
     pub use crate::rust_vec::RustVec;
     pub use crate::shared_ptr::SharedPtrTarget;
+    pub use crate::stack_slot::StackSlot;
     pub use crate::string::StackString;
     pub use crate::unique_ptr::UniquePtrTarget;
     pub use crate::unwind::prevent_unwind;
+    pub use crate::upcast::Upcast;
     pub use crate::weak_ptr::WeakPtrTarget;
     pub use core::{concat, module_path};
     pub use cxxbridge_macro::type_id;
@@ -6,3 +6,19 @@ macro_rules! attr {
         $($rest)*
     };
 }
+
+// Link name of a symbol shared with the C++ side, under the symbol prefix
+// configured through cxxbridge-flags.
+#[cfg(built_with_cargo)]
+macro_rules! symbol {
+    ($($segment:expr),+ $(,)?) => {
+        concat!(env!("CXXBRIDGE_SYMBOL_PREFIX"), "$", $($segment),+)
+    };
+}
+
+#[cfg(not(built_with_cargo))]
+macro_rules! symbol {
+    ($($segment:expr),+ $(,)?) => {
+        concat!("cxxbridge1$", $($segment),+)
+    };
+}
@@ -37,19 +37,57 @@ where
 }
 
 unsafe fn to_c_error(msg: String) -> Result {
+    let err = unsafe { to_c_string(msg) };
+    Result { err }
+}
+
+unsafe fn to_c_string(msg: String) -> PtrLen {
     let mut msg = msg;
     unsafe { msg.as_mut_vec() }.push(b'\0');
     let ptr = msg.as_ptr();
     let len = msg.len();
 
     extern "C" {
-        #[link_name = "cxxbridge1$error"]
+        #[link_name = symbol!("error")]
         fn error(ptr: *const u8, len: usize) -> NonNull<u8>;
     }
 
     let copy = unsafe { error(ptr, len) };
-    let err = PtrLen { ptr: copy, len };
-    Result { err }
+    PtrLen { ptr: copy, len }
+}
+
+// ABI compatible with C++ rust::repr::Panic. C++ zero-initializes it and
+// checks `msg.ptr` after the call to find out whether a panic was caught.
+#[repr(C)]
+pub struct Panic {
+    msg: PtrLen,
+    file: PtrLen,
+    line: u32,
+    column: u32,
+}
+
+#[cfg(feature = "std")]
+pub unsafe fn catch_unwind<F>(panic: *mut Panic, f: F) -> Result
+where
+    F: FnOnce() -> Result,
+{
+    match crate::unwind::catch_unwind(f) {
+        Ok(result) => result,
+        Err(panicked) => {
+            unsafe {
+                ptr::write(
+                    panic,
+                    Panic {
+                        msg: to_c_string(panicked.message),
+                        file: to_c_string(panicked.file),
+                        line: panicked.line,
+                        column: panicked.column,
+                    },
+                );
+            }
+            Result { ok: ptr::null() }
+        }
+    }
 }
 
 impl Result {
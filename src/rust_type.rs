@@ -2,4 +2,6 @@
 
 pub unsafe trait RustType {}
 pub unsafe trait ImplBox {}
+pub unsafe trait ImplArc {}
 pub unsafe trait ImplVec {}
+pub unsafe trait ImplBoxDeleter {}
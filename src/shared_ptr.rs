@@ -1,6 +1,8 @@
+use crate::clone_target::CloneTarget;
 use crate::fmt::display;
 use crate::kind::Trivial;
 use crate::string::CxxString;
+use crate::upcast::Upcast;
 use crate::weak_ptr::{WeakPtr, WeakPtrTarget};
 use crate::ExternType;
 use core::ffi::c_void;
@@ -8,6 +10,8 @@ use core::fmt::{self, Debug, Display};
 use core::marker::PhantomData;
 use core::mem::MaybeUninit;
 use core::ops::Deref;
+use core::pin::Pin;
+use core::ptr;
 
 /// Binding to C++ `std::shared_ptr<T>`.
 #[repr(C)]
@@ -64,6 +68,213 @@ where
         unsafe { T::__get(this).as_ref() }
     }
 
+    /// Returns a pinned mutable reference to the object owned by this
+    /// SharedPtr if it is the only owner of a non-null object, otherwise None.
+    ///
+    /// SharedPtr has no counterpart of
+    /// [std::const\_pointer\_cast](https://en.cppreference.com/w/cpp/memory/shared_ptr/pointer_cast)
+    /// because it never binds a std::shared\_ptr to const. This is how to get
+    /// mutable access to the object instead.
+    ///
+    /// # Safety
+    ///
+    /// std::shared\_ptr does not expose how many std::weak\_ptr refer to its
+    /// control block, so unlike `Arc::get_mut` this cannot rule out another
+    /// owner appearing through [`WeakPtr::upgrade`] or `shared_from_this`. The
+    /// caller must ensure no weak reference to the object is upgraded while
+    /// the returned reference is in use.
+    pub unsafe fn get_mut(&mut self) -> Option<Pin<&mut T>> {
+        if self.use_count() != 1 {
+            return None;
+        }
+        let this = self as *const Self as *const c_void;
+        let ptr = unsafe { T::__get(this) } as *mut T;
+        unsafe { ptr.as_mut().map(|ptr| Pin::new_unchecked(ptr)) }
+    }
+
+    /// Returns the number of SharedPtr instances, on either side of the
+    /// bridge, that share ownership of the object managed by `self`. Returns 0
+    /// if `self` manages no object.
+    ///
+    /// Matches the behavior of [std::shared\_ptr\<T\>::use\_count](https://en.cppreference.com/w/cpp/memory/shared_ptr/use_count).
+    pub fn use_count(&self) -> usize {
+        let this = self as *const Self as *const c_void;
+        unsafe { T::__use_count(this) }
+    }
+
+    /// Checks whether `self` and `other` point to the same object, or are
+    /// both null.
+    ///
+    /// Like `==` on two std::shared\_ptr, this compares the stored pointers,
+    /// not ownership. Use [`owner_before`](Self::owner_before) to compare
+    /// ownership.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        let this = self as *const Self as *const c_void;
+        let other = other as *const Self as *const c_void;
+        unsafe { T::__get(this) == T::__get(other) }
+    }
+
+    /// Orders `self` before `other` by the control block that owns the
+    /// object rather than by the stored pointer.
+    ///
+    /// Two SharedPtr are equivalent under this ordering, meaning neither is
+    /// ordered before the other, exactly when they share ownership or are both
+    /// empty. This holds even between pointers of different types, such as a
+    /// SharedPtr and a projection of it made by [`map`](Self::map).
+    ///
+    /// Matches the behavior of [std::shared\_ptr\<T\>::owner\_before](https://en.cppreference.com/w/cpp/memory/shared_ptr/owner_before).
+    pub fn owner_before<U>(&self, other: &SharedPtr<U>) -> bool
+    where
+        U: SharedPtrTarget,
+    {
+        let this = self as *const Self as *const c_void;
+        let other = other as *const SharedPtr<U> as *const c_void;
+        let mut alias = MaybeUninit::<[*mut c_void; 2]>::uninit();
+        unsafe {
+            U::__alias(other, ptr::null_mut(), alias.as_mut_ptr().cast());
+            T::__owner_before(this, alias.as_mut_ptr().cast())
+        }
+    }
+
+    /// Makes a new SharedPtr pointing to a part of the object managed by
+    /// `self`, such as one of its fields, while sharing ownership of the whole
+    /// object.
+    ///
+    /// Matches the behavior of the [aliasing constructor] of std::shared\_ptr.
+    /// If `self` is null, the result is null and `f` is not called.
+    ///
+    /// [aliasing constructor]: https://en.cppreference.com/w/cpp/memory/shared_ptr/shared_ptr
+    pub fn map<U>(&self, f: impl FnOnce(&T) -> &U) -> SharedPtr<U>
+    where
+        U: SharedPtrTarget,
+    {
+        let value = match self.as_ref() {
+            None => return SharedPtr::null(),
+            Some(value) => value,
+        };
+        let ptr = f(value) as *const U as *mut c_void;
+        let this = self as *const Self as *const c_void;
+        let mut alias = MaybeUninit::<[*mut c_void; 2]>::uninit();
+        let mut shared_ptr = MaybeUninit::<SharedPtr<U>>::uninit();
+        let new = shared_ptr.as_mut_ptr().cast();
+        unsafe {
+            T::__alias(this, ptr, alias.as_mut_ptr().cast());
+            U::__from_alias(alias.as_mut_ptr().cast(), new);
+            shared_ptr.assume_init()
+        }
+    }
+
+    /// Recovers the SharedPtr that owns `value`, for a C++ type which derives
+    /// from std::enable\_shared\_from\_this.
+    ///
+    /// Matches the behavior of [std::enable\_shared\_from\_this\<T\>::weak\_from\_this](https://en.cppreference.com/w/cpp/memory/enable_shared_from_this/weak_from_this)
+    /// followed by `lock`. Returns None if `value` is not currently owned by
+    /// any std::shared\_ptr, or if T does not derive from
+    /// std::enable\_shared\_from\_this. Requires C++17 or newer on the C++
+    /// side; with an older standard library this always returns None.
+    pub fn shared_from_this(value: &T) -> Option<Self> {
+        let mut shared_ptr = MaybeUninit::<SharedPtr<T>>::uninit();
+        let new = shared_ptr.as_mut_ptr().cast();
+        let shared_ptr = unsafe {
+            T::__shared_from_this(value, new);
+            shared_ptr.assume_init()
+        };
+        if shared_ptr.is_null() {
+            None
+        } else {
+            Some(shared_ptr)
+        }
+    }
+
+    /// Copies the owned object, if any, into a new allocation owned by a new
+    /// SharedPtr.
+    ///
+    /// Unlike [`clone`](Clone::clone), which shares ownership of the same
+    /// object, the result does not alias `self`.
+    pub fn deep_clone(&self) -> Self
+    where
+        T: CloneTarget,
+    {
+        let value = match self.as_ref() {
+            None => return SharedPtr::null(),
+            Some(value) => value,
+        };
+        let mut shared_ptr = MaybeUninit::<SharedPtr<T>>::uninit();
+        let new = shared_ptr.as_mut_ptr().cast();
+        unsafe {
+            T::__clone_shared(value, new);
+            shared_ptr.assume_init()
+        }
+    }
+
+    /// Makes a new SharedPtr to a base class of T, sharing ownership of the
+    /// object managed by `self`.
+    ///
+    /// Matches the behavior of the converting copy constructor of
+    /// std::shared\_ptr.
+    pub fn upcast<Base>(&self) -> SharedPtr<Base>
+    where
+        T: Upcast<Base>,
+        Base: SharedPtrTarget,
+    {
+        let this = self as *const Self as *const c_void;
+        let mut shared_ptr = MaybeUninit::<SharedPtr<Base>>::uninit();
+        let new = shared_ptr.as_mut_ptr().cast();
+        unsafe {
+            T::__upcast_shared(this, new);
+            shared_ptr.assume_init()
+        }
+    }
+
+    /// Makes a new SharedPtr to the derived class `Derived` sharing ownership
+    /// of the object managed by `self`, if that object is a `Derived`.
+    ///
+    /// Matches the behavior of [std::dynamic\_pointer\_cast](https://en.cppreference.com/w/cpp/memory/shared_ptr/pointer_cast).
+    /// Returns None if `self` is null, if the object is not a `Derived`, or if
+    /// T is not a polymorphic class so its dynamic type cannot be checked.
+    pub fn downcast<Derived>(&self) -> Option<SharedPtr<Derived>>
+    where
+        Derived: Upcast<T> + SharedPtrTarget,
+    {
+        let this = self as *const Self as *const c_void;
+        let mut shared_ptr = MaybeUninit::<SharedPtr<Derived>>::uninit();
+        let new = shared_ptr.as_mut_ptr().cast();
+        let shared_ptr = unsafe {
+            Derived::__downcast_shared(this, new);
+            shared_ptr.assume_init()
+        };
+        if shared_ptr.is_null() {
+            None
+        } else {
+            Some(shared_ptr)
+        }
+    }
+
+    /// Makes a new SharedPtr to the derived class `Derived` sharing ownership
+    /// of the object managed by `self`, without checking the object's dynamic
+    /// type.
+    ///
+    /// Matches the behavior of [std::static\_pointer\_cast](https://en.cppreference.com/w/cpp/memory/shared_ptr/pointer_cast).
+    /// Unlike [`downcast`](Self::downcast) this works for non-polymorphic base
+    /// classes and does not require RTTI. A virtual base class cannot be
+    /// converted this way in C++, in which case the result is null.
+    ///
+    /// # Safety
+    ///
+    /// The object managed by `self`, if any, must be a `Derived`.
+    pub unsafe fn downcast_unchecked<Derived>(&self) -> SharedPtr<Derived>
+    where
+        Derived: Upcast<T> + SharedPtrTarget,
+    {
+        let this = self as *const Self as *const c_void;
+        let mut shared_ptr = MaybeUninit::<SharedPtr<Derived>>::uninit();
+        let new = shared_ptr.as_mut_ptr().cast();
+        unsafe {
+            Derived::__static_downcast_shared(this, new);
+            shared_ptr.assume_init()
+        }
+    }
+
     /// Constructs new WeakPtr as a non-owning reference to the object managed
     /// by `self`. If `self` manages no object, the WeakPtr manages no object
     /// too.
@@ -203,6 +414,16 @@ pub unsafe trait SharedPtrTarget {
     unsafe fn __get(this: *const c_void) -> *const Self;
     #[doc(hidden)]
     unsafe fn __drop(this: *mut c_void);
+    #[doc(hidden)]
+    unsafe fn __use_count(this: *const c_void) -> usize;
+    #[doc(hidden)]
+    unsafe fn __owner_before(this: *const c_void, alias: *mut c_void) -> bool;
+    #[doc(hidden)]
+    unsafe fn __alias(this: *const c_void, ptr: *mut c_void, alias: *mut c_void);
+    #[doc(hidden)]
+    unsafe fn __from_alias(alias: *mut c_void, new: *mut c_void);
+    #[doc(hidden)]
+    unsafe fn __shared_from_this(value: *const Self, new: *mut c_void);
 }
 
 macro_rules! impl_shared_ptr_target {
@@ -214,7 +435,7 @@ macro_rules! impl_shared_ptr_target {
             unsafe fn __null(new: *mut c_void) {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$std$shared_ptr$", $segment, "$null")]
+                        #[link_name = symbol!("std$shared_ptr$", $segment, "$null")]
                         fn __null(new: *mut c_void);
                     }
                 }
@@ -223,7 +444,7 @@ macro_rules! impl_shared_ptr_target {
             unsafe fn __new(value: Self, new: *mut c_void) {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$std$shared_ptr$", $segment, "$uninit")]
+                        #[link_name = symbol!("std$shared_ptr$", $segment, "$uninit")]
                         fn __uninit(new: *mut c_void) -> *mut c_void;
                     }
                 }
@@ -232,7 +453,7 @@ macro_rules! impl_shared_ptr_target {
             unsafe fn __clone(this: *const c_void, new: *mut c_void) {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$std$shared_ptr$", $segment, "$clone")]
+                        #[link_name = symbol!("std$shared_ptr$", $segment, "$clone")]
                         fn __clone(this: *const c_void, new: *mut c_void);
                     }
                 }
@@ -241,7 +462,7 @@ macro_rules! impl_shared_ptr_target {
             unsafe fn __get(this: *const c_void) -> *const Self {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$std$shared_ptr$", $segment, "$get")]
+                        #[link_name = symbol!("std$shared_ptr$", $segment, "$get")]
                         fn __get(this: *const c_void) -> *const c_void;
                     }
                 }
@@ -250,12 +471,57 @@ macro_rules! impl_shared_ptr_target {
             unsafe fn __drop(this: *mut c_void) {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$std$shared_ptr$", $segment, "$drop")]
+                        #[link_name = symbol!("std$shared_ptr$", $segment, "$drop")]
                         fn __drop(this: *mut c_void);
                     }
                 }
                 unsafe { __drop(this) }
             }
+            unsafe fn __use_count(this: *const c_void) -> usize {
+                extern "C" {
+                    attr! {
+                        #[link_name = symbol!("std$shared_ptr$", $segment, "$use_count")]
+                        fn __use_count(this: *const c_void) -> usize;
+                    }
+                }
+                unsafe { __use_count(this) }
+            }
+            unsafe fn __owner_before(this: *const c_void, alias: *mut c_void) -> bool {
+                extern "C" {
+                    attr! {
+                        #[link_name = symbol!("std$shared_ptr$", $segment, "$owner_before")]
+                        fn __owner_before(this: *const c_void, alias: *mut c_void) -> bool;
+                    }
+                }
+                unsafe { __owner_before(this, alias) }
+            }
+            unsafe fn __alias(this: *const c_void, ptr: *mut c_void, alias: *mut c_void) {
+                extern "C" {
+                    attr! {
+                        #[link_name = symbol!("std$shared_ptr$", $segment, "$alias")]
+                        fn __alias(this: *const c_void, ptr: *mut c_void, alias: *mut c_void);
+                    }
+                }
+                unsafe { __alias(this, ptr, alias) }
+            }
+            unsafe fn __from_alias(alias: *mut c_void, new: *mut c_void) {
+                extern "C" {
+                    attr! {
+                        #[link_name = symbol!("std$shared_ptr$", $segment, "$from_alias")]
+                        fn __from_alias(alias: *mut c_void, new: *mut c_void);
+                    }
+                }
+                unsafe { __from_alias(alias, new) }
+            }
+            unsafe fn __shared_from_this(value: *const Self, new: *mut c_void) {
+                extern "C" {
+                    attr! {
+                        #[link_name = symbol!("std$shared_ptr$", $segment, "$shared_from_this")]
+                        fn __shared_from_this(value: *const c_void, new: *mut c_void);
+                    }
+                }
+                unsafe { __shared_from_this(value.cast(), new) }
+            }
         }
     };
 }
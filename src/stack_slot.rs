@@ -0,0 +1,50 @@
+use core::any;
+use core::ffi::c_void;
+use core::mem::{self, MaybeUninit};
+use core::pin::Pin;
+
+// Storage on the Rust stack for an opaque C++ object constructed in place by a
+// bridge constructor. The Rust type's layout comes from its ExternType impl
+// and is checked against the C++ sizeof/alignof before anything is written.
+#[doc(hidden)]
+pub struct StackSlot<T> {
+    space: MaybeUninit<T>,
+    drop: Option<unsafe extern "C" fn(*mut c_void)>,
+}
+
+#[allow(missing_docs)]
+impl<T> StackSlot<T> {
+    pub fn new(size: usize, align: usize) -> Self {
+        if mem::size_of::<T>() < size || mem::align_of::<T>() < align {
+            panic!(
+                "cannot construct {} in place: the C++ type has size {} and alignment {} but its Rust binding has size {} and alignment {}",
+                any::type_name::<T>(),
+                size,
+                align,
+                mem::size_of::<T>(),
+                mem::align_of::<T>(),
+            );
+        }
+        StackSlot {
+            space: MaybeUninit::uninit(),
+            drop: None,
+        }
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut c_void {
+        self.space.as_mut_ptr().cast()
+    }
+
+    pub unsafe fn init(&mut self, drop: unsafe extern "C" fn(*mut c_void)) -> Pin<&mut T> {
+        self.drop = Some(drop);
+        unsafe { Pin::new_unchecked(&mut *self.space.as_mut_ptr()) }
+    }
+}
+
+impl<T> Drop for StackSlot<T> {
+    fn drop(&mut self) {
+        if let Some(drop) = self.drop {
+            unsafe { drop(self.as_mut_ptr()) }
+        }
+    }
+}
@@ -7,21 +7,21 @@ use core::ptr;
 use core::slice;
 use core::str;
 
-#[export_name = "cxxbridge1$string$new"]
+#[export_name = symbol!("string$new")]
 unsafe extern "C" fn string_new(this: &mut MaybeUninit<String>) {
     let this = this.as_mut_ptr();
     let new = String::new();
     unsafe { ptr::write(this, new) }
 }
 
-#[export_name = "cxxbridge1$string$clone"]
+#[export_name = symbol!("string$clone")]
 unsafe extern "C" fn string_clone(this: &mut MaybeUninit<String>, other: &String) {
     let this = this.as_mut_ptr();
     let clone = other.clone();
     unsafe { ptr::write(this, clone) }
 }
 
-#[export_name = "cxxbridge1$string$from_utf8"]
+#[export_name = symbol!("string$from_utf8")]
 unsafe extern "C" fn string_from_utf8(
     this: &mut MaybeUninit<String>,
     ptr: *const u8,
@@ -39,7 +39,7 @@ unsafe extern "C" fn string_from_utf8(
     }
 }
 
-#[export_name = "cxxbridge1$string$from_utf8_lossy"]
+#[export_name = symbol!("string$from_utf8_lossy")]
 unsafe extern "C" fn string_from_utf8_lossy(
     this: &mut MaybeUninit<String>,
     ptr: *const u8,
@@ -51,7 +51,7 @@ unsafe extern "C" fn string_from_utf8_lossy(
     unsafe { ptr::write(this, owned) }
 }
 
-#[export_name = "cxxbridge1$string$from_utf16"]
+#[export_name = symbol!("string$from_utf16")]
 unsafe extern "C" fn string_from_utf16(
     this: &mut MaybeUninit<String>,
     ptr: *const u16,
@@ -68,7 +68,7 @@ unsafe extern "C" fn string_from_utf16(
     }
 }
 
-#[export_name = "cxxbridge1$string$from_utf16_lossy"]
+#[export_name = symbol!("string$from_utf16_lossy")]
 unsafe extern "C" fn string_from_utf16_lossy(
     this: &mut MaybeUninit<String>,
     ptr: *const u16,
@@ -80,32 +80,32 @@ unsafe extern "C" fn string_from_utf16_lossy(
     unsafe { ptr::write(this, owned) }
 }
 
-#[export_name = "cxxbridge1$string$drop"]
+#[export_name = symbol!("string$drop")]
 unsafe extern "C" fn string_drop(this: &mut ManuallyDrop<String>) {
     unsafe { ManuallyDrop::drop(this) }
 }
 
-#[export_name = "cxxbridge1$string$ptr"]
+#[export_name = symbol!("string$ptr")]
 unsafe extern "C" fn string_ptr(this: &String) -> *const u8 {
     this.as_ptr()
 }
 
-#[export_name = "cxxbridge1$string$len"]
+#[export_name = symbol!("string$len")]
 unsafe extern "C" fn string_len(this: &String) -> usize {
     this.len()
 }
 
-#[export_name = "cxxbridge1$string$capacity"]
+#[export_name = symbol!("string$capacity")]
 unsafe extern "C" fn string_capacity(this: &String) -> usize {
     this.capacity()
 }
 
-#[export_name = "cxxbridge1$string$reserve_additional"]
+#[export_name = symbol!("string$reserve_additional")]
 unsafe extern "C" fn string_reserve_additional(this: &mut String, additional: usize) {
     this.reserve(additional);
 }
 
-#[export_name = "cxxbridge1$string$reserve_total"]
+#[export_name = symbol!("string$reserve_total")]
 unsafe extern "C" fn string_reserve_total(this: &mut String, new_cap: usize) {
     if new_cap > this.capacity() {
         let additional = new_cap - this.len();
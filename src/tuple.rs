@@ -0,0 +1,83 @@
+//! Tuples in the fields of shared structs.
+//!
+//! Neither a Rust tuple nor a `std::tuple` has a layout that can be relied upon
+//! across the language boundary. A field of tuple type in a shared struct is
+//! therefore declared as one of the `repr(C)` structs in this module, which
+//! matches `rust::repr::TupleN` on the C++ side.
+//!
+//! ```
+//! #[cxx::bridge]
+//! mod ffi {
+//!     struct Entry {
+//!         pair: (u32, String),
+//!     }
+//! }
+//!
+//! let entry = ffi::Entry {
+//!     pair: (1, "one".to_owned()).into(),
+//! };
+//! assert_eq!(entry.pair.0, 1);
+//! let (number, name) = entry.pair.into_tuple();
+//! # let _ = (number, name);
+//! ```
+//!
+//! Elements are accessed as `.0`, `.1` like those of a tuple, and conversions
+//! to and from the Rust tuple are provided by `From`.
+//!
+//! Tuples in the signatures of extern functions are ordinary Rust tuples and
+//! are converted on the way across the bridge.
+
+use core::fmt::{self, Debug};
+
+macro_rules! tuple_repr {
+    ($($name:ident($($field:tt $ty:ident),+))*) => {
+        $(
+            /// Layout-stable counterpart of a Rust tuple of the same arity.
+            ///
+            /// See the [module documentation](self).
+            #[repr(C)]
+            #[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name<$($ty),+>($(pub $ty),+);
+
+            impl<$($ty),+> $name<$($ty),+> {
+                /// Converts into the Rust tuple of the same elements.
+                pub fn into_tuple(self) -> ($($ty,)+) {
+                    ($(self.$field,)+)
+                }
+            }
+
+            impl<$($ty),+> From<($($ty,)+)> for $name<$($ty),+> {
+                fn from(tuple: ($($ty,)+)) -> Self {
+                    $name($(tuple.$field),+)
+                }
+            }
+
+            impl<$($ty),+> From<$name<$($ty),+>> for ($($ty,)+) {
+                fn from(repr: $name<$($ty),+>) -> Self {
+                    repr.into_tuple()
+                }
+            }
+
+            impl<$($ty: Debug),+> Debug for $name<$($ty),+> {
+                fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.debug_tuple("")$(.field(&self.$field))+.finish()
+                }
+            }
+        )*
+    };
+}
+
+tuple_repr! {
+    Tuple1(0 T0)
+    Tuple2(0 T0, 1 T1)
+    Tuple3(0 T0, 1 T1, 2 T2)
+    Tuple4(0 T0, 1 T1, 2 T2, 3 T3)
+    Tuple5(0 T0, 1 T1, 2 T2, 3 T3, 4 T4)
+    Tuple6(0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5)
+    Tuple7(0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6)
+    Tuple8(0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7)
+    Tuple9(0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8)
+    Tuple10(0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9)
+    Tuple11(0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10)
+    Tuple12(0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11)
+}
@@ -1,8 +1,14 @@
+use crate::clone_target::CloneTarget;
 use crate::cxx_vector::{CxxVector, VectorElement};
 use crate::fmt::display;
 use crate::kind::Trivial;
+#[cfg(feature = "alloc")]
+use crate::rust_type::ImplBoxDeleter;
 use crate::string::CxxString;
+use crate::upcast::Upcast;
 use crate::ExternType;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
 use core::ffi::c_void;
 use core::fmt::{self, Debug, Display};
 use core::marker::PhantomData;
@@ -11,6 +17,12 @@ use core::ops::{Deref, DerefMut};
 use core::pin::Pin;
 
 /// Binding to C++ `std::unique_ptr<T, std::default_delete<T>>`.
+///
+/// A bridge can declare a different deleter for an opaque C++ type with
+/// `impl UniquePtr<T, Deleter> {}`, in which case this binds to
+/// `std::unique_ptr<T, Deleter>`. For an opaque Rust type `T`, the C++ side is
+/// instead `std::unique_ptr<T, rust::BoxDeleter>` which owns a Box allocation,
+/// and the UniquePtr can be converted to and from `Box<T>` without copying.
 #[repr(C)]
 pub struct UniquePtr<T>
 where
@@ -107,6 +119,67 @@ where
             ty: PhantomData,
         }
     }
+
+    /// Converts into a UniquePtr to a base class of T, keeping ownership of
+    /// the same object.
+    ///
+    /// Matches the behavior of the converting move constructor of
+    /// std::unique\_ptr.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `Base` does not have a virtual destructor, since the object
+    /// could then not be correctly destroyed through the resulting UniquePtr.
+    pub fn upcast<Base>(self) -> UniquePtr<Base>
+    where
+        T: Upcast<Base>,
+        Base: UniquePtrTarget,
+    {
+        if self.is_null() {
+            return UniquePtr::null();
+        }
+        let raw = self.into_raw();
+        let base = unsafe { T::__upcast_unique(raw) };
+        if base.is_null() {
+            drop(unsafe { UniquePtr::from_raw(raw) });
+            panic!(
+                "cannot upcast UniquePtr<{}> to UniquePtr<{}> without a virtual destructor",
+                display(T::__typename),
+                display(Base::__typename),
+            );
+        }
+        unsafe { UniquePtr::from_raw(base) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> UniquePtr<T>
+where
+    T: UniquePtrTarget + ImplBoxDeleter,
+{
+    /// Consumes the UniquePtr, handing its object back to Rust as a Box
+    /// without copying, or returns None if the UniquePtr is null.
+    ///
+    /// Only available for UniquePtr of an opaque Rust type, whose C++ side
+    /// is a `std::unique_ptr<T, rust::BoxDeleter>`.
+    pub fn into_box(self) -> Option<Box<T>> {
+        let ptr = self.into_raw();
+        if ptr.is_null() {
+            None
+        } else {
+            Some(unsafe { Box::from_raw(ptr) })
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> From<Box<T>> for UniquePtr<T>
+where
+    T: UniquePtrTarget + ImplBoxDeleter,
+{
+    fn from(value: Box<T>) -> Self {
+        unsafe { UniquePtr::from_raw(Box::into_raw(value)) }
+    }
 }
 
 unsafe impl<T> Send for UniquePtr<T> where T: Send + UniquePtrTarget {}
@@ -157,6 +230,22 @@ where
     }
 }
 
+impl<T> Clone for UniquePtr<T>
+where
+    T: UniquePtrTarget + CloneTarget,
+{
+    /// Copies the owned object, if any, into a new allocation.
+    ///
+    /// Matches the behavior of `std::make_unique<T>(*ptr)` for a non-null
+    /// `std::unique_ptr<T>`.
+    fn clone(&self) -> Self {
+        match self.as_ref() {
+            None => UniquePtr::null(),
+            Some(value) => unsafe { UniquePtr::from_raw(T::__clone(value)) },
+        }
+    }
+}
+
 impl<T> Debug for UniquePtr<T>
 where
     T: Debug + UniquePtrTarget,
@@ -232,15 +321,15 @@ pub unsafe trait UniquePtrTarget {
 }
 
 extern "C" {
-    #[link_name = "cxxbridge1$unique_ptr$std$string$null"]
+    #[link_name = symbol!("unique_ptr$std$string$null")]
     fn unique_ptr_std_string_null(this: *mut MaybeUninit<*mut c_void>);
-    #[link_name = "cxxbridge1$unique_ptr$std$string$raw"]
+    #[link_name = symbol!("unique_ptr$std$string$raw")]
     fn unique_ptr_std_string_raw(this: *mut MaybeUninit<*mut c_void>, raw: *mut CxxString);
-    #[link_name = "cxxbridge1$unique_ptr$std$string$get"]
+    #[link_name = symbol!("unique_ptr$std$string$get")]
     fn unique_ptr_std_string_get(this: *const MaybeUninit<*mut c_void>) -> *const CxxString;
-    #[link_name = "cxxbridge1$unique_ptr$std$string$release"]
+    #[link_name = symbol!("unique_ptr$std$string$release")]
     fn unique_ptr_std_string_release(this: *mut MaybeUninit<*mut c_void>) -> *mut CxxString;
-    #[link_name = "cxxbridge1$unique_ptr$std$string$drop"]
+    #[link_name = symbol!("unique_ptr$std$string$drop")]
     fn unique_ptr_std_string_drop(this: *mut MaybeUninit<*mut c_void>);
 }
 
@@ -37,3 +37,88 @@ impl Drop for Guard {
         panic!("panic in ffi function {}, aborting.", self.label);
     }
 }
+
+#[cfg(feature = "std")]
+pub struct Panicked {
+    pub message: std::string::String,
+    pub file: std::string::String,
+    pub line: u32,
+    pub column: u32,
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    // Nonzero while this thread is inside catch_unwind, so that the location
+    // hook leaves panics it has no business with alone.
+    static CATCHING: core::cell::Cell<usize> = core::cell::Cell::new(0);
+    static LOCATION: core::cell::RefCell<Option<(std::string::String, u32, u32)>> =
+        core::cell::RefCell::new(None);
+}
+
+/// Records the source location of panics caught by `#[catch_unwind]`
+/// functions, for `rust::Panic::file()`, `line()` and `column()` in C++.
+///
+/// The payload of a panic does not say where the panic happened; only a panic
+/// hook gets to see that. This function chains a hook in front of whichever
+/// hook is currently installed, so it is best called once at startup after
+/// the application has set up its own hook, if any. The chained hook ignores
+/// panics outside of `#[catch_unwind]` functions other than forwarding them to
+/// the previous hook. Calling this function more than once has no further
+/// effect.
+///
+/// Without this hook, `rust::Panic` still carries the panic message but its
+/// location is empty.
+#[cfg(feature = "std")]
+pub fn install_panic_location_hook() {
+    use std::boxed::Box;
+    use std::panic;
+    use std::string::ToString;
+    use std::sync::Once;
+
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CATCHING.with(|catching| catching.get() != 0) {
+                let location = info
+                    .location()
+                    .map(|loc| (loc.file().to_string(), loc.line(), loc.column()));
+                LOCATION.with(|cell| *cell.borrow_mut() = location);
+            }
+            previous(info);
+        }));
+    });
+}
+
+#[cfg(feature = "std")]
+pub fn catch_unwind<F, R>(f: F) -> Result<R, Panicked>
+where
+    F: FnOnce() -> R,
+{
+    use std::panic::{self, AssertUnwindSafe};
+    use std::string::{String, ToString};
+
+    LOCATION.with(|cell| *cell.borrow_mut() = None);
+    CATCHING.with(|catching| catching.set(catching.get() + 1));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|catching| catching.set(catching.get() - 1));
+
+    result.map_err(|payload| {
+        let message = if let Some(message) = payload.downcast_ref::<&'static str>() {
+            (*message).to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "Box<dyn Any>".to_string()
+        };
+        let (file, line, column) = LOCATION
+            .with(|cell| cell.borrow_mut().take())
+            .unwrap_or_default();
+        Panicked {
+            message,
+            file,
+            line,
+            column,
+        }
+    })
+}
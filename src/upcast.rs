@@ -0,0 +1,46 @@
+use core::ffi::c_void;
+use core::pin::Pin;
+
+/// Trait bound for C++ types which publicly derive from the C++ type `Base`.
+///
+/// Implemented by the `#[cxx::bridge]` macro for each base class listed in
+/// `#[base(...)]` on an opaque C++ type. It enables [`UniquePtr::upcast`],
+/// [`SharedPtr::upcast`], [`SharedPtr::downcast`] and
+/// [`SharedPtr::downcast_unchecked`].
+///
+/// Implementing this trait outside of the CXX codebase is not supported.
+///
+/// [`UniquePtr::upcast`]: crate::UniquePtr::upcast
+/// [`SharedPtr::upcast`]: crate::SharedPtr::upcast
+/// [`SharedPtr::downcast`]: crate::SharedPtr::downcast
+/// [`SharedPtr::downcast_unchecked`]: crate::SharedPtr::downcast_unchecked
+pub unsafe trait Upcast<Base>: Sized {
+    /// Returns a reference to the `Base` subobject of `self`.
+    ///
+    /// Matches the behavior of an implicit derived-to-base conversion of a C++
+    /// reference.
+    fn upcast(&self) -> &Base {
+        unsafe { &*Self::__upcast(self) }
+    }
+
+    /// Returns a pinned mutable reference to the `Base` subobject of `self`.
+    fn upcast_mut(self: Pin<&mut Self>) -> Pin<&mut Base> {
+        unsafe {
+            let this = Pin::into_inner_unchecked(self) as *mut Self;
+            Pin::new_unchecked(&mut *Self::__upcast_mut(this))
+        }
+    }
+
+    #[doc(hidden)]
+    unsafe fn __upcast(this: *const Self) -> *const Base;
+    #[doc(hidden)]
+    unsafe fn __upcast_mut(this: *mut Self) -> *mut Base;
+    #[doc(hidden)]
+    unsafe fn __upcast_unique(this: *mut Self) -> *mut Base;
+    #[doc(hidden)]
+    unsafe fn __upcast_shared(this: *const c_void, new: *mut c_void);
+    #[doc(hidden)]
+    unsafe fn __downcast_shared(this: *const c_void, new: *mut c_void);
+    #[doc(hidden)]
+    unsafe fn __static_downcast_shared(this: *const c_void, new: *mut c_void);
+}
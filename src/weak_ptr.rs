@@ -36,6 +36,23 @@ where
         }
     }
 
+    /// Returns the number of SharedPtr instances that share ownership of the
+    /// object referred to by `self`, or 0 if that object has been destroyed.
+    ///
+    /// Matches the behavior of [std::weak\_ptr\<T\>::use\_count](https://en.cppreference.com/w/cpp/memory/weak_ptr/use_count).
+    pub fn use_count(&self) -> usize {
+        let this = self as *const Self as *const c_void;
+        unsafe { T::__use_count(this) }
+    }
+
+    /// Checks whether the object referred to by `self` has already been
+    /// destroyed, in which case [`upgrade`](Self::upgrade) would return null.
+    ///
+    /// Matches the behavior of [std::weak\_ptr\<T\>::expired](https://en.cppreference.com/w/cpp/memory/weak_ptr/expired).
+    pub fn expired(&self) -> bool {
+        self.use_count() == 0
+    }
+
     /// Upgrades a non-owning reference into an owning reference if possible,
     /// otherwise to a null reference.
     ///
@@ -109,6 +126,8 @@ pub unsafe trait WeakPtrTarget {
     unsafe fn __upgrade(weak: *const c_void, shared: *mut c_void);
     #[doc(hidden)]
     unsafe fn __drop(this: *mut c_void);
+    #[doc(hidden)]
+    unsafe fn __use_count(this: *const c_void) -> usize;
 }
 
 macro_rules! impl_weak_ptr_target {
@@ -120,7 +139,7 @@ macro_rules! impl_weak_ptr_target {
             unsafe fn __null(new: *mut c_void) {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$std$weak_ptr$", $segment, "$null")]
+                        #[link_name = symbol!("std$weak_ptr$", $segment, "$null")]
                         fn __null(new: *mut c_void);
                     }
                 }
@@ -129,7 +148,7 @@ macro_rules! impl_weak_ptr_target {
             unsafe fn __clone(this: *const c_void, new: *mut c_void) {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$std$weak_ptr$", $segment, "$clone")]
+                        #[link_name = symbol!("std$weak_ptr$", $segment, "$clone")]
                         fn __clone(this: *const c_void, new: *mut c_void);
                     }
                 }
@@ -138,7 +157,7 @@ macro_rules! impl_weak_ptr_target {
             unsafe fn __downgrade(shared: *const c_void, weak: *mut c_void) {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$std$weak_ptr$", $segment, "$downgrade")]
+                        #[link_name = symbol!("std$weak_ptr$", $segment, "$downgrade")]
                         fn __downgrade(shared: *const c_void, weak: *mut c_void);
                     }
                 }
@@ -147,7 +166,7 @@ macro_rules! impl_weak_ptr_target {
             unsafe fn __upgrade(weak: *const c_void, shared: *mut c_void) {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$std$weak_ptr$", $segment, "$upgrade")]
+                        #[link_name = symbol!("std$weak_ptr$", $segment, "$upgrade")]
                         fn __upgrade(weak: *const c_void, shared: *mut c_void);
                     }
                 }
@@ -156,12 +175,21 @@ macro_rules! impl_weak_ptr_target {
             unsafe fn __drop(this: *mut c_void) {
                 extern "C" {
                     attr! {
-                        #[link_name = concat!("cxxbridge1$std$weak_ptr$", $segment, "$drop")]
+                        #[link_name = symbol!("std$weak_ptr$", $segment, "$drop")]
                         fn __drop(this: *mut c_void);
                     }
                 }
                 unsafe { __drop(this) }
             }
+            unsafe fn __use_count(this: *const c_void) -> usize {
+                extern "C" {
+                    attr! {
+                        #[link_name = symbol!("std$weak_ptr$", $segment, "$use_count")]
+                        fn __use_count(this: *const c_void) -> usize;
+                    }
+                }
+                unsafe { __use_count(this) }
+            }
         }
     };
 }
@@ -5,7 +5,7 @@ use crate::syntax::Atom::{self, *};
 use crate::syntax::{cfg, Derive, Doc, ForeignName};
 use proc_macro2::{Ident, TokenStream};
 use quote::ToTokens;
-use syn::parse::ParseStream;
+use syn::parse::{Parse, ParseStream};
 use syn::{Attribute, Error, Expr, Lit, LitStr, Meta, Path, Result, Token};
 
 // Intended usage:
@@ -31,11 +31,17 @@ pub struct Parser<'a> {
     pub cfg: Option<&'a mut CfgExpr>,
     pub doc: Option<&'a mut Doc>,
     pub derives: Option<&'a mut Vec<Derive>>,
+    pub bases: Option<&'a mut Vec<Ident>>,
     pub repr: Option<&'a mut Option<Atom>>,
     pub namespace: Option<&'a mut Namespace>,
     pub cxx_name: Option<&'a mut Option<ForeignName>>,
     pub rust_name: Option<&'a mut Option<Ident>>,
+    pub self_type: Option<&'a mut Option<Ident>>,
+    pub catch_unwind: Option<&'a mut bool>,
+    pub dlopen: Option<&'a mut Option<Ident>>,
+    pub from_header: Option<&'a mut bool>,
     pub variants_from_header: Option<&'a mut Option<Attribute>>,
+    pub allow_operator: bool,
     pub ignore_unrecognized: bool,
 
     // Suppress clippy needless_update lint ("struct update has no effect, all
@@ -77,6 +83,19 @@ pub fn parse(cx: &mut Errors, attrs: Vec<Attribute>, mut parser: Parser) -> Othe
                     break;
                 }
             }
+        } else if attr_path.is_ident("base") {
+            match attr.parse_args_with(parse_base_attribute) {
+                Ok(attr) => {
+                    if let Some(bases) = &mut parser.bases {
+                        bases.extend(attr);
+                        continue;
+                    }
+                }
+                Err(err) => {
+                    cx.push(err);
+                    break;
+                }
+            }
         } else if attr_path.is_ident("repr") {
             match attr.parse_args_with(parse_repr_attribute) {
                 Ok(attr) => {
@@ -104,7 +123,7 @@ pub fn parse(cx: &mut Errors, attrs: Vec<Attribute>, mut parser: Parser) -> Othe
                 }
             }
         } else if attr_path.is_ident("cxx_name") {
-            match parse_cxx_name_attribute(&attr.meta) {
+            match parse_cxx_name_attribute(&attr.meta, parser.allow_operator) {
                 Ok(attr) => {
                     if let Some(cxx_name) = &mut parser.cxx_name {
                         **cxx_name = Some(attr);
@@ -129,6 +148,50 @@ pub fn parse(cx: &mut Errors, attrs: Vec<Attribute>, mut parser: Parser) -> Othe
                     break;
                 }
             }
+        } else if attr_path.is_ident("Self") {
+            match parse_self_type_attribute(&attr.meta) {
+                Ok(attr) => {
+                    if let Some(self_type) = &mut parser.self_type {
+                        **self_type = Some(attr);
+                        continue;
+                    }
+                }
+                Err(err) => {
+                    cx.push(err);
+                    break;
+                }
+            }
+        } else if attr_path.is_ident("dlopen") {
+            match parse_dlopen_attribute(&attr.meta) {
+                Ok(attr) => {
+                    if let Some(dlopen) = &mut parser.dlopen {
+                        **dlopen = Some(attr);
+                        continue;
+                    }
+                }
+                Err(err) => {
+                    cx.push(err);
+                    break;
+                }
+            }
+        } else if attr_path.is_ident("catch_unwind") {
+            if let Err(err) = attr.meta.require_path_only() {
+                cx.push(err);
+                break;
+            }
+            if let Some(catch_unwind) = &mut parser.catch_unwind {
+                **catch_unwind = true;
+                continue;
+            }
+        } else if attr_path.is_ident("from_header") {
+            if let Err(err) = attr.meta.require_path_only() {
+                cx.push(err);
+                break;
+            }
+            if let Some(from_header) = &mut parser.from_header {
+                **from_header = true;
+                continue;
+            }
         } else if attr_path.is_ident("cfg") {
             match cfg::parse_attribute(&attr) {
                 Ok(cfg_expr) => {
@@ -227,6 +290,11 @@ fn parse_derive_attribute(cx: &mut Errors, input: ParseStream) -> Result<Vec<Der
     Ok(derives)
 }
 
+fn parse_base_attribute(input: ParseStream) -> Result<Vec<Ident>> {
+    let bases = input.parse_terminated(Ident::parse, Token![,])?;
+    Ok(bases.into_iter().collect())
+}
+
 fn parse_repr_attribute(input: ParseStream) -> Result<Atom> {
     let begin = input.cursor();
     let ident: Ident = input.parse()?;
@@ -244,12 +312,16 @@ fn parse_repr_attribute(input: ParseStream) -> Result<Atom> {
     ))
 }
 
-fn parse_cxx_name_attribute(meta: &Meta) -> Result<ForeignName> {
+fn parse_cxx_name_attribute(meta: &Meta, allow_operator: bool) -> Result<ForeignName> {
     if let Meta::NameValue(meta) = meta {
         match &meta.value {
             Expr::Lit(expr) => {
                 if let Lit::Str(lit) = &expr.lit {
-                    return ForeignName::parse(&lit.value(), lit.span());
+                    return if allow_operator {
+                        ForeignName::parse_operator(&lit.value(), lit.span())
+                    } else {
+                        ForeignName::parse(&lit.value(), lit.span())
+                    };
                 }
             }
             Expr::Path(expr) => {
@@ -282,6 +354,44 @@ fn parse_rust_name_attribute(meta: &Meta) -> Result<Ident> {
     Err(Error::new_spanned(meta, "unsupported rust_name attribute"))
 }
 
+fn parse_self_type_attribute(meta: &Meta) -> Result<Ident> {
+    if let Meta::NameValue(meta) = meta {
+        match &meta.value {
+            Expr::Lit(expr) => {
+                if let Lit::Str(lit) = &expr.lit {
+                    return lit.parse();
+                }
+            }
+            Expr::Path(expr) => {
+                if let Some(ident) = expr.path.get_ident() {
+                    return Ok(ident.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(Error::new_spanned(meta, "unsupported Self attribute"))
+}
+
+fn parse_dlopen_attribute(meta: &Meta) -> Result<Ident> {
+    if let Meta::NameValue(meta) = meta {
+        match &meta.value {
+            Expr::Lit(expr) => {
+                if let Lit::Str(lit) = &expr.lit {
+                    return lit.parse();
+                }
+            }
+            Expr::Path(expr) => {
+                if let Some(ident) = expr.path.get_ident() {
+                    return Ok(ident.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(Error::new_spanned(meta, "unsupported dlopen attribute"))
+}
+
 #[derive(Clone)]
 pub struct OtherAttrs(Vec<Attribute>);
 
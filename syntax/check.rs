@@ -2,13 +2,14 @@ use crate::syntax::atom::Atom::{self, *};
 use crate::syntax::report::Errors;
 use crate::syntax::visit::{self, Visit};
 use crate::syntax::{
-    error, ident, trivial, Api, Array, Enum, ExternFn, ExternType, Impl, Lang, Lifetimes,
-    NamedType, Ptr, Receiver, Ref, Signature, SliceRef, Struct, Trait, Ty1, Type, TypeAlias, Types,
+    derive, error, ident, mangle, trivial, Api, Array, Const, DynFn, Enum, ExternFn, ExternType,
+    Impl, Lang, Lifetimes, NamedType, Ptr, Receiver, Ref, Signature, SliceRef, Struct, Trait,
+    Tuple, Ty1, Type, TypeAlias, Types,
 };
 use proc_macro2::{Delimiter, Group, Ident, TokenStream};
 use quote::{quote, ToTokens};
 use std::fmt::Display;
-use syn::{GenericParam, Generics, Lifetime};
+use syn::{Expr, GenericParam, Generics, Lifetime, Lit, UnOp};
 
 pub(crate) struct Check<'a> {
     apis: &'a [Api],
@@ -46,6 +47,7 @@ fn do_typecheck(cx: &mut Check) {
         match ty {
             Type::Ident(ident) => check_type_ident(cx, ident),
             Type::RustBox(ptr) => check_type_box(cx, ptr),
+            Type::RustArc(ptr) => check_type_arc(cx, ptr),
             Type::RustVec(ty) => check_type_rust_vec(cx, ty),
             Type::UniquePtr(ptr) => check_type_unique_ptr(cx, ptr),
             Type::SharedPtr(ptr) => check_type_shared_ptr(cx, ptr),
@@ -56,6 +58,8 @@ fn do_typecheck(cx: &mut Check) {
             Type::Array(array) => check_type_array(cx, array),
             Type::Fn(ty) => check_type_fn(cx, ty),
             Type::SliceRef(ty) => check_type_slice_ref(cx, ty),
+            Type::Tuple(ty) => check_type_tuple(cx, ty),
+            Type::DynFn(ty) => check_type_dyn_fn(cx, ty),
             Type::Str(_) | Type::Void(_) => {}
         }
     }
@@ -69,6 +73,7 @@ fn do_typecheck(cx: &mut Check) {
             Api::CxxFunction(efn) | Api::RustFunction(efn) => check_api_fn(cx, efn),
             Api::TypeAlias(alias) => check_api_type_alias(cx, alias),
             Api::Impl(imp) => check_api_impl(cx, imp),
+            Api::Const(konst) | Api::CxxConst(konst) => check_api_const(cx, konst),
         }
     }
 }
@@ -110,6 +115,27 @@ fn check_type_box(cx: &mut Check, ptr: &Ty1) {
     cx.error(ptr, "unsupported target type of Box");
 }
 
+fn check_type_arc(cx: &mut Check, ptr: &Ty1) {
+    if let Type::Ident(ident) = &ptr.inner {
+        if cx.types.cxx.contains(&ident.rust)
+            && !cx.types.aliases.contains_key(&ident.rust)
+            && !cx.types.structs.contains_key(&ident.rust)
+            && !cx.types.enums.contains_key(&ident.rust)
+        {
+            cx.error(
+                ptr,
+                "Arc of a C++ type is not supported, use SharedPtr<> instead",
+            );
+        }
+
+        if Atom::from(&ident.rust).is_none() {
+            return;
+        }
+    }
+
+    cx.error(ptr, "unsupported target type of Arc");
+}
+
 fn check_type_rust_vec(cx: &mut Check, ty: &Ty1) {
     match &ty.inner {
         Type::Ident(ident) => {
@@ -138,11 +164,6 @@ fn check_type_rust_vec(cx: &mut Check, ty: &Ty1) {
 
 fn check_type_unique_ptr(cx: &mut Check, ptr: &Ty1) {
     if let Type::Ident(ident) = &ptr.inner {
-        if cx.types.rust.contains(&ident.rust) {
-            cx.error(ptr, "unique_ptr of a Rust type is not supported yet");
-            return;
-        }
-
         match Atom::from(&ident.rust) {
             None | Some(CxxString) => return,
             _ => {}
@@ -243,6 +264,21 @@ fn check_type_ref(cx: &mut Check, ty: &Ref) {
             cx.error(ty, "C++ does not allow references to references");
             return;
         }
+        Type::Tuple(_) => {
+            cx.error(ty, "reference to a tuple is not supported yet");
+            return;
+        }
+        Type::DynFn(_) => {
+            cx.error(ty, "reference to a boxed closure is not supported yet");
+            return;
+        }
+        Type::RustArc(_) => {
+            cx.error(
+                ty,
+                "reference to Arc is not supported, pass the Arc by value instead",
+            );
+            return;
+        }
         _ => return,
     }
 
@@ -251,7 +287,7 @@ fn check_type_ref(cx: &mut Check, ty: &Ref) {
 
 fn check_type_ptr(cx: &mut Check, ty: &Ptr) {
     match ty.inner {
-        Type::Fn(_) | Type::Void(_) => {}
+        Type::Fn(_) | Type::Void(_) | Type::Tuple(_) | Type::DynFn(_) | Type::RustArc(_) => {}
         Type::Ref(_) => {
             cx.error(ty, "C++ does not allow pointer to reference as a type");
             return;
@@ -264,6 +300,7 @@ fn check_type_ptr(cx: &mut Check, ty: &Ptr) {
 
 fn check_type_slice_ref(cx: &mut Check, ty: &SliceRef) {
     let supported = !is_unsized(cx, &ty.inner)
+        && !matches!(ty.inner, Type::Tuple(_) | Type::DynFn(_) | Type::RustArc(_))
         || match &ty.inner {
             Type::Ident(ident) => {
                 cx.types.rust.contains(&ident.rust) || cx.types.aliases.contains_key(&ident.rust)
@@ -284,11 +321,36 @@ fn check_type_slice_ref(cx: &mut Check, ty: &SliceRef) {
 }
 
 fn check_type_array(cx: &mut Check, ty: &Array) {
-    let supported = !is_unsized(cx, &ty.inner);
+    let supported =
+        !is_unsized(cx, &ty.inner) && !matches!(ty.inner, Type::Tuple(_) | Type::DynFn(_));
 
     if !supported {
         cx.error(ty, "unsupported array element type");
     }
+
+    if let Some(len_const) = &ty.len_const {
+        let konst = match cx.types.consts.get(len_const) {
+            Some(konst) => konst,
+            None => {
+                let msg =
+                    "array length must be an integer literal or a constant declared in the bridge";
+                cx.error(len_const, msg);
+                return;
+            }
+        };
+        if !matches!(&konst.ty, Type::Ident(ident) if ident.rust == Usize) {
+            cx.error(
+                len_const,
+                "constant used as array length must have type usize",
+            );
+        } else if let Expr::Lit(lit) = &konst.value {
+            if let Lit::Int(int) = &lit.lit {
+                if int.base10_parse::<usize>().ok() == Some(0) {
+                    cx.error(ty, "array with zero size is not supported");
+                }
+            }
+        }
+    }
 }
 
 fn check_type_fn(cx: &mut Check, ty: &Signature) {
@@ -304,6 +366,106 @@ fn check_type_fn(cx: &mut Check, ty: &Signature) {
                     "pointer argument requires that the function pointer be marked unsafe",
                 );
             }
+        } else if let Type::Tuple(_) = arg.ty {
+            cx.error(
+                arg,
+                "passing a tuple to a function pointer is not supported yet",
+            );
+        } else if let Type::DynFn(_) = arg.ty {
+            cx.error(
+                arg,
+                "passing a closure to a function pointer is not supported yet",
+            );
+        } else if let Type::RustArc(_) = arg.ty {
+            cx.error(
+                arg,
+                "passing Arc to a function pointer is not supported yet",
+            );
+        }
+    }
+
+    if let Some(ret @ Type::Tuple(_)) = &ty.ret {
+        cx.error(
+            ret,
+            "function pointer returning a tuple is not supported yet",
+        );
+    } else if let Some(ret @ Type::DynFn(_)) = &ty.ret {
+        cx.error(
+            ret,
+            "function pointer returning a closure is not supported yet",
+        );
+    } else if let Some(ret @ Type::RustArc(_)) = &ty.ret {
+        cx.error(ret, "function pointer returning Arc is not supported yet");
+    }
+}
+
+fn check_type_dyn_fn(cx: &mut Check, ty: &DynFn) {
+    for arg in &ty.sig.args {
+        match &arg.ty {
+            Type::Ptr(_) => cx.error(
+                &arg.ty,
+                "pointer argument in a closure signature is not supported",
+            ),
+            Type::Tuple(_) => {
+                cx.error(&arg.ty, "passing a tuple to a closure is not supported yet")
+            }
+            Type::Fn(_) | Type::DynFn(_) => cx.error(
+                &arg.ty,
+                "passing a function pointer or closure to a closure is not supported yet",
+            ),
+            Type::RustArc(_) => cx.error(&arg.ty, "passing Arc to a closure is not supported yet"),
+            ty if is_unsized(cx, ty) => {
+                let desc = describe(cx, ty);
+                let msg = format!("passing {} by value is not supported", desc);
+                cx.error(ty, msg);
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(error) = &ty.sig.error {
+        cx.error(
+            error,
+            "closure returning Result with an error type is not supported yet",
+        );
+    }
+
+    if let Some(ret) = &ty.sig.ret {
+        match ret {
+            Type::Tuple(_) => cx.error(ret, "closure returning a tuple is not supported yet"),
+            Type::RustArc(_) => cx.error(ret, "closure returning Arc is not supported yet"),
+            Type::Fn(_) | Type::DynFn(_) => cx.error(
+                ret,
+                "closure returning a function pointer or closure is not supported yet",
+            ),
+            ret if is_unsized(cx, ret) => {
+                let desc = describe(cx, ret);
+                let msg = format!("returning {} by value is not supported", desc);
+                cx.error(ret, msg);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn check_type_tuple(cx: &mut Check, ty: &Tuple) {
+    if ty.elems.len() > 12 {
+        cx.error(ty, "tuples with more than 12 elements are not supported");
+    }
+
+    for elem in &ty.elems {
+        if let Type::Tuple(_) = elem {
+            cx.error(elem, "nested tuples are not supported yet");
+        } else if let Type::Fn(_) = elem {
+            cx.error(elem, "function pointers in a tuple are not implemented yet");
+        } else if let Type::DynFn(_) = elem {
+            cx.error(elem, "closures in a tuple are not supported yet");
+        } else if let Type::RustArc(_) = elem {
+            cx.error(elem, "Arc in a tuple is not supported yet");
+        } else if is_unsized(cx, elem) {
+            let desc = describe(cx, elem);
+            let msg = format!("using {} by value in a tuple is not supported", desc);
+            cx.error(elem, msg);
         }
     }
 }
@@ -326,7 +488,7 @@ fn check_api_struct(cx: &mut Check, strct: &Struct) {
     }
 
     for derive in &strct.derives {
-        if derive.what == Trait::ExternType {
+        if matches!(derive.what, Trait::Add | Trait::Deref | Trait::ExternType) {
             let msg = format!("derive({}) on shared struct is not supported", derive);
             cx.error(derive, msg);
         }
@@ -338,6 +500,13 @@ fn check_api_struct(cx: &mut Check, strct: &Struct) {
                 field,
                 "function pointers in a struct field are not implemented yet",
             );
+        } else if let Type::DynFn(_) = field.ty {
+            cx.error(field, "closures in a struct field are not supported yet");
+        } else if let Type::RustArc(_) = field.ty {
+            cx.error(
+                field,
+                "Arc in a struct field is not supported; rust::Arc is not layout compatible with a Rust Arc",
+            );
         } else if is_unsized(cx, &field.ty) {
             let desc = describe(cx, &field.ty);
             let msg = format!("using {} by value is not supported", desc);
@@ -359,7 +528,10 @@ fn check_api_enum(cx: &mut Check, enm: &Enum) {
     }
 
     for derive in &enm.derives {
-        if derive.what == Trait::Default || derive.what == Trait::ExternType {
+        if matches!(
+            derive.what,
+            Trait::Add | Trait::Default | Trait::Deref | Trait::ExternType,
+        ) {
             let msg = format!("derive({}) on shared enum is not supported", derive);
             cx.error(derive, msg);
         }
@@ -374,6 +546,29 @@ fn check_api_type(cx: &mut Check, ety: &ExternType) {
         if derive.what == Trait::ExternType && ety.lang == Lang::Rust {
             continue;
         }
+        if ety.lang == Lang::Cxx
+            && matches!(
+                derive.what,
+                Trait::Add
+                    | Trait::Clone
+                    | Trait::Deref
+                    | Trait::PartialEq
+                    | Trait::Eq
+                    | Trait::PartialOrd
+                    | Trait::Ord
+                    | Trait::Hash
+                    | Trait::Debug,
+            )
+        {
+            continue;
+        }
+        if ety.lang == Lang::Cxx && derive.what == Trait::Copy {
+            cx.error(
+                derive,
+                "derive(Copy) on opaque C++ type is not supported; use derive(Clone) to copy through the C++ copy constructor",
+            );
+            continue;
+        }
         let lang = match ety.lang {
             Lang::Rust => "Rust",
             Lang::Cxx => "C++",
@@ -385,12 +580,108 @@ fn check_api_type(cx: &mut Check, ety: &ExternType) {
         cx.error(derive, msg);
     }
 
+    if ety.lang == Lang::Cxx {
+        // The generated impls of the comparison traits rely on their
+        // supertraits, e.g. PartialOrd tests for equality through PartialEq.
+        for derive in &ety.derives {
+            let requirements: &[Trait] = match derive.what {
+                Trait::Eq | Trait::PartialOrd => &[Trait::PartialEq],
+                Trait::Ord => &[Trait::Eq, Trait::PartialOrd],
+                _ => &[],
+            };
+            for &requirement in requirements {
+                if !derive::contains(&ety.derives, requirement) {
+                    let msg = format!(
+                        "derive({}) on opaque C++ type requires derive({}) too",
+                        derive,
+                        requirement.as_ref(),
+                    );
+                    cx.error(derive, msg);
+                }
+            }
+        }
+
+        // Deref picks a base class implicitly, which is only unambiguous when
+        // there is exactly one.
+        if let Some(derive) = ety
+            .derives
+            .iter()
+            .find(|derive| derive.what == Trait::Deref)
+        {
+            if ety.bases.len() != 1 {
+                cx.error(
+                    derive,
+                    "derive(Deref) on opaque C++ type requires exactly one #[base(...)]",
+                );
+            }
+        }
+
+        // Whether an opaque C++ type has a copy constructor is only known to
+        // the C++ compiler, which checks it with a static_assert in the
+        // generated clone thunk. A shared struct redeclared as an extern C++
+        // type is the exception because its fields are visible here.
+        if let Some(derive) = ety
+            .derives
+            .iter()
+            .find(|derive| derive.what == Trait::Clone)
+        {
+            if let Some(strct) = cx.types.structs.get(&ety.name.rust) {
+                let move_only = strct
+                    .fields
+                    .iter()
+                    .find(|field| !is_copy_constructible(cx, &field.ty));
+                if let Some(field) = move_only {
+                    let msg = format!(
+                        "derive(Clone) requires {} to be copy constructible in C++, but field `{}` is move-only",
+                        ety.name.rust, field.name.rust,
+                    );
+                    cx.error(derive, msg);
+                }
+            }
+        }
+    }
+
     if !ety.bounds.is_empty() {
         let bounds = &ety.bounds;
         let span = quote!(#(#bounds)*);
         cx.error(span, "extern type bounds are not implemented yet");
     }
 
+    let mut seen_bases = Vec::new();
+    for base in &ety.bases {
+        let is_shared =
+            |ident| cx.types.structs.contains_key(ident) || cx.types.enums.contains_key(ident);
+        if ety.lang == Lang::Rust {
+            cx.error(base, "base class of an opaque Rust type is not supported");
+        } else if is_shared(&ety.name.rust) {
+            cx.error(
+                base,
+                "base class of a shared struct or enum is not supported",
+            );
+        } else if !ety.generics.lifetimes.is_empty() {
+            cx.error(
+                base,
+                "base class of a type with lifetime parameters is not supported yet",
+            );
+        } else if *base == ety.name.rust {
+            cx.error(base, "type cannot be its own base class");
+        } else if !cx.types.cxx.contains(base) || is_shared(base) {
+            cx.error(base, "base class must be an opaque C++ type");
+        } else if cx
+            .types
+            .try_resolve(base)
+            .map_or(false, |resolve| !resolve.generics.lifetimes.is_empty())
+        {
+            cx.error(
+                base,
+                "base class with lifetime parameters is not supported yet",
+            );
+        } else if seen_bases.contains(&base) {
+            cx.error(base, "duplicate base class");
+        }
+        seen_bases.push(base);
+    }
+
     if let Some(reasons) = cx.types.required_trivial.get(&ety.name.rust) {
         let msg = format!(
             "needs a cxx::ExternType impl in order to be used as {}",
@@ -456,6 +747,49 @@ fn check_api_fn(cx: &mut Check, efn: &ExternFn) {
         }
     }
 
+    if let Some(self_type) = efn.self_type.as_ref().filter(|_| !efn.constructor) {
+        if cx.types.enums.contains_key(&self_type.rust) {
+            cx.error(
+                self_type,
+                "unsupported Self type; C++ does not allow static member functions on enums",
+            );
+        } else if !cx.types.structs.contains_key(&self_type.rust)
+            && !cx.types.cxx.contains(&self_type.rust)
+            && !cx.types.rust.contains(&self_type.rust)
+        {
+            cx.error(self_type, "unrecognized Self type");
+        }
+    }
+
+    if let Some(self_type) = efn.self_type.as_ref().filter(|_| efn.constructor) {
+        if !cx.types.cxx.contains(&self_type.rust)
+            || cx.types.structs.contains_key(&self_type.rust)
+            || cx.types.enums.contains_key(&self_type.rust)
+        {
+            cx.error(
+                self_type,
+                "unsupported Self type of constructor; expected an extern C++ type",
+            );
+        } else if let Some(resolve) = cx.types.try_resolve(self_type) {
+            if !resolve.generics.lifetimes.is_empty() {
+                cx.error(
+                    self_type,
+                    "constructor of a type with lifetime parameters is not supported yet",
+                );
+            }
+        }
+        if efn.sig.unsafety.is_none() && cx.types.untrusted.contains_key(&self_type.rust) {
+            let fn_token = efn.sig.fn_token;
+            let ident = &efn.name.rust;
+            let span = quote!(#fn_token #ident);
+            let msg = format!(
+                "must be `unsafe fn {}` unless {} is declared in an `unsafe extern \"C++\"` block",
+                ident, self_type.rust,
+            );
+            cx.error(span, msg);
+        }
+    }
+
     for arg in &efn.args {
         if let Type::Fn(_) = arg.ty {
             if efn.lang == Lang::Rust {
@@ -463,6 +797,18 @@ fn check_api_fn(cx: &mut Check, efn: &ExternFn) {
                     arg,
                     "passing a function pointer from C++ to Rust is not implemented yet",
                 );
+            } else if efn.constructor {
+                cx.error(
+                    arg,
+                    "passing a function pointer to a constructor is not implemented yet",
+                );
+            }
+        } else if let Type::DynFn(_) = arg.ty {
+            if efn.constructor {
+                cx.error(
+                    arg,
+                    "passing a closure to a constructor is not implemented yet",
+                );
             }
         } else if let Type::Ptr(_) = arg.ty {
             if efn.sig.unsafety.is_none() {
@@ -481,6 +827,8 @@ fn check_api_fn(cx: &mut Check, efn: &ExternFn) {
     if let Some(ty) = &efn.ret {
         if let Type::Fn(_) = ty {
             cx.error(ty, "returning a function pointer is not implemented yet");
+        } else if let Type::DynFn(_) = ty {
+            cx.error(ty, "returning a closure is not implemented yet");
         } else if is_unsized(cx, ty) {
             let desc = describe(cx, ty);
             let msg = format!("returning {} by value is not supported", desc);
@@ -488,9 +836,128 @@ fn check_api_fn(cx: &mut Check, efn: &ExternFn) {
         }
     }
 
+    if let Some(error) = &efn.error {
+        let shared = match error {
+            Type::Ident(ident) => {
+                cx.types.structs.contains_key(&ident.rust)
+                    || cx.types.enums.contains_key(&ident.rust)
+            }
+            _ => false,
+        };
+        if efn.lang == Lang::Rust {
+            cx.error(
+                error,
+                "Result with an error type is only supported on extern \"C++\" functions",
+            );
+        } else if efn.constructor {
+            cx.error(
+                error,
+                "constructor returning Result with an error type is not supported yet",
+            );
+        } else if !shared {
+            cx.error(
+                error,
+                "error type of Result must be a shared struct or enum",
+            );
+        } else if efn.ret.as_ref() == Some(error) {
+            cx.error(
+                error,
+                "Result with the same success and error type is not supported",
+            );
+        }
+    }
+
     if efn.lang == Lang::Cxx {
         check_mut_return_restriction(cx, efn);
     }
+
+    if efn.name.cxx == *"operator[]" {
+        check_index_operator(cx, efn);
+    }
+
+    if cxxbridge_flags::LEGACY_MANGLING {
+        check_legacy_mangling(cx, efn);
+    }
+}
+
+// A member `operator[]` is exposed to Rust as an impl of std::ops::Index on the
+// receiver type, so its signature needs to be the shape of Index::index.
+fn check_index_operator(cx: &mut Check, efn: &ExternFn) {
+    let fn_token = efn.fn_token;
+    let ident = &efn.name.rust;
+    let span = quote!(#fn_token #ident);
+
+    let receiver = match &efn.receiver {
+        Some(receiver) => receiver,
+        None => {
+            cx.error(span, "operator[] is only supported on member functions");
+            return;
+        }
+    };
+
+    if cx.types.aliases.contains_key(&receiver.ty.rust) || !is_opaque_cxx(cx, &receiver.ty.rust) {
+        let ref span = span_for_receiver_error(receiver);
+        cx.error(
+            span,
+            "operator[] is only supported on opaque C++ types declared in this bridge",
+        );
+        return;
+    }
+
+    let returns_shared_ref = match &efn.ret {
+        Some(Type::Ref(ty)) => !ty.mutable && !ty.pinned,
+        _ => false,
+    };
+    if receiver.mutable
+        || efn.args.len() != 1
+        || !returns_shared_ref
+        || efn.throws
+        || efn.unsafety.is_some()
+        || !efn.generics.params.is_empty()
+    {
+        cx.error(
+            span,
+            "operator[] must have a signature like `fn index(self: &T, index: I) -> &Output` to be mapped to std::ops::Index",
+        );
+    }
+}
+
+// Without a namespace, the legacy symbol of a user-defined function can be the
+// same as the symbol of one of cxx's own functions. See syntax/mangle.rs.
+fn check_legacy_mangling(cx: &mut Check, efn: &ExternFn) {
+    if !efn.name.namespace.is_root() {
+        return;
+    }
+
+    let self_type = match &efn.receiver {
+        Some(receiver) => Some(&receiver.ty),
+        None => efn.self_type.as_ref(),
+    };
+
+    match self_type {
+        Some(self_type) => {
+            if let Some(resolve) = cx.types.try_resolve(self_type) {
+                let name = resolve.name.cxx.to_string();
+                if mangle::BUILTIN_TYPES.contains(&name.as_str()) {
+                    let msg = format!(
+                        "with legacy-mangling, member functions of a type named `{}` in the global namespace collide with cxx's own symbols; rename the type or put the bridge in a namespace",
+                        name,
+                    );
+                    cx.error(self_type, msg);
+                }
+            }
+        }
+        None => {
+            let name = efn.name.rust.to_string();
+            if mangle::ONE_OFF_SYMBOLS.contains(&name.as_str()) {
+                let msg = format!(
+                    "with legacy-mangling, a function named `{}` in the global namespace collides with cxx's own symbols; rename the function or put the bridge in a namespace",
+                    name,
+                );
+                cx.error(&efn.name.rust, msg);
+            }
+        }
+    }
 }
 
 fn check_api_type_alias(cx: &mut Check, alias: &TypeAlias) {
@@ -502,6 +969,44 @@ fn check_api_type_alias(cx: &mut Check, alias: &TypeAlias) {
     }
 }
 
+fn check_api_const(cx: &mut Check, konst: &Const) {
+    let atom = match &konst.ty {
+        Type::Ident(ident) => Atom::from(&ident.rust),
+        _ => None,
+    };
+    let atom = match atom {
+        Some(
+            atom @ (Bool | U8 | U16 | U32 | U64 | Usize | I8 | I16 | I32 | I64 | Isize | F32 | F64),
+        ) => atom,
+        _ => {
+            let msg = "unsupported type of constant, expected bool, integer or floating point";
+            cx.error(&konst.ty, msg);
+            return;
+        }
+    };
+
+    let (negative, lit) = match &konst.value {
+        Expr::Lit(lit) => (false, &lit.lit),
+        Expr::Unary(unary) => match (&unary.op, &*unary.expr) {
+            (UnOp::Neg(_), Expr::Lit(lit)) => (true, &lit.lit),
+            _ => return,
+        },
+        _ => return,
+    };
+
+    let matches_type = match atom {
+        Bool => !negative && matches!(lit, Lit::Bool(_)),
+        U8 | U16 | U32 | U64 | Usize => !negative && matches!(lit, Lit::Int(_)),
+        I8 | I16 | I32 | I64 | Isize => matches!(lit, Lit::Int(_)),
+        F32 | F64 => matches!(lit, Lit::Float(_)),
+        _ => unreachable!(),
+    };
+    if !matches_type {
+        let msg = format!("constant value is not a valid {} literal", atom);
+        cx.error(&konst.value, msg);
+    }
+}
+
 fn check_api_impl(cx: &mut Check, imp: &Impl) {
     let ty = &imp.ty;
 
@@ -513,8 +1018,13 @@ fn check_api_impl(cx: &mut Check, imp: &Impl) {
         return;
     }
 
+    if let Some(deleter) = &imp.deleter {
+        check_unique_ptr_deleter(cx, imp, deleter);
+    }
+
     match ty {
         Type::RustBox(ty)
+        | Type::RustArc(ty)
         | Type::RustVec(ty)
         | Type::UniquePtr(ty)
         | Type::SharedPtr(ty)
@@ -532,6 +1042,38 @@ fn check_api_impl(cx: &mut Check, imp: &Impl) {
     cx.error(imp, "unsupported Self type of explicit impl");
 }
 
+fn check_unique_ptr_deleter(cx: &mut Check, imp: &Impl, deleter: &Type) {
+    if let Type::UniquePtr(ptr) = &imp.ty {
+        if let Type::Ident(inner) = &ptr.inner {
+            if cx.types.rust.contains(&inner.rust) {
+                cx.error(
+                    deleter,
+                    "UniquePtr of a Rust type always uses rust::BoxDeleter; a custom deleter is not supported",
+                );
+                return;
+            }
+            if !cx.types.cxx.contains(&inner.rust) {
+                cx.error(
+                    deleter,
+                    "custom deleter is only supported for UniquePtr of an extern C++ type",
+                );
+                return;
+            }
+        }
+    }
+
+    if let Type::Ident(ident) = deleter {
+        if cx.types.cxx.contains(&ident.rust) && ident.generics.lifetimes.is_empty() {
+            return;
+        }
+    }
+
+    cx.error(
+        deleter,
+        "deleter of a UniquePtr must be a type declared in an extern \"C++\" block",
+    );
+}
+
 fn check_mut_return_restriction(cx: &mut Check, efn: &ExternFn) {
     if efn.sig.unsafety.is_some() {
         // Unrestricted as long as the function is made unsafe-to-call.
@@ -643,6 +1185,7 @@ fn is_unsized(cx: &mut Check, ty: &Type) -> bool {
         Type::Array(array) => is_unsized(cx, &array.inner),
         Type::CxxVector(_) | Type::Fn(_) | Type::Void(_) => true,
         Type::RustBox(_)
+        | Type::RustArc(_)
         | Type::RustVec(_)
         | Type::UniquePtr(_)
         | Type::SharedPtr(_)
@@ -650,7 +1193,28 @@ fn is_unsized(cx: &mut Check, ty: &Type) -> bool {
         | Type::Ref(_)
         | Type::Ptr(_)
         | Type::Str(_)
-        | Type::SliceRef(_) => false,
+        | Type::SliceRef(_)
+        | Type::Tuple(_)
+        | Type::DynFn(_) => false,
+    }
+}
+
+fn is_copy_constructible(cx: &Check, ty: &Type) -> bool {
+    match ty {
+        Type::Ident(ident) => match cx.types.structs.get(&ident.rust) {
+            Some(strct) => strct
+                .fields
+                .iter()
+                .all(|field| is_copy_constructible(cx, &field.ty)),
+            None => true,
+        },
+        Type::Array(array) => is_copy_constructible(cx, &array.inner),
+        Type::Tuple(tuple) => tuple
+            .elems
+            .iter()
+            .all(|elem| is_copy_constructible(cx, elem)),
+        Type::RustBox(_) | Type::UniquePtr(_) => false,
+        _ => true,
     }
 }
 
@@ -717,6 +1281,7 @@ fn describe(cx: &mut Check, ty: &Type) -> String {
             }
         }
         Type::RustBox(_) => "Box".to_owned(),
+        Type::RustArc(_) => "Arc".to_owned(),
         Type::RustVec(_) => "Vec".to_owned(),
         Type::UniquePtr(_) => "unique_ptr".to_owned(),
         Type::SharedPtr(_) => "shared_ptr".to_owned(),
@@ -729,5 +1294,7 @@ fn describe(cx: &mut Check, ty: &Type) -> String {
         Type::Fn(_) => "function pointer".to_owned(),
         Type::Void(_) => "()".to_owned(),
         Type::Array(_) => "array".to_owned(),
+        Type::Tuple(_) => "tuple".to_owned(),
+        Type::DynFn(_) => "closure".to_owned(),
     }
 }
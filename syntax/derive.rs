@@ -9,10 +9,12 @@ pub struct Derive {
 
 #[derive(Copy, Clone, PartialEq)]
 pub enum Trait {
+    Add,
     Clone,
     Copy,
     Debug,
     Default,
+    Deref,
     Eq,
     ExternType,
     Hash,
@@ -26,10 +28,12 @@ pub enum Trait {
 impl Derive {
     pub fn from(ident: &Ident) -> Option<Self> {
         let what = match ident.to_string().as_str() {
+            "Add" => Trait::Add,
             "Clone" => Trait::Clone,
             "Copy" => Trait::Copy,
             "Debug" => Trait::Debug,
             "Default" => Trait::Default,
+            "Deref" => Trait::Deref,
             "Eq" => Trait::Eq,
             "ExternType" => Trait::ExternType,
             "Hash" => Trait::Hash,
@@ -54,10 +58,12 @@ impl PartialEq<Trait> for Derive {
 impl AsRef<str> for Trait {
     fn as_ref(&self) -> &str {
         match self {
+            Trait::Add => "Add",
             Trait::Clone => "Clone",
             Trait::Copy => "Copy",
             Trait::Debug => "Debug",
             Trait::Default => "Default",
+            Trait::Deref => "Deref",
             Trait::Eq => "Eq",
             Trait::ExternType => "ExternType",
             Trait::Hash => "Hash",
@@ -3,8 +3,8 @@ use crate::syntax::namespace::Namespace;
 use quote::quote;
 use syn::parse::{Error, Parse, ParseStream, Result};
 use syn::{
-    braced, token, Abi, Attribute, ForeignItem, Ident, Item as RustItem, ItemEnum, ItemImpl,
-    ItemStruct, ItemUse, LitStr, Token, Visibility,
+    braced, token, Abi, Attribute, ForeignItem, Ident, Item as RustItem, ItemConst, ItemEnum,
+    ItemImpl, ItemStruct, ItemUse, LitStr, Token, Visibility,
 };
 
 pub struct Module {
@@ -25,6 +25,7 @@ pub enum Item {
     ForeignMod(ItemForeignMod),
     Use(ItemUse),
     Impl(ItemImpl),
+    Const(ItemConst),
     Other(RustItem),
 }
 
@@ -117,6 +118,10 @@ impl Parse for Item {
                 item.attrs.splice(..0, attrs);
                 Ok(Item::Impl(item))
             }
+            RustItem::Const(mut item) => {
+                item.attrs.splice(..0, attrs);
+                Ok(Item::Const(item))
+            }
             RustItem::Use(mut item) => {
                 item.attrs.splice(..0, attrs);
                 Ok(Item::Use(item))
@@ -1,4 +1,5 @@
 use crate::syntax::check::Check;
+use crate::syntax::mangle::CXXBRIDGE;
 use crate::syntax::{error, Api, Pair};
 
 fn check(cx: &mut Check, name: &Pair) {
@@ -9,17 +10,24 @@ fn check(cx: &mut Check, name: &Pair) {
     check_rust_ident(cx, &name.rust.to_string());
 
     fn check_cxx_ident(cx: &mut Check, ident: &str) {
-        if ident.starts_with("cxxbridge") {
-            cx.error(ident, error::CXXBRIDGE_RESERVED.msg);
-        }
+        check_reserved_prefix(cx, ident);
         if ident.contains("__") {
             cx.error(ident, error::DOUBLE_UNDERSCORE.msg);
         }
     }
 
     fn check_rust_ident(cx: &mut Check, ident: &str) {
+        check_reserved_prefix(cx, ident);
+    }
+
+    // Also reserve the symbol prefix if cxx is built with a custom one, so
+    // that user-defined symbols never begin with it.
+    fn check_reserved_prefix(cx: &mut Check, ident: &str) {
         if ident.starts_with("cxxbridge") {
             cx.error(ident, error::CXXBRIDGE_RESERVED.msg);
+        } else if ident.starts_with(CXXBRIDGE) {
+            let msg = format!("identifiers starting with {} are reserved", CXXBRIDGE);
+            cx.error(ident, msg);
         }
     }
 }
@@ -52,6 +60,9 @@ pub(crate) fn check_all(cx: &mut Check, apis: &[Api]) {
             Api::TypeAlias(alias) => {
                 check(cx, &alias.name);
             }
+            Api::Const(konst) | Api::CxxConst(konst) => {
+                check(cx, &konst.name);
+            }
         }
     }
 }
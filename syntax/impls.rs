@@ -1,5 +1,6 @@
 use crate::syntax::{
-    Array, ExternFn, Include, Lifetimes, Ptr, Receiver, Ref, Signature, SliceRef, Ty1, Type, Var,
+    Array, DynFn, ExternFn, Include, Lifetimes, Ptr, Receiver, Ref, Signature, SliceRef, Tuple,
+    Ty1, Type, Var,
 };
 use std::hash::{Hash, Hasher};
 use std::mem;
@@ -45,6 +46,7 @@ impl Hash for Type {
         match self {
             Type::Ident(t) => t.hash(state),
             Type::RustBox(t) => t.hash(state),
+            Type::RustArc(t) => t.hash(state),
             Type::UniquePtr(t) => t.hash(state),
             Type::SharedPtr(t) => t.hash(state),
             Type::WeakPtr(t) => t.hash(state),
@@ -56,6 +58,8 @@ impl Hash for Type {
             Type::Fn(t) => t.hash(state),
             Type::SliceRef(t) => t.hash(state),
             Type::Array(t) => t.hash(state),
+            Type::Tuple(t) => t.hash(state),
+            Type::DynFn(t) => t.hash(state),
             Type::Void(_) => {}
         }
     }
@@ -68,6 +72,7 @@ impl PartialEq for Type {
         match (self, other) {
             (Type::Ident(lhs), Type::Ident(rhs)) => lhs == rhs,
             (Type::RustBox(lhs), Type::RustBox(rhs)) => lhs == rhs,
+            (Type::RustArc(lhs), Type::RustArc(rhs)) => lhs == rhs,
             (Type::UniquePtr(lhs), Type::UniquePtr(rhs)) => lhs == rhs,
             (Type::SharedPtr(lhs), Type::SharedPtr(rhs)) => lhs == rhs,
             (Type::WeakPtr(lhs), Type::WeakPtr(rhs)) => lhs == rhs,
@@ -77,6 +82,8 @@ impl PartialEq for Type {
             (Type::CxxVector(lhs), Type::CxxVector(rhs)) => lhs == rhs,
             (Type::Fn(lhs), Type::Fn(rhs)) => lhs == rhs,
             (Type::SliceRef(lhs), Type::SliceRef(rhs)) => lhs == rhs,
+            (Type::Tuple(lhs), Type::Tuple(rhs)) => lhs == rhs,
+            (Type::DynFn(lhs), Type::DynFn(rhs)) => lhs == rhs,
             (Type::Void(_), Type::Void(_)) => true,
             (_, _) => false,
         }
@@ -278,6 +285,7 @@ impl PartialEq for Array {
             semi_token: _,
             len,
             len_token: _,
+            len_const,
         } = self;
         let Array {
             bracket: _,
@@ -285,8 +293,9 @@ impl PartialEq for Array {
             semi_token: _,
             len: len2,
             len_token: _,
+            len_const: len_const2,
         } = other;
-        inner == inner2 && len == len2
+        inner == inner2 && len == len2 && len_const == len_const2
     }
 }
 
@@ -298,9 +307,82 @@ impl Hash for Array {
             semi_token: _,
             len,
             len_token: _,
+            len_const,
         } = self;
         inner.hash(state);
         len.hash(state);
+        len_const.hash(state);
+    }
+}
+
+impl Eq for Tuple {}
+
+impl PartialEq for Tuple {
+    fn eq(&self, other: &Self) -> bool {
+        let Tuple {
+            paren_token: _,
+            elems,
+        } = self;
+        let Tuple {
+            paren_token: _,
+            elems: elems2,
+        } = other;
+        elems.iter().eq(elems2)
+    }
+}
+
+impl Hash for Tuple {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let Tuple {
+            paren_token: _,
+            elems,
+        } = self;
+        elems.len().hash(state);
+        for elem in elems {
+            elem.hash(state);
+        }
+    }
+}
+
+impl Eq for DynFn {}
+
+impl PartialEq for DynFn {
+    fn eq(&self, other: &Self) -> bool {
+        let DynFn {
+            box_token: _,
+            langle: _,
+            dyn_token: _,
+            fn_mut: _,
+            sig,
+            send,
+            rangle: _,
+        } = self;
+        let DynFn {
+            box_token: _,
+            langle: _,
+            dyn_token: _,
+            fn_mut: _,
+            sig: sig2,
+            send: send2,
+            rangle: _,
+        } = other;
+        sig == sig2 && send.is_some() == send2.is_some()
+    }
+}
+
+impl Hash for DynFn {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let DynFn {
+            box_token: _,
+            langle: _,
+            dyn_token: _,
+            fn_mut: _,
+            sig,
+            send,
+            rangle: _,
+        } = self;
+        sig.hash(state);
+        send.is_some().hash(state);
     }
 }
 
@@ -314,9 +396,11 @@ impl PartialEq for Signature {
             fn_token: _,
             generics: _,
             receiver,
+            self_type,
             args,
             ret,
             throws,
+            error,
             paren_token: _,
             throws_tokens: _,
         } = self;
@@ -326,17 +410,21 @@ impl PartialEq for Signature {
             fn_token: _,
             generics: _,
             receiver: receiver2,
+            self_type: self_type2,
             args: args2,
             ret: ret2,
             throws: throws2,
+            error: error2,
             paren_token: _,
             throws_tokens: _,
         } = other;
         asyncness.is_some() == asyncness2.is_some()
             && unsafety.is_some() == unsafety2.is_some()
             && receiver == receiver2
+            && self_type == self_type2
             && ret == ret2
             && throws == throws2
+            && error == error2
             && args.len() == args2.len()
             && args.iter().zip(args2).all(|(arg, arg2)| {
                 let Var {
@@ -370,15 +458,18 @@ impl Hash for Signature {
             fn_token: _,
             generics: _,
             receiver,
+            self_type,
             args,
             ret,
             throws,
+            error,
             paren_token: _,
             throws_tokens: _,
         } = self;
         asyncness.is_some().hash(state);
         unsafety.is_some().hash(state);
         receiver.hash(state);
+        self_type.hash(state);
         for arg in args {
             let Var {
                 cfg: _,
@@ -393,6 +484,7 @@ impl Hash for Signature {
         }
         ret.hash(state);
         throws.hash(state);
+        error.hash(state);
     }
 }
 
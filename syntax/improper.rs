@@ -23,11 +23,14 @@ impl<'a> Types<'a> {
                 }
             }
             Type::RustBox(_)
+            | Type::RustArc(_)
             | Type::RustVec(_)
             | Type::Str(_)
             | Type::Fn(_)
             | Type::Void(_)
-            | Type::SliceRef(_) => Definite(true),
+            | Type::SliceRef(_)
+            | Type::Tuple(_)
+            | Type::DynFn(_) => Definite(true),
             Type::UniquePtr(_) | Type::SharedPtr(_) | Type::WeakPtr(_) | Type::CxxVector(_) => {
                 Definite(false)
             }
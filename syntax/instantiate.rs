@@ -6,6 +6,7 @@ use syn::Token;
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub enum ImplKey<'a> {
     RustBox(NamedImplKey<'a>),
+    RustArc(NamedImplKey<'a>),
     RustVec(NamedImplKey<'a>),
     UniquePtr(NamedImplKey<'a>),
     SharedPtr(NamedImplKey<'a>),
@@ -28,6 +29,10 @@ impl Type {
             if let Type::Ident(ident) = &ty.inner {
                 return Some(ImplKey::RustBox(NamedImplKey::new(ty, ident)));
             }
+        } else if let Type::RustArc(ty) = self {
+            if let Type::Ident(ident) = &ty.inner {
+                return Some(ImplKey::RustArc(NamedImplKey::new(ty, ident)));
+            }
         } else if let Type::RustVec(ty) = self {
             if let Type::Ident(ident) = &ty.inner {
                 return Some(ImplKey::RustVec(NamedImplKey::new(ty, ident)));
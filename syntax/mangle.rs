@@ -28,19 +28,18 @@
 //   (d) User-defined extern function.
 //          pattern:  {NAMESPACE...} $ {CXXBRIDGE} $ {NAME}
 //          examples:
-//             - cxxbridge1$new_client
+//             - $cxxbridge1$new_client
 //             - org$rust$cxxbridge1$new_client
 //          defining characteristics:
 //             - cxxbridge is second from end
-//          FIXME: conflict with (a) if they collide with one of our one-off symbol names in the global namespace
 //
 //   (e) User-defined extern member function.
 //          pattern:  {NAMESPACE...} $ {CXXBRIDGE} $ {TYPE} $ {NAME}
 //          examples:
 //             - org$cxxbridge1$Struct$get
+//             - org$cxxbridge1$Struct$new  (constructor)
 //          defining characteristics:
 //             - cxxbridge is third from end
-//          FIXME: conflict with (b) if e.g. user binds a type in global namespace that collides with our builtin type names
 //
 //   (f) Operator overload.
 //          pattern:  {NAMESPACE...} $ {CXXBRIDGE} $ {TYPE} $ operator $ {NAME}
@@ -55,6 +54,20 @@
 //             - org$rust$cxxbridge1$Struct$invoke$f$0
 //          defining characteristics:
 //             - last symbol is `0` (C half) or `1` (Rust half) which are not legal identifiers on their own
+//             - last symbol is `2` for the C++ call shim of a C++ callable received by Rust
+//
+//   In (d) through (g) the global namespace is written as a single empty
+//   segment, so those symbols begin with `$`. Every other namespace begins with
+//   an identifier, and identifiers starting with cxxbridge are rejected by
+//   syntax/ident.rs. Thus (a) through (c) are exactly the symbols whose first
+//   segment is {CXXBRIDGE}, and within (d) through (g) the first {CXXBRIDGE}
+//   segment is where the namespace ends.
+//
+//   With the legacy-mangling feature of cxxbridge-flags, the global namespace
+//   contributes no segments, as in cxx 1.0.94 and older. Then (d) conflicts
+//   with (a) if the function is named like one of our one-off symbols, and (e)
+//   conflicts with (b) if the type is named like one of our builtin bindings.
+//   Those cases are rejected by syntax/check.rs instead.
 //
 //
 // Mangled preprocessor variable arrangements:
@@ -73,10 +86,37 @@
 //             - CXXBRIDGE1_STRUCT_org$rust$Struct
 //             - CXXBRIDGE1_ENUM_Enabled
 
+use crate::syntax::namespace::Namespace;
 use crate::syntax::symbol::{self, Symbol};
 use crate::syntax::{ExternFn, Pair, Types};
 
-const CXXBRIDGE: &str = "cxxbridge1";
+// The symbol prefix configured through cxxbridge-flags, cxxbridge1 by default.
+pub const CXXBRIDGE: &str = cxxbridge_flags::SYMBOL_PREFIX;
+
+// The {CXXBRIDGE} segment of the preprocessor variables, which is the symbol
+// prefix in uppercase: CXXBRIDGE1 by default.
+#[allow(dead_code)] // only used by cxx-build, not cxxbridge-macro
+pub fn preprocessor_prefix() -> String {
+    CXXBRIDGE.to_ascii_uppercase()
+}
+
+// The NAME of the one-off symbols in arrangement (a).
+pub const ONE_OFF_SYMBOLS: &[&str] = &["error", "exception"];
+
+// The first TYPE segment of the symbols in arrangements (b) and (c).
+pub const BUILTIN_TYPES: &[&str] = &[
+    "arc",
+    "box",
+    "cxx_string",
+    "rust_vec",
+    "shared_ptr",
+    "slice",
+    "std",
+    "str",
+    "string",
+    "unique_ptr",
+    "weak_ptr",
+];
 
 macro_rules! join {
     ($($segment:expr),+ $(,)?) => {
@@ -85,7 +125,7 @@ macro_rules! join {
 }
 
 pub fn extern_fn(efn: &ExternFn, types: &Types) -> Symbol {
-    match &efn.receiver {
+    let symbol = match &efn.receiver {
         Some(receiver) => {
             let receiver_ident = types.resolve(&receiver.ty);
             join!(
@@ -95,18 +135,38 @@ pub fn extern_fn(efn: &ExternFn, types: &Types) -> Symbol {
                 efn.name.rust,
             )
         }
-        None => join!(efn.name.namespace, CXXBRIDGE, efn.name.rust),
-    }
+        None => match &efn.self_type {
+            Some(self_type) => {
+                let self_type_ident = types.resolve(self_type);
+                join!(
+                    efn.name.namespace,
+                    CXXBRIDGE,
+                    self_type_ident.name.cxx,
+                    efn.name.rust,
+                )
+            }
+            None => join!(efn.name.namespace, CXXBRIDGE, efn.name.rust),
+        },
+    };
+    user_defined(&efn.name.namespace, symbol)
 }
 
 pub fn operator(receiver: &Pair, operator: &'static str) -> Symbol {
-    join!(
+    let symbol = join!(
         receiver.namespace,
         CXXBRIDGE,
         receiver.cxx,
         "operator",
         operator,
-    )
+    );
+    user_defined(&receiver.namespace, symbol)
+}
+
+fn user_defined(namespace: &Namespace, mut symbol: Symbol) -> Symbol {
+    if namespace.is_root() && !cxxbridge_flags::LEGACY_MANGLING {
+        symbol.prepend_empty_segment();
+    }
+    symbol
 }
 
 // The C half of a function pointer trampoline.
@@ -118,3 +178,67 @@ pub fn c_trampoline(efn: &ExternFn, var: &Pair, types: &Types) -> Symbol {
 pub fn r_trampoline(efn: &ExternFn, var: &Pair, types: &Types) -> Symbol {
     join!(extern_fn(efn, types), var.rust, 1)
 }
+
+// The C++ entry point through which Rust calls a C++ callable it was passed.
+pub fn closure_call(efn: &ExternFn, var: &Pair, types: &Types) -> Symbol {
+    join!(extern_fn(efn, types), var.rust, 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BUILTIN_TYPES, ONE_OFF_SYMBOLS};
+    use std::fs;
+    use std::path::Path;
+
+    // Every symbol exported by the handwritten C++ runtime must be one that
+    // syntax/check.rs knows to keep user-defined symbols from colliding with.
+    #[test]
+    fn test_runtime_symbols_are_reserved() {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let root = manifest_dir
+            .ancestors()
+            .find(|dir| dir.join("src").join("cxx.cc").exists())
+            .expect("cxx repository root");
+
+        let mut count = 0;
+        for file in ["src/cxx.cc", "include/cxx.h"] {
+            let content = fs::read_to_string(root.join(file)).unwrap();
+            for symbol in runtime_symbols(&content) {
+                let segments: Vec<&str> = symbol.split('$').collect();
+                match segments.as_slice() {
+                    ["cxxbridge1", name] => assert!(
+                        ONE_OFF_SYMBOLS.contains(name),
+                        "{} in {} is missing from ONE_OFF_SYMBOLS",
+                        symbol,
+                        file,
+                    ),
+                    ["cxxbridge1", ty, _, ..] => assert!(
+                        BUILTIN_TYPES.contains(ty),
+                        "{} in {} is missing from BUILTIN_TYPES",
+                        symbol,
+                        file,
+                    ),
+                    _ => unreachable!(),
+                }
+                count += 1;
+            }
+        }
+        assert!(count > 0);
+    }
+
+    // Symbols spliced together by the preprocessor, such as
+    // `cxxbridge1$rust_vec$##RUST_TYPE##$new`, are cut off at the `##`. The
+    // trailing `$` is kept so that they still count as more than 2 segments.
+    fn runtime_symbols(content: &str) -> Vec<&str> {
+        let is_ident = |ch: char| ch.is_ascii_alphanumeric() || ch == '_' || ch == '$';
+        let mut symbols = Vec::new();
+        for (i, _) in content.match_indices("cxxbridge1$") {
+            if content[..i].ends_with(is_ident) {
+                continue;
+            }
+            let len = content[i..].find(|ch| !is_ident(ch)).unwrap();
+            symbols.push(&content[i..i + len]);
+        }
+        symbols
+    }
+}
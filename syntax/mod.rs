@@ -28,7 +28,7 @@ mod tokens;
 mod toposort;
 pub mod trivial;
 pub mod types;
-mod visit;
+pub mod visit;
 
 use self::attrs::OtherAttrs;
 use self::cfg::CfgExpr;
@@ -58,6 +58,8 @@ pub enum Api {
     RustFunction(ExternFn),
     TypeAlias(TypeAlias),
     Impl(Impl),
+    Const(Const),
+    CxxConst(Const),
 }
 
 pub struct Include {
@@ -82,6 +84,7 @@ pub struct ExternType {
     pub lang: Lang,
     pub doc: Doc,
     pub derives: Vec<Derive>,
+    pub bases: Vec<Ident>,
     pub attrs: OtherAttrs,
     pub visibility: Token![pub],
     pub type_token: Token![type],
@@ -144,6 +147,9 @@ pub struct ExternFn {
     pub sig: Signature,
     pub semi_token: Token![;],
     pub trusted: bool,
+    pub constructor: bool,
+    pub catch_unwind: bool,
+    pub dlopen: Option<Ident>,
 }
 
 pub struct TypeAlias {
@@ -160,6 +166,20 @@ pub struct TypeAlias {
     pub semi_token: Token![;],
 }
 
+pub struct Const {
+    pub cfg: CfgExpr,
+    pub doc: Doc,
+    pub attrs: OtherAttrs,
+    pub visibility: Token![pub],
+    pub const_token: Token![const],
+    pub name: Pair,
+    pub colon_token: Token![:],
+    pub ty: Type,
+    pub eq_token: Token![=],
+    pub value: Expr,
+    pub semi_token: Token![;],
+}
+
 pub struct Impl {
     pub cfg: CfgExpr,
     pub impl_token: Token![impl],
@@ -167,6 +187,7 @@ pub struct Impl {
     pub negative: bool,
     pub ty: Type,
     pub ty_generics: Lifetimes,
+    pub deleter: Option<Type>,
     pub brace_token: Brace,
     pub negative_token: Option<Token![!]>,
 }
@@ -184,9 +205,11 @@ pub struct Signature {
     pub fn_token: Token![fn],
     pub generics: Generics,
     pub receiver: Option<Receiver>,
+    pub self_type: Option<NamedType>,
     pub args: Punctuated<Var, Token![,]>,
     pub ret: Option<Type>,
     pub throws: bool,
+    pub error: Option<Type>,
     pub paren_token: Paren,
     pub throws_tokens: Option<(kw::Result, Token![<], Token![>])>,
 }
@@ -226,6 +249,7 @@ pub struct Variant {
 pub enum Type {
     Ident(NamedType),
     RustBox(Box<Ty1>),
+    RustArc(Box<Ty1>),
     RustVec(Box<Ty1>),
     UniquePtr(Box<Ty1>),
     SharedPtr(Box<Ty1>),
@@ -238,6 +262,8 @@ pub enum Type {
     Void(Span),
     SliceRef(Box<SliceRef>),
     Array(Box<Array>),
+    Tuple(Box<Tuple>),
+    DynFn(Box<DynFn>),
 }
 
 pub struct Ty1 {
@@ -280,6 +306,23 @@ pub struct Array {
     pub semi_token: Token![;],
     pub len: usize,
     pub len_token: LitInt,
+    pub len_const: Option<Ident>,
+}
+
+pub struct Tuple {
+    pub paren_token: Paren,
+    pub elems: Punctuated<Type, Token![,]>,
+}
+
+// Box<dyn FnMut(A, B) -> R + Send>
+pub struct DynFn {
+    pub box_token: Ident,
+    pub langle: Token![<],
+    pub dyn_token: Token![dyn],
+    pub fn_mut: Ident,
+    pub sig: Signature,
+    pub send: Option<Ident>,
+    pub rangle: Token![>],
 }
 
 #[derive(Copy, Clone, PartialEq)]
@@ -50,6 +50,16 @@ impl ForeignName {
             Err(err) => Err(Error::new(span, err)),
         }
     }
+
+    pub fn parse_operator(text: &str, span: Span) -> Result<Self> {
+        // Member operators which map onto a Rust std trait.
+        match text {
+            "operator[]" => Ok(ForeignName {
+                text: text.to_owned(),
+            }),
+            _ => ForeignName::parse(text, span),
+        }
+    }
 }
 
 impl Display for ForeignName {
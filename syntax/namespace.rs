@@ -20,6 +20,10 @@ impl Namespace {
         segments: Vec::new(),
     };
 
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
     pub fn iter(&self) -> Iter<Ident> {
         self.segments.iter()
     }
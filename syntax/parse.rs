@@ -5,9 +5,9 @@ use crate::syntax::file::{Item, ItemForeignMod};
 use crate::syntax::report::Errors;
 use crate::syntax::Atom::*;
 use crate::syntax::{
-    attrs, error, Api, Array, Derive, Doc, Enum, EnumRepr, ExternFn, ExternType, ForeignName, Impl,
-    Include, IncludeKind, Lang, Lifetimes, NamedType, Namespace, Pair, Ptr, Receiver, Ref,
-    Signature, SliceRef, Struct, Ty1, Type, TypeAlias, Var, Variant,
+    attrs, error, Api, Array, Const, Derive, Doc, DynFn, Enum, EnumRepr, ExternFn, ExternType,
+    ForeignName, Impl, Include, IncludeKind, Lang, Lifetimes, NamedType, Namespace, Pair, Ptr,
+    Receiver, Ref, Signature, SliceRef, Struct, Tuple, Ty1, Type, TypeAlias, Var, Variant,
 };
 use proc_macro2::{Delimiter, Group, Span, TokenStream, TokenTree};
 use quote::{format_ident, quote, quote_spanned};
@@ -15,11 +15,12 @@ use std::mem;
 use syn::parse::{ParseStream, Parser};
 use syn::punctuated::Punctuated;
 use syn::{
-    Abi, Attribute, Error, Expr, Fields, FnArg, ForeignItem, ForeignItemFn, ForeignItemType,
-    GenericArgument, GenericParam, Generics, Ident, ItemEnum, ItemImpl, ItemStruct, Lit, LitStr,
-    Pat, PathArguments, Result, ReturnType, Signature as RustSignature, Token, TraitBound,
-    TraitBoundModifier, Type as RustType, TypeArray, TypeBareFn, TypeParamBound, TypePath, TypePtr,
-    TypeReference, Variant as RustVariant, Visibility,
+    Abi, AngleBracketedGenericArguments, Attribute, Error, Expr, Fields, FnArg, ForeignItem,
+    ForeignItemFn, ForeignItemType, GenericArgument, GenericParam, Generics, Ident, ImplItem,
+    ItemConst, ItemEnum, ItemImpl, ItemStruct, Lit, LitInt, LitStr, Pat, PathArguments, Result,
+    ReturnType, Signature as RustSignature, Token, TraitBound, TraitBoundModifier,
+    Type as RustType, TypeArray, TypeBareFn, TypeParamBound, TypePath, TypePtr, TypeReference,
+    TypeTraitObject, TypeTuple, UnOp, Variant as RustVariant, Visibility,
 };
 
 pub mod kw {
@@ -44,10 +45,17 @@ pub fn parse_items(
             Item::ForeignMod(foreign_mod) => {
                 parse_foreign_mod(cx, foreign_mod, &mut apis, trusted, namespace)
             }
+            Item::Impl(item) if is_constructor_impl(&item) => {
+                parse_constructors(cx, item, &mut apis, trusted, namespace)
+            }
             Item::Impl(item) => match parse_impl(cx, item) {
                 Ok(imp) => apis.push(imp),
                 Err(err) => cx.push(err),
             },
+            Item::Const(item) => match parse_const(cx, item, namespace) {
+                Ok(konst) => apis.push(konst),
+                Err(err) => cx.push(err),
+            },
             Item::Use(item) => cx.error(item, error::USE_NOT_ALLOWED),
             Item::Other(item) => cx.error(item, "unsupported item"),
         }
@@ -360,16 +368,34 @@ fn parse_foreign_mod(
 
     let mut cfg = CfgExpr::Unconditional;
     let mut namespace = namespace.clone();
+    let mut catch_unwind = false;
+    let mut dlopen = None;
     let attrs = attrs::parse(
         cx,
         foreign_mod.attrs,
         attrs::Parser {
             cfg: Some(&mut cfg),
             namespace: Some(&mut namespace),
+            catch_unwind: Some(&mut catch_unwind),
+            dlopen: Some(&mut dlopen),
             ..Default::default()
         },
     );
 
+    if catch_unwind && lang == Lang::Cxx {
+        cx.error(
+            &foreign_mod.abi,
+            "#[catch_unwind] is only supported on extern \"Rust\" blocks",
+        );
+    }
+
+    if dlopen.is_some() && lang == Lang::Rust {
+        cx.error(
+            &foreign_mod.abi,
+            "#[dlopen] is only supported on extern \"C++\" blocks",
+        );
+    }
+
     let mut items = Vec::new();
     for foreign in foreign_mod.items {
         match foreign {
@@ -378,7 +404,17 @@ fn parse_foreign_mod(
                 items.push(ety);
             }
             ForeignItem::Fn(foreign) => {
-                match parse_extern_fn(cx, foreign, lang, trusted, &cfg, &namespace, &attrs) {
+                match parse_extern_fn(
+                    cx,
+                    foreign,
+                    lang,
+                    trusted,
+                    catch_unwind,
+                    dlopen.as_ref(),
+                    &cfg,
+                    &namespace,
+                    &attrs,
+                ) {
                     Ok(efn) => items.push(efn),
                     Err(err) => cx.push(err),
                 }
@@ -468,6 +504,7 @@ fn parse_extern_type(
     let mut cfg = extern_block_cfg.clone();
     let mut doc = Doc::new();
     let mut derives = Vec::new();
+    let mut bases = Vec::new();
     let mut namespace = namespace.clone();
     let mut cxx_name = None;
     let mut rust_name = None;
@@ -479,6 +516,7 @@ fn parse_extern_type(
             cfg: Some(&mut cfg),
             doc: Some(&mut doc),
             derives: Some(&mut derives),
+            bases: Some(&mut bases),
             namespace: Some(&mut namespace),
             cxx_name: Some(&mut cxx_name),
             rust_name: Some(&mut rust_name),
@@ -502,6 +540,7 @@ fn parse_extern_type(
         lang,
         doc,
         derives,
+        bases,
         attrs,
         visibility,
         type_token,
@@ -519,6 +558,8 @@ fn parse_extern_fn(
     mut foreign_fn: ForeignItemFn,
     lang: Lang,
     trusted: bool,
+    extern_block_catch_unwind: bool,
+    extern_block_dlopen: Option<&Ident>,
     extern_block_cfg: &CfgExpr,
     namespace: &Namespace,
     attrs: &OtherAttrs,
@@ -528,6 +569,8 @@ fn parse_extern_fn(
     let mut namespace = namespace.clone();
     let mut cxx_name = None;
     let mut rust_name = None;
+    let mut self_type = None;
+    let mut catch_unwind = false;
     let mut attrs = attrs.clone();
     attrs.extend(attrs::parse(
         cx,
@@ -538,6 +581,9 @@ fn parse_extern_fn(
             namespace: Some(&mut namespace),
             cxx_name: Some(&mut cxx_name),
             rust_name: Some(&mut rust_name),
+            self_type: Some(&mut self_type),
+            catch_unwind: Some(&mut catch_unwind),
+            allow_operator: lang == Lang::Cxx,
             ..Default::default()
         },
     ));
@@ -660,9 +706,35 @@ fn parse_extern_fn(
         }
     }
 
+    if receiver.is_some() && self_type.is_some() {
+        return Err(Error::new_spanned(
+            foreign_fn,
+            "#[Self] is only supported on functions without a self receiver",
+        ));
+    }
+    let self_type = self_type.map(NamedType::new);
+
     let mut throws_tokens = None;
-    let ret = parse_return_type(&foreign_fn.sig.output, &mut throws_tokens)?;
+    let mut error = None;
+    let ret = parse_return_type(&foreign_fn.sig.output, &mut throws_tokens, &mut error)?;
     let throws = throws_tokens.is_some();
+    if catch_unwind {
+        if lang == Lang::Cxx {
+            return Err(Error::new_spanned(
+                foreign_fn,
+                "#[catch_unwind] is only supported on extern \"Rust\" functions",
+            ));
+        }
+        if !throws {
+            return Err(Error::new_spanned(
+                foreign_fn,
+                "#[catch_unwind] is only supported on functions returning Result",
+            ));
+        }
+    }
+    // An extern block's #[catch_unwind] covers those of its functions that are
+    // able to throw; the rest keep aborting on panic.
+    let catch_unwind = (catch_unwind || extern_block_catch_unwind) && throws;
     let asyncness = foreign_fn.sig.asyncness;
     let unsafety = foreign_fn.sig.unsafety;
     let fn_token = foreign_fn.sig.fn_token;
@@ -689,14 +761,19 @@ fn parse_extern_fn(
             fn_token,
             generics,
             receiver,
+            self_type,
             args,
             ret,
             throws,
+            error,
             paren_token,
             throws_tokens,
         },
         semi_token,
         trusted,
+        constructor: false,
+        catch_unwind,
+        dlopen: extern_block_dlopen.cloned(),
     }))
 }
 
@@ -897,6 +974,68 @@ fn parse_type_alias(
     }))
 }
 
+fn parse_const(cx: &mut Errors, item: ItemConst, namespace: &Namespace) -> Result<Api> {
+    let mut cfg = CfgExpr::Unconditional;
+    let mut doc = Doc::new();
+    let mut namespace = namespace.clone();
+    let mut cxx_name = None;
+    let mut rust_name = None;
+    let mut from_header = false;
+    let attrs = attrs::parse(
+        cx,
+        item.attrs,
+        attrs::Parser {
+            cfg: Some(&mut cfg),
+            doc: Some(&mut doc),
+            namespace: Some(&mut namespace),
+            cxx_name: Some(&mut cxx_name),
+            rust_name: Some(&mut rust_name),
+            from_header: Some(&mut from_header),
+            ..Default::default()
+        },
+    );
+
+    if !item.generics.params.is_empty() {
+        let msg = "generic constant is not supported";
+        return Err(Error::new_spanned(&item.generics, msg));
+    }
+
+    let value = *item.expr;
+    let literal = match &value {
+        Expr::Lit(_) => true,
+        Expr::Unary(unary) => {
+            matches!(unary.op, UnOp::Neg(_)) && matches!(*unary.expr, Expr::Lit(_))
+        }
+        _ => false,
+    };
+    if !literal {
+        let msg = "constant value must be a literal";
+        return Err(Error::new_spanned(value, msg));
+    }
+
+    let visibility = visibility_pub(&item.vis, item.const_token.span);
+    let name = pair(namespace, &item.ident, cxx_name, rust_name);
+    let konst = Const {
+        cfg,
+        doc,
+        attrs,
+        visibility,
+        const_token: item.const_token,
+        name,
+        colon_token: item.colon_token,
+        ty: parse_type(&item.ty)?,
+        eq_token: item.eq_token,
+        value,
+        semi_token: item.semi_token,
+    };
+
+    Ok(if from_header {
+        Api::CxxConst(konst)
+    } else {
+        Api::Const(konst)
+    })
+}
+
 fn parse_extern_type_bounded(
     cx: &mut Errors,
     unparsed_attrs: Vec<Attribute>,
@@ -945,6 +1084,7 @@ fn parse_extern_type_bounded(
     let mut cfg = extern_block_cfg.clone();
     let mut doc = Doc::new();
     let mut derives = Vec::new();
+    let mut bases = Vec::new();
     let mut namespace = namespace.clone();
     let mut cxx_name = None;
     let mut rust_name = None;
@@ -956,6 +1096,7 @@ fn parse_extern_type_bounded(
             cfg: Some(&mut cfg),
             doc: Some(&mut doc),
             derives: Some(&mut derives),
+            bases: Some(&mut bases),
             namespace: Some(&mut namespace),
             cxx_name: Some(&mut cxx_name),
             rust_name: Some(&mut rust_name),
@@ -974,6 +1115,7 @@ fn parse_extern_type_bounded(
         lang,
         doc,
         derives,
+        bases,
         attrs,
         visibility,
         type_token,
@@ -1059,9 +1201,14 @@ fn parse_impl(cx: &mut Errors, imp: ItemImpl) -> Result<Api> {
         }
     }
 
+    let deleter = match split_unique_ptr_deleter(&mut self_ty) {
+        Some(deleter) => Some(parse_type(&deleter)?),
+        None => None,
+    };
     let ty = parse_type(&self_ty)?;
     let ty_generics = match &ty {
         Type::RustBox(ty)
+        | Type::RustArc(ty)
         | Type::RustVec(ty)
         | Type::UniquePtr(ty)
         | Type::SharedPtr(ty)
@@ -1077,7 +1224,9 @@ fn parse_impl(cx: &mut Errors, imp: ItemImpl) -> Result<Api> {
         | Type::Fn(_)
         | Type::Void(_)
         | Type::SliceRef(_)
-        | Type::Array(_) => Lifetimes::default(),
+        | Type::Array(_)
+        | Type::Tuple(_)
+        | Type::DynFn(_) => Lifetimes::default(),
     };
 
     let negative = negative_token.is_some();
@@ -1090,11 +1239,146 @@ fn parse_impl(cx: &mut Errors, imp: ItemImpl) -> Result<Api> {
         negative,
         ty,
         ty_generics,
+        deleter,
         brace_token,
         negative_token,
     }))
 }
 
+// impl UniquePtr<T, Deleter> {}
+fn split_unique_ptr_deleter(self_ty: &mut RustType) -> Option<RustType> {
+    let path = match self_ty {
+        RustType::Path(ty) if ty.qself.is_none() && ty.path.leading_colon.is_none() => &mut ty.path,
+        _ => return None,
+    };
+    if path.segments.len() != 1 || path.segments[0].ident != "UniquePtr" {
+        return None;
+    }
+    let generic = match &mut path.segments[0].arguments {
+        PathArguments::AngleBracketed(generic) if generic.args.len() == 2 => generic,
+        _ => return None,
+    };
+    if let GenericArgument::Type(_) = &generic.args[1] {
+        let mut args = mem::take(&mut generic.args).into_iter();
+        generic.args.push(args.next()?);
+        match args.next()? {
+            GenericArgument::Type(deleter) => Some(deleter),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+fn is_constructor_impl(imp: &ItemImpl) -> bool {
+    // impl TheType { fn new(...) -> Self; }
+    !imp.items.is_empty()
+        && imp.trait_.is_none()
+        && match imp.self_ty.as_ref() {
+            RustType::Path(ty) => ty.qself.is_none() && ty.path.get_ident().is_some(),
+            _ => false,
+        }
+}
+
+fn parse_constructors(
+    cx: &mut Errors,
+    imp: ItemImpl,
+    out: &mut Vec<Api>,
+    trusted: bool,
+    namespace: &Namespace,
+) {
+    let mut cfg = CfgExpr::Unconditional;
+    let attrs = attrs::parse(
+        cx,
+        imp.attrs,
+        attrs::Parser {
+            cfg: Some(&mut cfg),
+            ..Default::default()
+        },
+    );
+
+    if !imp.generics.params.is_empty() || imp.generics.where_clause.is_some() {
+        let impl_token = imp.impl_token;
+        let generics = &imp.generics;
+        let span = quote!(#impl_token #generics);
+        cx.error(span, "generic parameter on an impl is not supported yet");
+        return;
+    }
+
+    let self_ty = match parse_type(&imp.self_ty) {
+        Ok(Type::Ident(ident)) => ident,
+        Ok(_) => {
+            let self_ty = &imp.self_ty;
+            cx.error(self_ty, "unsupported Self type of constructor impl");
+            return;
+        }
+        Err(err) => return cx.push(err),
+    };
+
+    for item in imp.items {
+        let tokens = match item {
+            ImplItem::Verbatim(tokens) => tokens,
+            _ => {
+                cx.error(
+                    item,
+                    "expected a constructor signature, like `fn new() -> Self;`",
+                );
+                continue;
+            }
+        };
+        let foreign_fn: ForeignItemFn = match syn::parse2(tokens) {
+            Ok(foreign_fn) => foreign_fn,
+            Err(err) => {
+                cx.push(err);
+                continue;
+            }
+        };
+        let catch_unwind = false;
+        let dlopen = None;
+        let mut efn = match parse_extern_fn(
+            cx,
+            foreign_fn,
+            Lang::Cxx,
+            trusted,
+            catch_unwind,
+            dlopen,
+            &cfg,
+            namespace,
+            &attrs,
+        ) {
+            Ok(Api::CxxFunction(efn)) => efn,
+            Ok(_) => unreachable!(),
+            Err(err) => {
+                cx.push(err);
+                continue;
+            }
+        };
+        if let Some(receiver) = &efn.receiver {
+            cx.error(receiver.var, "constructor cannot take a self receiver");
+            continue;
+        }
+        let span = match &efn.ret {
+            Some(Type::Ident(ident)) if ident.rust == "Self" => ident.rust.span(),
+            _ => {
+                let fn_token = efn.fn_token;
+                let ident = &efn.name.rust;
+                let span = quote!(#fn_token #ident);
+                cx.error(span, "constructor must return `Self`");
+                continue;
+            }
+        };
+        efn.sig.ret = Some(Type::UniquePtr(Box::new(Ty1 {
+            name: Ident::new("UniquePtr", span),
+            langle: Token![<](span),
+            inner: Type::Ident(NamedType::new(self_ty.rust.clone())),
+            rangle: Token![>](span),
+        })));
+        efn.self_type = Some(NamedType::new(self_ty.rust.clone()));
+        efn.constructor = true;
+        out.push(Api::CxxFunction(efn));
+    }
+}
+
 fn parse_include(input: ParseStream) -> Result<Include> {
     if input.peek(LitStr) {
         let lit: LitStr = input.parse()?;
@@ -1149,6 +1433,7 @@ fn parse_type(ty: &RustType) -> Result<Type> {
         RustType::Array(ty) => parse_type_array(ty),
         RustType::BareFn(ty) => parse_type_fn(ty),
         RustType::Tuple(ty) if ty.elems.is_empty() => Ok(Type::Void(ty.paren_token.span.join())),
+        RustType::Tuple(ty) => parse_type_tuple(ty),
         _ => Err(Error::new_spanned(ty, "unsupported type")),
     }
 }
@@ -1262,7 +1547,9 @@ fn parse_type_path(ty: &TypePath) -> Result<Type> {
                         })));
                     }
                 } else if ident == "Box" && generic.args.len() == 1 {
-                    if let GenericArgument::Type(arg) = &generic.args[0] {
+                    if let GenericArgument::Type(RustType::TraitObject(arg)) = &generic.args[0] {
+                        return parse_type_dyn_fn(ident, generic, arg);
+                    } else if let GenericArgument::Type(arg) = &generic.args[0] {
                         let inner = parse_type(arg)?;
                         return Ok(Type::RustBox(Box::new(Ty1 {
                             name: ident,
@@ -1271,6 +1558,16 @@ fn parse_type_path(ty: &TypePath) -> Result<Type> {
                             rangle: generic.gt_token,
                         })));
                     }
+                } else if ident == "Arc" && generic.args.len() == 1 {
+                    if let GenericArgument::Type(arg) = &generic.args[0] {
+                        let inner = parse_type(arg)?;
+                        return Ok(Type::RustArc(Box::new(Ty1 {
+                            name: ident,
+                            langle: generic.lt_token,
+                            inner,
+                            rangle: generic.gt_token,
+                        })));
+                    }
                 } else if ident == "Vec" && generic.args.len() == 1 {
                     if let GenericArgument::Type(arg) = &generic.args[0] {
                         let inner = parse_type(arg)?;
@@ -1328,12 +1625,29 @@ fn parse_type_path(ty: &TypePath) -> Result<Type> {
 
 fn parse_type_array(ty: &TypeArray) -> Result<Type> {
     let inner = parse_type(&ty.elem)?;
+    let bracket = ty.bracket_token;
+    let semi_token = ty.semi_token;
 
-    let len_expr = if let Expr::Lit(lit) = &ty.len {
-        lit
-    } else {
-        let msg = "unsupported expression, array length must be an integer literal";
-        return Err(Error::new_spanned(&ty.len, msg));
+    let len_expr = match &ty.len {
+        Expr::Lit(lit) => lit,
+        Expr::Path(path) if path.qself.is_none() && path.path.get_ident().is_some() => {
+            // The length is resolved against the constants declared in the
+            // bridge by check.rs, once all of them have been parsed.
+            let len_const = path.path.get_ident().unwrap().clone();
+            return Ok(Type::Array(Box::new(Array {
+                bracket,
+                inner,
+                semi_token,
+                len: 0,
+                len_token: LitInt::new("0", len_const.span()),
+                len_const: Some(len_const),
+            })));
+        }
+        _ => {
+            let msg =
+                "unsupported expression, array length must be an integer literal or a constant";
+            return Err(Error::new_spanned(&ty.len, msg));
+        }
     };
 
     let len_token = if let Lit::Int(int) = &len_expr.lit {
@@ -1349,15 +1663,29 @@ fn parse_type_array(ty: &TypeArray) -> Result<Type> {
         return Err(Error::new_spanned(ty, msg));
     }
 
-    let bracket = ty.bracket_token;
-    let semi_token = ty.semi_token;
-
     Ok(Type::Array(Box::new(Array {
         bracket,
         inner,
         semi_token,
         len,
         len_token,
+        len_const: None,
+    })))
+}
+
+fn parse_type_tuple(ty: &TypeTuple) -> Result<Type> {
+    let mut elems = Punctuated::new();
+    for pair in ty.elems.pairs() {
+        let (elem, comma) = pair.into_tuple();
+        elems.push_value(parse_type(elem)?);
+        if let Some(comma) = comma {
+            elems.push_punct(*comma);
+        }
+    }
+
+    Ok(Type::Tuple(Box::new(Tuple {
+        paren_token: ty.paren_token,
+        elems,
     })))
 }
 
@@ -1409,7 +1737,8 @@ fn parse_type_fn(ty: &TypeBareFn) -> Result<Type> {
         .collect::<Result<_>>()?;
 
     let mut throws_tokens = None;
-    let ret = parse_return_type(&ty.output, &mut throws_tokens)?;
+    let mut error = None;
+    let ret = parse_return_type(&ty.output, &mut throws_tokens, &mut error)?;
     let throws = throws_tokens.is_some();
 
     let asyncness = None;
@@ -1425,17 +1754,126 @@ fn parse_type_fn(ty: &TypeBareFn) -> Result<Type> {
         fn_token,
         generics,
         receiver,
+        self_type: None,
         args,
         ret,
         throws,
+        error,
         paren_token,
         throws_tokens,
     })))
 }
 
+fn parse_type_dyn_fn(
+    box_token: Ident,
+    generic: &AngleBracketedGenericArguments,
+    ty: &TypeTraitObject,
+) -> Result<Type> {
+    let unsupported = || {
+        Error::new_spanned(
+            ty,
+            "unsupported trait object; expected `Box<dyn FnMut(...) -> ... + Send>`",
+        )
+    };
+
+    let dyn_token = match ty.dyn_token {
+        Some(dyn_token) => dyn_token,
+        None => return Err(unsupported()),
+    };
+
+    let mut fn_mut = None;
+    let mut send = None;
+    for bound in &ty.bounds {
+        let bound = match bound {
+            TypeParamBound::Trait(bound)
+                if bound.lifetimes.is_none()
+                    && bound.path.leading_colon.is_none()
+                    && bound.path.segments.len() == 1
+                    && matches!(bound.modifier, TraitBoundModifier::None) =>
+            {
+                &bound.path.segments[0]
+            }
+            _ => return Err(unsupported()),
+        };
+        match &bound.arguments {
+            PathArguments::Parenthesized(arguments)
+                if bound.ident == "FnMut" && fn_mut.is_none() =>
+            {
+                fn_mut = Some((bound.ident.clone(), arguments));
+            }
+            PathArguments::None if bound.ident == "Send" && send.is_none() => {
+                send = Some(bound.ident.clone());
+            }
+            _ => return Err(unsupported()),
+        }
+    }
+
+    let (fn_mut, arguments) = match fn_mut {
+        Some(fn_mut) => fn_mut,
+        None => return Err(unsupported()),
+    };
+
+    let args = arguments
+        .inputs
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| {
+            let fn_span = arguments.paren_token.span.join();
+            let ident = format_ident!("arg{}", i, span = fn_span);
+            let colon_token = Token![:](fn_span);
+            let ty = parse_type(arg)?;
+            let cfg = CfgExpr::Unconditional;
+            let doc = Doc::new();
+            let attrs = OtherAttrs::none();
+            let visibility = Token![pub](ident.span());
+            let name = pair(Namespace::default(), &ident, None, None);
+            Ok(Var {
+                cfg,
+                doc,
+                attrs,
+                visibility,
+                name,
+                colon_token,
+                ty,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    let mut throws_tokens = None;
+    let mut error = None;
+    let ret = parse_return_type(&arguments.output, &mut throws_tokens, &mut error)?;
+    let throws = throws_tokens.is_some();
+
+    let sig = Signature {
+        asyncness: None,
+        unsafety: None,
+        fn_token: Token![fn](fn_mut.span()),
+        generics: Generics::default(),
+        receiver: None,
+        self_type: None,
+        args,
+        ret,
+        throws,
+        error,
+        paren_token: arguments.paren_token,
+        throws_tokens,
+    };
+
+    Ok(Type::DynFn(Box::new(DynFn {
+        box_token,
+        langle: generic.lt_token,
+        dyn_token,
+        fn_mut,
+        sig,
+        send,
+        rangle: generic.gt_token,
+    })))
+}
+
 fn parse_return_type(
     ty: &ReturnType,
     throws_tokens: &mut Option<(kw::Result, Token![<], Token![>])>,
+    error: &mut Option<Type>,
 ) -> Result<Option<Type>> {
     let mut ret = match ty {
         ReturnType::Default => return Ok(None),
@@ -1448,8 +1886,11 @@ fn parse_return_type(
             let segment = &path.segments[0];
             let ident = segment.ident.clone();
             if let PathArguments::AngleBracketed(generic) = &segment.arguments {
-                if ident == "Result" && generic.args.len() == 1 {
+                if ident == "Result" && (generic.args.len() == 1 || generic.args.len() == 2) {
                     if let GenericArgument::Type(arg) = &generic.args[0] {
+                        if let Some(GenericArgument::Type(err)) = generic.args.iter().nth(1) {
+                            *error = Some(parse_type(err)?);
+                        }
                         ret = arg;
                         *throws_tokens =
                             Some((kw::Result(ident.span()), generic.lt_token, generic.gt_token));
@@ -23,12 +23,15 @@ impl<'a> Types<'a> {
                 }
             }
             Type::RustBox(_)
+            | Type::RustArc(_)
             | Type::RustVec(_)
             | Type::UniquePtr(_)
             | Type::SharedPtr(_)
             | Type::WeakPtr(_)
             | Type::CxxVector(_)
-            | Type::Void(_) => false,
+            | Type::Void(_)
+            | Type::Tuple(_)
+            | Type::DynFn(_) => false,
             Type::Ref(_) | Type::Str(_) | Type::Fn(_) | Type::SliceRef(_) | Type::Ptr(_) => true,
             Type::Array(array) => self.is_guaranteed_pod(&array.inner),
         }
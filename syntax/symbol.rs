@@ -30,6 +30,11 @@ impl Symbol {
         assert!(self.0.len() > len_before);
     }
 
+    // Prepends an empty segment, as in $cxxbridge1$new_client.
+    pub fn prepend_empty_segment(&mut self) {
+        self.0.insert(0, '$');
+    }
+
     pub fn from_idents<'a>(it: impl Iterator<Item = &'a dyn Segment>) -> Self {
         let mut symbol = Symbol(String::new());
         for segment in it {
@@ -1,7 +1,7 @@
 use crate::syntax::atom::Atom::*;
 use crate::syntax::{
-    Array, Atom, Derive, Enum, EnumRepr, ExternFn, ExternType, Impl, Lifetimes, NamedType, Ptr,
-    Ref, Signature, SliceRef, Struct, Ty1, Type, TypeAlias, Var,
+    Array, Atom, Const, Derive, DynFn, Enum, EnumRepr, ExternFn, ExternType, Impl, Lifetimes,
+    NamedType, Ptr, Ref, Signature, SliceRef, Struct, Tuple, Ty1, Type, TypeAlias, Var,
 };
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote_spanned, ToTokens};
@@ -24,6 +24,7 @@ impl ToTokens for Type {
                 ident.to_tokens(tokens);
             }
             Type::RustBox(ty)
+            | Type::RustArc(ty)
             | Type::UniquePtr(ty)
             | Type::SharedPtr(ty)
             | Type::WeakPtr(ty)
@@ -35,6 +36,8 @@ impl ToTokens for Type {
             Type::Fn(f) => f.to_tokens(tokens),
             Type::Void(span) => tokens.extend(quote_spanned!(*span=> ())),
             Type::SliceRef(r) => r.to_tokens(tokens),
+            Type::Tuple(t) => t.to_tokens(tokens),
+            Type::DynFn(f) => f.to_tokens(tokens),
         }
     }
 }
@@ -72,6 +75,9 @@ impl ToTokens for Ty1 {
             "Box" => {
                 tokens.extend(quote_spanned!(span=> ::cxx::alloc::boxed::));
             }
+            "Arc" => {
+                tokens.extend(quote_spanned!(span=> ::cxx::alloc::sync::));
+            }
             "Vec" => {
                 tokens.extend(quote_spanned!(span=> ::cxx::alloc::vec::));
             }
@@ -152,15 +158,81 @@ impl ToTokens for Array {
             semi_token,
             len: _,
             len_token,
+            len_const,
         } = self;
         bracket.surround(tokens, |tokens| {
             inner.to_tokens(tokens);
             semi_token.to_tokens(tokens);
-            len_token.to_tokens(tokens);
+            match len_const {
+                Some(len_const) => len_const.to_tokens(tokens),
+                None => len_token.to_tokens(tokens),
+            }
         });
     }
 }
 
+impl ToTokens for Tuple {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Tuple { paren_token, elems } = self;
+        paren_token.surround(tokens, |tokens| elems.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for DynFn {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let DynFn {
+            box_token,
+            langle,
+            dyn_token,
+            fn_mut,
+            sig,
+            send,
+            rangle,
+        } = self;
+        let span = box_token.span();
+        tokens.extend(quote_spanned!(span=> ::cxx::alloc::boxed::));
+        box_token.to_tokens(tokens);
+        langle.to_tokens(tokens);
+        dyn_token.to_tokens(tokens);
+        let span = fn_mut.span();
+        tokens.extend(quote_spanned!(span=> ::cxx::core::ops::));
+        fn_mut.to_tokens(tokens);
+        sig.paren_token.surround(tokens, |tokens| {
+            for arg in &sig.args {
+                arg.ty.to_tokens(tokens);
+                Token![,](arg.name.rust.span()).to_tokens(tokens);
+            }
+        });
+        if sig.ret.is_some() || sig.throws {
+            Token![->](sig.paren_token.span.join()).to_tokens(tokens);
+        }
+        // The error type of a fallible closure is a plain String, which Rust
+        // closures can easily produce and which C++ exceptions map into.
+        match (&sig.ret, &sig.throws_tokens) {
+            (ret, Some((result, _langle, rangle))) => {
+                let span = result.span;
+                let ok = match ret {
+                    Some(ret) => ret.to_token_stream(),
+                    None => quote_spanned!(span=> ()),
+                };
+                tokens.extend(quote_spanned! {span=>
+                    ::cxx::core::result::Result<#ok, ::cxx::alloc::string::String
+                });
+                rangle.to_tokens(tokens);
+            }
+            (Some(ret), None) => ret.to_tokens(tokens),
+            (None, None) => {}
+        }
+        if let Some(send) = send {
+            Token![+](send.span()).to_tokens(tokens);
+            let span = send.span();
+            tokens.extend(quote_spanned!(span=> ::cxx::core::marker::));
+            send.to_tokens(tokens);
+        }
+        rangle.to_tokens(tokens);
+    }
+}
+
 impl ToTokens for Atom {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         Ident::new(self.as_ref(), Span::call_site()).to_tokens(tokens);
@@ -209,6 +281,15 @@ impl ToTokens for Enum {
     }
 }
 
+impl ToTokens for Const {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        // Notional token range for error reporting purposes.
+        self.const_token.to_tokens(tokens);
+        self.name.rust.to_tokens(tokens);
+        self.semi_token.to_tokens(tokens);
+    }
+}
+
 impl ToTokens for ExternFn {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         // Notional token range for error reporting purposes.
@@ -227,6 +308,7 @@ impl ToTokens for Impl {
             negative: _,
             ty,
             ty_generics: _,
+            deleter: _,
             brace_token,
             negative_token,
         } = self;
@@ -259,9 +341,11 @@ impl ToTokens for Signature {
             fn_token,
             generics: _,
             receiver: _,
+            self_type: _,
             args,
             ret,
             throws: _,
+            error,
             paren_token,
             throws_tokens,
         } = self;
@@ -269,22 +353,22 @@ impl ToTokens for Signature {
         paren_token.surround(tokens, |tokens| {
             args.to_tokens(tokens);
         });
-        if let Some(ret) = ret {
-            Token![->](paren_token.span.join()).to_tokens(tokens);
-            if let Some((result, langle, rangle)) = throws_tokens {
-                result.to_tokens(tokens);
-                langle.to_tokens(tokens);
-                ret.to_tokens(tokens);
-                rangle.to_tokens(tokens);
-            } else {
-                ret.to_tokens(tokens);
-            }
-        } else if let Some((result, langle, rangle)) = throws_tokens {
+        if let Some((result, langle, rangle)) = throws_tokens {
             Token![->](paren_token.span.join()).to_tokens(tokens);
             result.to_tokens(tokens);
             langle.to_tokens(tokens);
-            token::Paren(langle.span).surround(tokens, |_| ());
+            match ret {
+                Some(ret) => ret.to_tokens(tokens),
+                None => token::Paren(langle.span).surround(tokens, |_| ()),
+            }
+            if let Some(error) = error {
+                Token![,](rangle.span).to_tokens(tokens);
+                error.to_tokens(tokens);
+            }
             rangle.to_tokens(tokens);
+        } else if let Some(ret) = ret {
+            Token![->](paren_token.span.join()).to_tokens(tokens);
+            ret.to_tokens(tokens);
         }
     }
 }
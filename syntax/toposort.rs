@@ -36,11 +36,17 @@ fn visit<'a>(
     }
     let mut result = Ok(());
     for field in &strct.fields {
-        if let Type::Ident(ident) = &field.ty {
-            if let Some(inner) = types.structs.get(&ident.rust) {
-                if visit(cx, inner, sorted, marks, types).is_err() {
-                    cx.error(field, "unsupported cyclic data structure");
-                    result = Err(());
+        let by_value = match &field.ty {
+            Type::Tuple(tuple) => tuple.elems.iter().collect(),
+            ty => vec![ty],
+        };
+        for ty in by_value {
+            if let Type::Ident(ident) = ty {
+                if let Some(inner) = types.structs.get(&ident.rust) {
+                    if visit(cx, inner, sorted, marks, types).is_err() {
+                        cx.error(field, "unsupported cyclic data structure");
+                        result = Err(());
+                    }
                 }
             }
         }
@@ -1,5 +1,5 @@
 use crate::syntax::improper::ImproperCtype;
-use crate::syntax::instantiate::ImplKey;
+use crate::syntax::instantiate::{ImplKey, NamedImplKey};
 use crate::syntax::map::{OrderedMap, UnorderedMap};
 use crate::syntax::report::Errors;
 use crate::syntax::resolve::Resolution;
@@ -7,7 +7,8 @@ use crate::syntax::set::{OrderedSet, UnorderedSet};
 use crate::syntax::trivial::{self, TrivialReason};
 use crate::syntax::visit::{self, Visit};
 use crate::syntax::{
-    toposort, Api, Atom, Enum, EnumRepr, ExternType, Impl, Lifetimes, Pair, Struct, Type, TypeAlias,
+    toposort, Api, Atom, Const, Enum, EnumRepr, ExternType, Impl, Lifetimes, Pair, Struct, Trait,
+    Type, TypeAlias,
 };
 use proc_macro2::Ident;
 use quote::ToTokens;
@@ -22,6 +23,8 @@ pub struct Types<'a> {
     pub untrusted: UnorderedMap<&'a Ident, &'a ExternType>,
     pub required_trivial: UnorderedMap<&'a Ident, Vec<TrivialReason<'a>>>,
     pub impls: OrderedMap<ImplKey<'a>, Option<&'a Impl>>,
+    pub unique_ptr_deleters: UnorderedMap<&'a Ident, &'a Type>,
+    pub consts: UnorderedMap<&'a Ident, &'a Const>,
     pub resolutions: UnorderedMap<&'a Ident, Resolution<'a>>,
     pub struct_improper_ctypes: UnorderedSet<&'a Ident>,
     pub toposorted_structs: Vec<&'a Struct>,
@@ -37,6 +40,8 @@ impl<'a> Types<'a> {
         let mut aliases = UnorderedMap::new();
         let mut untrusted = UnorderedMap::new();
         let mut impls = OrderedMap::new();
+        let mut unique_ptr_deleters = UnorderedMap::new();
+        let mut consts = UnorderedMap::new();
         let mut resolutions = UnorderedMap::new();
         let struct_improper_ctypes = UnorderedSet::new();
         let toposorted_structs = Vec::new();
@@ -141,8 +146,15 @@ impl<'a> Types<'a> {
                 }
                 Api::CxxFunction(efn) | Api::RustFunction(efn) => {
                     // Note: duplication of the C++ name is fine because C++ has
-                    // function overloading.
-                    if !function_names.insert((&efn.receiver, &efn.name.rust)) {
+                    // function overloading. Methods and associated functions of
+                    // the same type share a namespace, since both are mangled
+                    // as {TYPE}${NAME}.
+                    let self_type = match (&efn.receiver, &efn.self_type) {
+                        (Some(receiver), _) => Some(&receiver.ty.rust),
+                        (None, Some(self_type)) => Some(&self_type.rust),
+                        (None, None) => None,
+                    };
+                    if !function_names.insert((self_type, &efn.name.rust)) {
                         duplicate_name(cx, efn, &efn.name.rust);
                     }
                     for arg in &efn.args {
@@ -151,6 +163,9 @@ impl<'a> Types<'a> {
                     if let Some(ret) = &efn.ret {
                         visit(&mut all, ret);
                     }
+                    if let Some(error) = &efn.error {
+                        visit(&mut all, error);
+                    }
                 }
                 Api::TypeAlias(alias) => {
                     let ident = &alias.name.rust;
@@ -166,6 +181,18 @@ impl<'a> Types<'a> {
                     if let Some(key) = imp.ty.impl_key() {
                         impls.insert(key, Some(imp));
                     }
+                    if let (Type::UniquePtr(ptr), Some(deleter)) = (&imp.ty, &imp.deleter) {
+                        if let Type::Ident(inner) = &ptr.inner {
+                            unique_ptr_deleters.insert(&inner.rust, deleter);
+                        }
+                    }
+                }
+                Api::Const(konst) | Api::CxxConst(konst) => {
+                    let ident = &konst.name.rust;
+                    if consts.insert(ident, konst).is_some() {
+                        duplicate_name(cx, konst, ident);
+                    }
+                    visit(&mut all, &konst.ty);
                 }
             }
         }
@@ -177,6 +204,7 @@ impl<'a> Types<'a> {
             };
             let implicit_impl = match impl_key {
                 ImplKey::RustBox(ident)
+                | ImplKey::RustArc(ident)
                 | ImplKey::RustVec(ident)
                 | ImplKey::UniquePtr(ident)
                 | ImplKey::SharedPtr(ident)
@@ -190,6 +218,41 @@ impl<'a> Types<'a> {
             }
         }
 
+        // derive(Add) on an opaque C++ type returns the sum in a UniquePtr.
+        for api in apis {
+            if let Api::CxxType(ety) = api {
+                if let Some(derive) = ety.derives.iter().find(|derive| derive.what == Trait::Add) {
+                    let impl_key = ImplKey::UniquePtr(NamedImplKey {
+                        begin_span: derive.span,
+                        rust: &ety.name.rust,
+                        lt_token: ety.generics.lt_token,
+                        gt_token: ety.generics.gt_token,
+                        end_span: derive.span,
+                    });
+                    if !impls.contains_key(&impl_key) {
+                        impls.insert(impl_key, None);
+                    }
+                }
+            }
+        }
+
+        // A UniquePtr of a Rust type owns a Box allocation, which C++ releases
+        // through rust::BoxDeleter, so that Box needs to be instantiated too.
+        let box_targets: Vec<_> = impls
+            .keys()
+            .filter_map(|impl_key| match impl_key {
+                ImplKey::UniquePtr(ident) if rust.contains(ident.rust) => {
+                    Some(ImplKey::RustBox(*ident))
+                }
+                _ => None,
+            })
+            .collect();
+        for impl_key in box_targets {
+            if !impls.contains_key(&impl_key) {
+                impls.insert(impl_key, None);
+            }
+        }
+
         // All these APIs may contain types passed by value. We need to ensure
         // we check that this is permissible. We do this _after_ scanning all
         // the APIs above, in case some function or struct references a type
@@ -207,6 +270,8 @@ impl<'a> Types<'a> {
             untrusted,
             required_trivial,
             impls,
+            unique_ptr_deleters,
+            consts,
             resolutions,
             struct_improper_ctypes,
             toposorted_structs,
@@ -243,7 +308,7 @@ impl<'a> Types<'a> {
 
     pub fn needs_indirect_abi(&self, ty: &Type) -> bool {
         match ty {
-            Type::RustBox(_) | Type::UniquePtr(_) => false,
+            Type::RustBox(_) | Type::RustArc(_) | Type::UniquePtr(_) => false,
             Type::Array(_) => true,
             _ => !self.is_guaranteed_pod(ty),
         }
@@ -1,4 +1,4 @@
-use crate::syntax::Type;
+use crate::syntax::{Signature, Type};
 
 pub trait Visit<'a> {
     fn visit_type(&mut self, ty: &'a Type) {
@@ -13,6 +13,7 @@ where
     match ty {
         Type::Ident(_) | Type::Str(_) | Type::Void(_) => {}
         Type::RustBox(ty)
+        | Type::RustArc(ty)
         | Type::UniquePtr(ty)
         | Type::SharedPtr(ty)
         | Type::WeakPtr(ty)
@@ -22,13 +23,27 @@ where
         Type::Ptr(p) => visitor.visit_type(&p.inner),
         Type::Array(a) => visitor.visit_type(&a.inner),
         Type::SliceRef(s) => visitor.visit_type(&s.inner),
-        Type::Fn(fun) => {
-            if let Some(ret) = &fun.ret {
-                visitor.visit_type(ret);
-            }
-            for arg in &fun.args {
-                visitor.visit_type(&arg.ty);
+        Type::Tuple(t) => {
+            for elem in &t.elems {
+                visitor.visit_type(elem);
             }
         }
+        Type::Fn(fun) => visit_signature(visitor, fun),
+        Type::DynFn(fun) => visit_signature(visitor, &fun.sig),
+    }
+}
+
+fn visit_signature<'a, V>(visitor: &mut V, fun: &'a Signature)
+where
+    V: Visit<'a> + ?Sized,
+{
+    if let Some(ret) = &fun.ret {
+        visitor.visit_type(ret);
+    }
+    if let Some(error) = &fun.error {
+        visitor.visit_type(error);
+    }
+    for arg in &fun.args {
+        visitor.visit_type(&arg.ty);
     }
 }
@@ -1,6 +1,6 @@
 #![allow(clippy::field_reassign_with_default)]
 
-use cxx_gen::{generate_header_and_cc, Opt};
+use cxx_gen::{generate_header_and_cc, CxxStandard, Include, IncludeKind, Module, Opt};
 use std::str;
 
 const BRIDGE0: &str = r#"
@@ -20,7 +20,7 @@ fn test_extern_c_function() {
     let output = str::from_utf8(&generated.implementation).unwrap();
     // To avoid continual breakage we won't test every byte.
     // Let's look for the major features.
-    assert!(output.contains("void cxxbridge1$do_cpp_thing(::rust::Str foo)"));
+    assert!(output.contains("void $cxxbridge1$do_cpp_thing(::rust::Str foo)"));
 }
 
 #[test]
@@ -30,5 +30,336 @@ fn test_impl_annotation() {
     let source = BRIDGE0.parse().unwrap();
     let generated = generate_header_and_cc(source, &opt).unwrap();
     let output = str::from_utf8(&generated.implementation).unwrap();
-    assert!(output.contains("ANNOTATION void cxxbridge1$do_cpp_thing(::rust::Str foo)"));
+    assert!(output.contains("ANNOTATION void $cxxbridge1$do_cpp_thing(::rust::Str foo)"));
+}
+
+const BRIDGE1: &str = r#"
+    #[cxx::bridge]
+    mod ffi {
+        extern "Rust" {
+            fn rust_parse(s: &str) -> Result<usize>;
+        }
+        unsafe extern "C++" {
+            fn cpp_parse(s: &str) -> Result<usize>;
+        }
+    }
+"#;
+
+#[test]
+fn test_no_exceptions() {
+    let mut opt = Opt::default();
+    opt.exceptions = false;
+    let source = BRIDGE1.parse().unwrap();
+    let generated = generate_header_and_cc(source, &opt).unwrap();
+    let header = str::from_utf8(&generated.header).unwrap();
+    let output = str::from_utf8(&generated.implementation).unwrap();
+    assert!(header.contains("::rust::Expected<::std::size_t> rust_parse(::rust::Str s) noexcept;"));
+    assert!(output
+        .contains("::rust::Expected<::std::size_t> (*cpp_parse$)(::rust::Str) = ::cpp_parse;"));
+    assert!(!output.contains("trycatch"));
+    assert!(!output.contains("throw ::rust"));
+}
+
+#[test]
+fn test_no_exceptions_catch_unwind() {
+    let mut opt = Opt::default();
+    opt.exceptions = false;
+    let source = r#"
+        #[cxx::bridge]
+        mod ffi {
+            extern "Rust" {
+                #[catch_unwind]
+                fn rust_parse(s: &str) -> Result<usize>;
+            }
+        }
+    "#;
+    let error = match generate_header_and_cc(source.parse().unwrap(), &opt) {
+        Ok(_) => panic!("expected #[catch_unwind] to be rejected"),
+        Err(error) => error,
+    };
+    assert_eq!(error.to_string(), "#[catch_unwind] requires C++ exceptions");
+}
+
+const BRIDGE2: &str = r#"
+    #[cxx::bridge]
+    mod ffi {
+        struct Shared {
+            always: i32,
+            #[cfg(feature = "extra")]
+            extra: Box<Opaque>,
+        }
+        enum Kind {
+            Plain,
+            #[cfg(all(unix, not(target_os = "macos")))]
+            Special,
+        }
+        extern "Rust" {
+            type Opaque;
+            #[cfg(any(feature = "a", feature = "b"))]
+            fn either();
+        }
+    }
+"#;
+
+#[test]
+fn test_preserve_cfg() {
+    let mut opt = Opt::default();
+    opt.preserve_cfg = true;
+    let source = BRIDGE2.parse().unwrap();
+    let generated = generate_header_and_cc(source, &opt).unwrap();
+    let header = str::from_utf8(&generated.header).unwrap();
+    let output = str::from_utf8(&generated.implementation).unwrap();
+    assert!(header.contains(concat!(
+        "#if defined(CXXBRIDGE_CFG_feature_eq_extra)\n",
+        "  ::rust::Box<::Opaque> extra;\n",
+        "#endif // defined(CXXBRIDGE_CFG_feature_eq_extra)\n",
+    )));
+    assert!(header.contains(concat!(
+        "#if (defined(CXXBRIDGE_CFG_unix) && !defined(CXXBRIDGE_CFG_target__os_eq_macos))\n",
+        "  Special = 1,\n",
+    )));
+    assert!(header.contains(concat!(
+        "#if (defined(CXXBRIDGE_CFG_feature_eq_a) || defined(CXXBRIDGE_CFG_feature_eq_b))\n",
+        "void either() noexcept;\n",
+    )));
+    assert!(output.contains(concat!(
+        "#if defined(CXXBRIDGE_CFG_feature_eq_extra)\n",
+        "::Opaque *cxxbridge1$box$Opaque$alloc() noexcept;\n",
+    )));
+}
+
+#[test]
+fn test_preserve_cfg_distinct_macros() {
+    let mut opt = Opt::default();
+    opt.preserve_cfg = true;
+    let source = r#"
+        #[cxx::bridge]
+        mod ffi {
+            extern "Rust" {
+                #[cfg(feature = "a-b")]
+                fn dash();
+                #[cfg(feature = "a_b")]
+                fn underscore();
+                #[cfg(feature_a_b)]
+                fn ident();
+            }
+        }
+    "#;
+    let generated = generate_header_and_cc(source.parse().unwrap(), &opt).unwrap();
+    let header = str::from_utf8(&generated.header).unwrap();
+    assert!(header.contains(concat!(
+        "#if defined(CXXBRIDGE_CFG_feature_eq_a_x2db)\n",
+        "void dash() noexcept;\n",
+    )));
+    assert!(header.contains(concat!(
+        "#if defined(CXXBRIDGE_CFG_feature_eq_a__b)\n",
+        "void underscore() noexcept;\n",
+    )));
+    assert!(header.contains(concat!(
+        "#if defined(CXXBRIDGE_CFG_feature__a__b)\n",
+        "void ident() noexcept;\n",
+    )));
+}
+
+const BRIDGE3: &str = r#"
+    #[cxx::bridge]
+    mod ffi {
+        const LIMIT: u32 = 64;
+    }
+"#;
+
+#[test]
+fn test_cxx_standard() {
+    let mut opt = Opt::default();
+    let source = BRIDGE3.parse().unwrap();
+    let generated = generate_header_and_cc(source, &opt).unwrap();
+    let header = str::from_utf8(&generated.header).unwrap();
+    assert!(header.contains("\nconstexpr ::std::uint32_t LIMIT = 64u;\n"));
+
+    opt.cxx_standard = CxxStandard::Cxx17;
+    let source = BRIDGE3.parse().unwrap();
+    let generated = generate_header_and_cc(source, &opt).unwrap();
+    let header = str::from_utf8(&generated.header).unwrap();
+    assert!(header.contains("\ninline constexpr ::std::uint32_t LIMIT = 64u;\n"));
+}
+
+#[test]
+fn test_const_signed_min() {
+    let opt = Opt::default();
+    let source = r#"
+        #[cxx::bridge]
+        mod ffi {
+            const MIN8: i8 = -128;
+            const MIN32: i32 = -2147483648;
+            const MIN64: i64 = -9223372036854775808;
+            const NEAR64: i64 = -9223372036854775807;
+        }
+    "#;
+    let generated = generate_header_and_cc(source.parse().unwrap(), &opt).unwrap();
+    let header = str::from_utf8(&generated.header).unwrap();
+    assert!(header.contains("\nconstexpr ::std::int8_t MIN8 = (-127 - 1);\n"));
+    assert!(header.contains("\nconstexpr ::std::int32_t MIN32 = (-2147483647 - 1);\n"));
+    assert!(header.contains("\nconstexpr ::std::int64_t MIN64 = (-9223372036854775807 - 1);\n"));
+    assert!(header.contains("\nconstexpr ::std::int64_t NEAR64 = -9223372036854775807;\n"));
+}
+
+#[test]
+fn test_fwd_header() {
+    let opt = Opt::default();
+    let source = r#"
+        #[cxx::bridge(namespace = "app")]
+        mod ffi {
+            struct Shared {
+                v: Vec<u8>,
+            }
+            #[repr(i32)]
+            enum Kind {
+                Plain,
+            }
+            extern "Rust" {
+                type Opaque;
+            }
+            unsafe extern "C++" {
+                type Handle;
+            }
+        }
+    "#;
+    let generated = generate_header_and_cc(source.parse().unwrap(), &opt).unwrap();
+    let fwd_header = str::from_utf8(&generated.fwd_header).unwrap();
+    assert!(fwd_header.contains(concat!(
+        "namespace app {\n",
+        "  struct Shared;\n",
+        "  enum class Kind : ::std::int32_t;\n",
+        "  struct Opaque;\n",
+        "}\n",
+    )));
+    assert!(!fwd_header.contains("Handle"));
+    assert!(!fwd_header.contains("namespace rust"));
+}
+
+#[test]
+fn test_module_interface() {
+    let mut opt = Opt::default();
+    opt.module = Some(Module {
+        name: "app.bridge".to_owned(),
+        header: Include {
+            path: "app/lib.rs.h".to_owned(),
+            kind: IncludeKind::Quoted,
+        },
+    });
+    let source = r#"
+        #[cxx::bridge(namespace = "app")]
+        mod ffi {
+            struct Shared {
+                v: u8,
+            }
+            extern "Rust" {
+                type Opaque;
+                fn make() -> Shared;
+                fn get(self: &Opaque) -> u8;
+            }
+            unsafe extern "C++" {
+                type Handle;
+                fn handle() -> UniquePtr<Handle>;
+            }
+        }
+    "#;
+    let generated = generate_header_and_cc(source.parse().unwrap(), &opt).unwrap();
+    let module_interface = str::from_utf8(&generated.module_interface).unwrap();
+    assert_eq!(
+        module_interface,
+        concat!(
+            "module;\n",
+            "#include \"app/lib.rs.h\"\n",
+            "\n",
+            "export module app.bridge;\n",
+            "\n",
+            "export import cxx;\n",
+            "\n",
+            "export namespace app {\n",
+            "  using ::app::Shared;\n",
+            "  using ::app::Opaque;\n",
+            "  using ::app::make;\n",
+            "}\n",
+        ),
+    );
+
+    opt.module = None;
+    let generated = generate_header_and_cc(source.parse().unwrap(), &opt).unwrap();
+    assert!(generated.module_interface.is_empty());
+}
+
+#[test]
+fn test_c_header() {
+    let mut opt = Opt::default();
+    opt.c_header = true;
+    let source = r#"
+        #[cxx::bridge(namespace = "app")]
+        mod ffi {
+            struct Point {
+                x: i32,
+                y: i32,
+            }
+            extern "Rust" {
+                type Counter;
+                fn new_counter() -> Box<Counter>;
+                fn bump(self: &mut Counter) -> u32;
+                fn length(s: &str) -> usize;
+            }
+        }
+    "#;
+    let generated = generate_header_and_cc(source.parse().unwrap(), &opt).unwrap();
+    let c_header = str::from_utf8(&generated.c_header).unwrap();
+    assert!(c_header.contains("struct app_Point {\n  int32_t x;\n  int32_t y;\n};\n"));
+    assert!(c_header.contains("typedef struct app_Counter app_Counter;\n"));
+    assert!(c_header.contains("static inline app_Counter *app_new_counter(void) {\n"));
+    assert!(c_header.contains("static inline uint32_t app_Counter_bump(app_Counter *self) {\n"));
+    assert!(c_header.contains("static inline size_t app_length(char const *s, size_t s_len) {\n"));
+    assert!(c_header.contains("static inline void app_Counter_free(app_Counter *ptr) {\n"));
+
+    let source = r#"
+        #[cxx::bridge]
+        mod ffi {
+            extern "Rust" {
+                fn greet(name: String);
+            }
+        }
+    "#;
+    assert!(generate_header_and_cc(source.parse().unwrap(), &opt).is_err());
+
+    opt.c_header = false;
+    let generated = generate_header_and_cc(source.parse().unwrap(), &opt).unwrap();
+    assert!(generated.c_header.is_empty());
+}
+
+#[test]
+fn test_global_namespace_symbols() {
+    let opt = Opt::default();
+    let source = r#"
+        #[cxx::bridge]
+        mod ffi {
+            unsafe extern "C++" {
+                type string;
+                fn exception();
+                fn len(self: &string) -> usize;
+            }
+        }
+    "#;
+    let generated = generate_header_and_cc(source.parse().unwrap(), &opt).unwrap();
+    let output = str::from_utf8(&generated.implementation).unwrap();
+    assert!(output.contains("void $cxxbridge1$exception() noexcept {"));
+    assert!(
+        output.contains("::std::size_t $cxxbridge1$string$len(::string const &self) noexcept {")
+    );
+    assert!(!output.contains(" cxxbridge1$exception("));
+    assert!(!output.contains(" cxxbridge1$string$len("));
+}
+
+#[test]
+fn test_header_independent_of_cxx_standard() {
+    // CFG.cxx_standard is per crate while cxx's own C++ is compiled once for
+    // the whole build, which is only sound as long as rust/cxx.h declares the
+    // same things under every standard.
+    assert!(!cxx_gen::HEADER.contains("__cplusplus"));
+    assert!(!cxx_gen::HEADER.contains("_MSVC_LANG"));
 }
@@ -0,0 +1,52 @@
+#![cfg(unix)]
+
+#[cxx::bridge(namespace = "tests::dlopen")]
+mod ffi {
+    #[dlopen = "Plugin"]
+    unsafe extern "C++" {
+        fn plugin_version() -> u32;
+    }
+}
+
+#[test]
+fn test_load_missing_library() {
+    let err = ffi::Plugin::load("/nonexistent/libplugin.so")
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("/nonexistent/libplugin.so"));
+    assert!(!ffi::Plugin::is_loaded());
+}
+
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn test_load_missing_symbol() {
+    let err = ffi::Plugin::load("libc.so.6").err().unwrap();
+    assert!(err
+        .to_string()
+        .contains("tests$dlopen$cxxbridge1$plugin_version"));
+    assert!(!ffi::Plugin::is_loaded());
+}
+
+#[test]
+#[should_panic = "Plugin is not loaded"]
+fn test_call_before_load() {
+    ffi::plugin_version();
+}
+
+#[test]
+fn test_load_plugin() {
+    use cxx_test_suite::dlopen::{ffi as plugin, PLUGIN};
+
+    assert!(!plugin::Plugin::is_loaded());
+    plugin::Plugin::load(PLUGIN).unwrap();
+    assert!(plugin::Plugin::is_loaded());
+
+    assert_eq!(2020, plugin::plugin_version());
+    assert_eq!(5, plugin::plugin_add(2, 3));
+    // Second calls go through the cached symbols.
+    assert_eq!(2020, plugin::plugin_version());
+    assert_eq!(-1, plugin::plugin_add(2, -3));
+
+    // Loading again is a no-op.
+    plugin::Plugin::load(PLUGIN).unwrap();
+}
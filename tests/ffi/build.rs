@@ -1,4 +1,7 @@
 use cxx_build::CFG;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 fn main() {
     if cfg!(trybuild) {
@@ -9,6 +12,10 @@ fn main() {
     let sources = vec!["lib.rs", "module.rs"];
     let mut build = cxx_build::bridges(sources);
     build.file("tests.cc");
+    build.file("fwd.cc");
+    build.file("prefixes.cc");
+    build.include(other_prefix_header());
+    build.define("CXXTEST_PREFIX", cxxbridge_flags::SYMBOL_PREFIX);
     build.flag_if_supported(cxxbridge_flags::STD);
     build.warnings_into_errors(cfg!(deny_warnings));
     if cfg!(not(target_env = "msvc")) {
@@ -16,6 +23,68 @@ fn main() {
     }
     build.compile("cxx-test-suite");
 
+    if env::var_os("CARGO_CFG_UNIX").is_some() {
+        let plugin = dlopen_plugin();
+        println!("cargo:rustc-env=CXX_TEST_PLUGIN={}", plugin.display());
+        println!("cargo:rustc-cfg=dlopen_plugin");
+    }
+
+    CFG.exceptions = false;
+    let mut build = cxx_build::bridge("no_exceptions.rs");
+    build.file("no_exceptions.cc");
+    build.flag_if_supported(cxxbridge_flags::STD);
+    build.flag_if_supported("-fno-exceptions");
+    build.warnings_into_errors(cfg!(deny_warnings));
+    build.compile("cxx-test-suite-no-exceptions");
+
     println!("cargo:rerun-if-changed=tests.cc");
     println!("cargo:rerun-if-changed=tests.h");
+    println!("cargo:rerun-if-changed=fwd.cc");
+    println!("cargo:rerun-if-changed=prefixes.cc");
+    println!("cargo:rerun-if-changed=dlopen.cc");
+    println!("cargo:rerun-if-changed=dlopen.h");
+    println!("cargo:rerun-if-changed=no_exceptions.cc");
+    println!("cargo:rerun-if-changed=no_exceptions.h");
+}
+
+// A copy of rust/cxx.h as it would be built with CXXBRIDGE_SYMBOL_PREFIX set to
+// cxxtest_other, for prefixes.cc to include next to the real one.
+fn other_prefix_header() -> PathBuf {
+    let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let original = manifest_dir.join("../../include/cxx.h");
+    println!("cargo:rerun-if-changed={}", original.display());
+    let renamed = fs::read_to_string(original)
+        .unwrap()
+        .replace("cxxbridge1", "cxxtest_other")
+        .replace("CXXBRIDGE1", "CXXTEST_OTHER");
+
+    let include_dir = Path::new(&env::var_os("OUT_DIR").unwrap()).join("prefixes");
+    let path = include_dir.join("cxxtest_other").join("rust").join("cxx.h");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, renamed).unwrap();
+    include_dir
+}
+
+// A shared library holding the C++ side of dlopen.rs, for the test suite to
+// load at runtime rather than link against.
+fn dlopen_plugin() -> PathBuf {
+    let mut build = cxx_build::bridge("dlopen.rs");
+    build.flag_if_supported(cxxbridge_flags::STD);
+    build.pic(true);
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    let generated = out_dir.join("cxxbridge/sources/tests/ffi/dlopen.rs.cc");
+    let plugin = out_dir.join("libcxx-test-plugin.so");
+    let status = build
+        .get_compiler()
+        .to_command()
+        .arg("-shared")
+        .arg(generated)
+        .arg("dlopen.cc")
+        .arg("-o")
+        .arg(&plugin)
+        .status()
+        .unwrap();
+    assert!(status.success(), "failed to build {}", plugin.display());
+    plugin
 }
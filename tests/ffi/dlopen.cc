@@ -0,0 +1,13 @@
+#include "tests/ffi/dlopen.h"
+
+namespace tests {
+namespace dlopen {
+
+std::uint32_t plugin_version() noexcept { return 2020; }
+
+std::int32_t plugin_add(std::int32_t a, std::int32_t b) noexcept {
+  return a + b;
+}
+
+} // namespace dlopen
+} // namespace tests
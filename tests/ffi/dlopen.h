@@ -0,0 +1,11 @@
+#pragma once
+#include <cstdint>
+
+namespace tests {
+namespace dlopen {
+
+std::uint32_t plugin_version() noexcept;
+std::int32_t plugin_add(std::int32_t a, std::int32_t b) noexcept;
+
+} // namespace dlopen
+} // namespace tests
@@ -0,0 +1,17 @@
+// The C++ side of this bridge is not linked into the test suite. Instead
+// build.rs compiles it into a shared library of its own, which the tests load
+// at runtime from the path in PLUGIN. The functions only pass primitives, since
+// the test executables do not export the cxx runtime to the library.
+
+#[cxx::bridge(namespace = "tests::dlopen")]
+pub mod ffi {
+    #[dlopen = "Plugin"]
+    unsafe extern "C++" {
+        include!("tests/ffi/dlopen.h");
+
+        fn plugin_version() -> u32;
+        fn plugin_add(a: i32, b: i32) -> i32;
+    }
+}
+
+pub const PLUGIN: &str = env!("CXX_TEST_PLUGIN");
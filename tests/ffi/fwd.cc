@@ -0,0 +1,21 @@
+// Checks that lib.rs.fwd.h stands on its own and agrees with lib.rs.h, which
+// rejects any forward declaration of an enum with the wrong underlying type.
+// Nothing here is called at runtime; compiling this file is the test.
+#include "tests/ffi/lib.rs.fwd.h"
+#include <cstddef>
+
+namespace tests {
+// With only the forward declarations in scope, shared structs are usable
+// through pointers and references, and shared enums are complete types.
+size_t c_fwd_count(const Shared *shared, const A::B::ABShared &ab, Enum e,
+                   A::AEnum ae);
+} // namespace tests
+
+#include "tests/ffi/lib.rs.h"
+
+namespace tests {
+size_t c_fwd_count(const Shared *shared, const A::B::ABShared &ab, Enum e,
+                   A::AEnum ae) {
+  return shared->z + ab.z + static_cast<size_t>(e) + static_cast<size_t>(ae);
+}
+} // namespace tests
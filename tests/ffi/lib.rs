@@ -8,17 +8,22 @@
     clippy::needless_pass_by_value,
     clippy::ptr_arg,
     clippy::trivially_copy_pass_by_ref,
+    clippy::type_complexity,
     clippy::unnecessary_wraps,
     clippy::unused_self
 )]
 
 pub mod cast;
+#[cfg(dlopen_plugin)]
+pub mod dlopen;
 pub mod module;
+pub mod no_exceptions;
 
 use cxx::{type_id, CxxString, CxxVector, ExternType, SharedPtr, UniquePtr};
 use std::fmt::{self, Display};
 use std::mem::MaybeUninit;
 use std::os::raw::c_char;
+use std::sync::Arc;
 
 #[cxx::bridge(namespace = "tests")]
 pub mod ffi {
@@ -79,8 +84,21 @@ pub mod ffi {
         e: COwnedEnum,
     }
 
+    /// Number of elements in `Array::a`.
+    const ARRAY_LEN: usize = 4;
+    const SHARED_I64: i64 = -2020;
+    const SHARED_F32: f32 = 0.5;
+    #[namespace = "A"]
+    #[cxx_name = "SHARED_FLAG"]
+    const A_SHARED_FLAG: bool = true;
+    #[from_header]
+    const C_CONSTANT: u32 = 1024;
+    #[from_header]
+    #[cxx_name = "C_SCALE"]
+    const C_CONSTANT_SCALE: f64 = 1.5;
+
     pub struct Array {
-        a: [i32; 4],
+        a: [i32; ARRAY_LEN],
         b: Buffer,
     }
 
@@ -89,6 +107,12 @@ pub mod ffi {
         s: &'a str,
     }
 
+    #[derive(Clone, Debug, PartialEq)]
+    struct TupleFields {
+        pair: (u32, String),
+        triple: (u8, bool, Shared),
+    }
+
     unsafe extern "C++" {
         include!("tests/ffi/tests.h");
 
@@ -98,6 +122,7 @@ pub mod ffi {
         fn c_return_shared() -> Shared;
         fn c_return_box() -> Box<R>;
         fn c_return_unique_ptr() -> UniquePtr<C>;
+        fn c_return_unique_ptr_r() -> UniquePtr<R>;
         fn c_return_shared_ptr() -> SharedPtr<C>;
         fn c_return_ref(shared: &Shared) -> &usize;
         fn c_return_mut(shared: &mut Shared) -> &mut usize;
@@ -128,10 +153,14 @@ pub mod ffi {
         fn c_return_nested_ns_enum(n: u16) -> ABEnum;
         fn c_return_const_ptr(n: usize) -> *const C;
         fn c_return_mut_ptr(n: usize) -> *mut C;
+        fn c_return_tuple() -> (usize, String);
 
         fn c_take_primitive(n: usize);
         fn c_take_shared(shared: Shared);
         fn c_take_box(r: Box<R>);
+        fn c_take_arc(r: Arc<R>);
+        fn c_take_unique_ptr_r(r: UniquePtr<R>);
+        fn c_clone_arc(arc: Arc<R>) -> Arc<R>;
         fn c_take_ref_r(r: &R);
         fn c_take_ref_c(c: &C);
         fn c_take_str(s: &str);
@@ -167,6 +196,11 @@ pub mod ffi {
         #[cxx_name = "c_take_callback_ref"]
         fn c_take_callback_ref_lifetime<'a>(callback: fn(&'a String));
         fn c_take_callback_mut(callback: fn(&mut String));
+        fn c_take_closure(closure: Box<dyn FnMut(usize) -> usize + Send>) -> usize;
+        fn c_take_local_closure(closure: Box<dyn FnMut(usize) -> usize>) -> usize;
+        fn c_take_fallible_closure(
+            closure: Box<dyn FnMut(String) -> Result<usize> + Send>,
+        ) -> usize;
         fn c_take_enum(e: Enum);
         fn c_take_ns_enum(e: AEnum);
         fn c_take_nested_ns_enum(e: ABEnum);
@@ -174,6 +208,8 @@ pub mod ffi {
         fn c_take_nested_ns_shared(shared: ABShared);
         fn c_take_rust_vec_ns_shared(v: Vec<AShared>);
         fn c_take_rust_vec_nested_ns_shared(v: Vec<ABShared>);
+        fn c_take_tuple(tuple: (usize, &str, Box<R>));
+        fn c_roundtrip_tuple_fields(fields: TupleFields) -> TupleFields;
         unsafe fn c_take_const_ptr(c: *const C) -> usize;
         unsafe fn c_take_mut_ptr(c: *mut C) -> usize;
 
@@ -190,6 +226,10 @@ pub mod ffi {
         fn c_try_return_rust_vec() -> Result<Vec<u8>>;
         fn c_try_return_rust_vec_string() -> Result<Vec<String>>;
         fn c_try_return_ref_rust_vec(c: &C) -> Result<&Vec<u8>>;
+        fn c_try_return_tuple() -> Result<(u8, Shared)>;
+        fn c_try_return_shared_error(fail: bool) -> Result<usize, SharedString>;
+        fn c_try_return_enum_error(fail: bool) -> Result<String, Enum>;
+        fn c_try_return_void_enum_error(fail: bool) -> Result<(), Enum>;
 
         fn get(self: &C) -> usize;
         fn set(self: Pin<&mut C>, n: usize) -> usize;
@@ -198,6 +238,10 @@ pub mod ffi {
         fn getMut(self: Pin<&mut C>) -> &mut usize;
         fn set_succeed(self: Pin<&mut C>, n: usize) -> Result<usize>;
         fn get_fail(self: Pin<&mut C>) -> Result<usize>;
+        #[Self = "C"]
+        fn create(n: usize) -> UniquePtr<C>;
+        #[cxx_name = "operator[]"]
+        fn at(self: &C, i: usize) -> &u8;
         fn c_method_on_shared(self: &Shared) -> usize;
         fn c_method_ref_on_shared(self: &Shared) -> &usize;
         fn c_method_mut_on_shared(self: &mut Shared) -> &mut usize;
@@ -242,6 +286,44 @@ pub mod ffi {
         fn nonconst_member(self: Pin<&mut Borrow>);
     }
 
+    unsafe extern "C++" {
+        #[derive(Add, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+        type K;
+
+        fn c_return_k(k: i32) -> UniquePtr<K>;
+        fn c_return_shared_k(k: i32) -> SharedPtr<K>;
+    }
+
+    unsafe extern "C++" {
+        type Shape;
+        #[base(Shape)]
+        #[derive(Deref)]
+        type Circle;
+        type Named;
+        #[base(Named, Shape)]
+        type Square;
+
+        fn sides(self: &Shape) -> usize;
+        fn name(self: &Named) -> &CxxString;
+        fn rename(self: Pin<&mut Named>, name: &str);
+        fn c_return_circle() -> UniquePtr<Circle>;
+        fn c_return_square() -> SharedPtr<Square>;
+        fn c_return_unique_square() -> UniquePtr<Square>;
+        fn c_take_shape(shape: &Shape) -> usize;
+        fn c_take_unique_ptr_shape(shape: UniquePtr<Shape>) -> usize;
+        fn c_take_shared_ptr_shape(shape: SharedPtr<Shape>) -> usize;
+    }
+
+    unsafe extern "C++" {
+        type Resource;
+        type ResourceDeleter;
+
+        fn get(self: &Resource) -> usize;
+        fn c_return_resource(n: usize) -> UniquePtr<Resource>;
+        fn c_take_resource(resource: UniquePtr<Resource>) -> usize;
+        fn c_resource_deleted_count() -> usize;
+    }
+
     #[repr(u32)]
     #[derive(Hash)]
     enum COwnedEnum {
@@ -261,7 +343,9 @@ pub mod ffi {
         fn r_return_primitive() -> usize;
         fn r_return_shared() -> Shared;
         fn r_return_box() -> Box<R>;
+        fn r_return_arc() -> Arc<R>;
         fn r_return_unique_ptr() -> UniquePtr<C>;
+        fn r_return_unique_ptr_r() -> UniquePtr<R>;
         fn r_return_shared_ptr() -> SharedPtr<C>;
         fn r_return_ref(shared: &Shared) -> &usize;
         fn r_return_mut(shared: &mut Shared) -> &mut usize;
@@ -278,11 +362,14 @@ pub mod ffi {
         fn r_return_identity(_: usize) -> usize;
         fn r_return_sum(_: usize, _: usize) -> usize;
         fn r_return_enum(n: u32) -> Enum;
+        fn r_return_tuple() -> (usize, String);
 
         fn r_take_primitive(n: usize);
         fn r_take_shared(shared: Shared);
         fn r_take_box(r: Box<R>);
+        fn r_take_arc(r: Arc<R>);
         fn r_take_unique_ptr(c: UniquePtr<C>);
+        fn r_take_unique_ptr_r(r: UniquePtr<R>);
         fn r_take_shared_ptr(c: SharedPtr<C>);
         fn r_take_ref_r(r: &R);
         fn r_take_ref_c(c: &C);
@@ -297,16 +384,26 @@ pub mod ffi {
         fn r_take_ref_rust_vec(v: &Vec<u8>);
         fn r_take_ref_rust_vec_string(v: &Vec<String>);
         fn r_take_enum(e: Enum);
+        fn r_take_tuple(tuple: (usize, &str, Shared));
+        fn r_take_closure(closure: Box<dyn FnMut(usize) -> usize + Send>) -> usize;
+        fn r_take_local_closure(closure: Box<dyn FnMut(usize) -> usize>) -> usize;
+        fn r_take_fallible_closure(closure: Box<dyn FnMut(&str) -> Result<usize> + Send>)
+            -> String;
 
         fn r_try_return_void() -> Result<()>;
         fn r_try_return_primitive() -> Result<usize>;
         fn r_try_return_box() -> Result<Box<R>>;
         fn r_fail_return_primitive() -> Result<usize>;
+        #[catch_unwind]
+        fn r_panic_return_primitive(n: usize) -> Result<usize>;
         fn r_try_return_sliceu8(s: &[u8]) -> Result<&[u8]>;
         fn r_try_return_mutsliceu8(s: &mut [u8]) -> Result<&mut [u8]>;
+        fn r_try_return_tuple() -> Result<(u8, Shared)>;
 
         fn get(self: &R) -> usize;
         fn set(self: &mut R, n: usize) -> usize;
+        #[Self = "R"]
+        fn create(n: usize) -> Box<R>;
         fn r_method_on_shared(self: &Shared) -> String;
         fn r_get_array_sum(self: &Array) -> i32;
 
@@ -335,13 +432,22 @@ pub mod ffi {
         dag0: Dag0,
     }
 
+    impl C {
+        fn new(n: usize) -> Self;
+    }
+
     impl Box<Shared> {}
     impl CxxVector<SharedString> {}
+    impl SharedPtr<Circle> {}
+    impl UniquePtr<Resource, ResourceDeleter> {}
+    impl SharedPtr<Shared> {}
+    impl WeakPtr<Shared> {}
 }
 
 mod other {
     use cxx::kind::{Opaque, Trivial};
     use cxx::{type_id, CxxString, ExternType};
+    use std::marker::{PhantomData, PhantomPinned};
 
     #[repr(C)]
     pub struct D {
@@ -389,6 +495,18 @@ mod other {
         type Id = type_id!("tests::E");
         type Kind = Opaque;
     }
+
+    #[repr(C)]
+    pub struct J {
+        j: u64,
+        this: *const J,
+        _pinned: PhantomData<PhantomPinned>,
+    }
+
+    unsafe impl ExternType for J {
+        type Id = type_id!("tests::J");
+        type Kind = Opaque;
+    }
 }
 
 #[derive(PartialEq, Debug)]
@@ -403,6 +521,10 @@ impl R {
         self.0 = n;
         n
     }
+
+    fn create(n: usize) -> Box<R> {
+        Box::new(R(n))
+    }
 }
 
 pub struct Reference<'a>(&'a String);
@@ -451,6 +573,10 @@ fn r_return_box() -> Box<R> {
     Box::new(R(2020))
 }
 
+fn r_return_arc() -> Arc<R> {
+    Arc::new(R(2020))
+}
+
 fn r_return_unique_ptr() -> UniquePtr<ffi::C> {
     extern "C" {
         fn cxx_test_suite_get_unique_ptr() -> *mut ffi::C;
@@ -458,6 +584,10 @@ fn r_return_unique_ptr() -> UniquePtr<ffi::C> {
     unsafe { UniquePtr::from_raw(cxx_test_suite_get_unique_ptr()) }
 }
 
+fn r_return_unique_ptr_r() -> UniquePtr<R> {
+    UniquePtr::from(Box::new(R(2020)))
+}
+
 fn r_return_shared_ptr() -> SharedPtr<ffi::C> {
     extern "C" {
         fn cxx_test_suite_get_shared_ptr(repr: *mut SharedPtr<ffi::C>);
@@ -543,6 +673,10 @@ fn r_return_enum(n: u32) -> ffi::Enum {
     }
 }
 
+fn r_return_tuple() -> (usize, String) {
+    (2020, "2020".to_owned())
+}
+
 fn r_take_primitive(n: usize) {
     assert_eq!(n, 2020);
 }
@@ -555,10 +689,18 @@ fn r_take_box(r: Box<R>) {
     let _ = r;
 }
 
+fn r_take_arc(r: Arc<R>) {
+    let _ = r;
+}
+
 fn r_take_unique_ptr(c: UniquePtr<ffi::C>) {
     let _ = c;
 }
 
+fn r_take_unique_ptr_r(r: UniquePtr<R>) {
+    assert_eq!(r.into_box().unwrap().0, 2020);
+}
+
 fn r_take_shared_ptr(c: SharedPtr<ffi::C>) {
     let _ = c;
 }
@@ -619,6 +761,30 @@ fn r_take_enum(e: ffi::Enum) {
     let _ = e;
 }
 
+fn r_take_tuple(tuple: (usize, &str, ffi::Shared)) {
+    assert_eq!(tuple.0, 2020);
+    assert_eq!(tuple.1, "2020");
+    assert_eq!(tuple.2.z, 2020);
+}
+
+fn r_take_closure(mut closure: Box<dyn FnMut(usize) -> usize + Send>) -> usize {
+    closure(1);
+    closure(2)
+}
+
+fn r_take_local_closure(mut closure: Box<dyn FnMut(usize) -> usize>) -> usize {
+    closure(1);
+    closure(2)
+}
+
+fn r_take_fallible_closure(
+    mut closure: Box<dyn FnMut(&str) -> Result<usize, String> + Send>,
+) -> String {
+    let err = closure("fail").unwrap_err();
+    let ok = closure("2020").unwrap();
+    format!("{}:{}", err, ok)
+}
+
 fn r_try_return_void() -> Result<(), Error> {
     Ok(())
 }
@@ -635,6 +801,13 @@ fn r_fail_return_primitive() -> Result<usize, Error> {
     Err(Error)
 }
 
+fn r_panic_return_primitive(n: usize) -> Result<usize, Error> {
+    if n == 0 {
+        panic!("rust panic");
+    }
+    Ok(n)
+}
+
 fn r_try_return_sliceu8(slice: &[u8]) -> Result<&[u8], Error> {
     Ok(slice)
 }
@@ -643,6 +816,10 @@ fn r_try_return_mutsliceu8(slice: &mut [u8]) -> Result<&mut [u8], Error> {
     Ok(slice)
 }
 
+fn r_try_return_tuple() -> Result<(u8, ffi::Shared), Error> {
+    Ok((20, ffi::Shared { z: 2020 }))
+}
+
 fn r_aliased_function(x: i32) -> String {
     x.to_string()
 }
@@ -69,10 +69,21 @@ pub mod ffi2 {
 
         #[namespace = "I"]
         fn ns_c_return_unique_ptr_ns() -> UniquePtr<I>;
+
+        type J = crate::other::J;
+
+        fn get(self: &J) -> u64;
+        fn c_live_j() -> usize;
+    }
+
+    impl J {
+        fn new(j: u64) -> Self;
+        fn try_new(j: u64, fail: bool) -> Result<Self>;
     }
 
     impl UniquePtr<D> {}
     impl UniquePtr<E> {}
     impl UniquePtr<F> {}
     impl UniquePtr<G> {}
+    impl UniquePtr<J> {}
 }
@@ -0,0 +1,34 @@
+#include "tests/ffi/no_exceptions.h"
+#include "tests/ffi/no_exceptions.rs.h"
+
+#if defined(__cpp_exceptions) || defined(__EXCEPTIONS)
+#error "no_exceptions.cc is meant to be compiled with -fno-exceptions"
+#endif
+
+namespace tests {
+namespace no_exceptions {
+
+rust::Expected<std::size_t> c_try_parse(rust::Str s) noexcept {
+  std::size_t n = 0;
+  if (s.empty()) {
+    return rust::Error("empty");
+  }
+  for (char ch : s) {
+    if (ch < '0' || ch > '9') {
+      return rust::Error("not a number");
+    }
+    n = n * 10 + (ch - '0');
+  }
+  return n;
+}
+
+std::size_t c_call_r_try_parse(rust::Str s) noexcept {
+  auto result = r_try_parse(s);
+  if (!result) {
+    return 0;
+  }
+  return std::move(result).value();
+}
+
+} // namespace no_exceptions
+} // namespace tests
@@ -0,0 +1,12 @@
+#pragma once
+#include "rust/cxx.h"
+#include <cstddef>
+
+namespace tests {
+namespace no_exceptions {
+
+rust::Expected<std::size_t> c_try_parse(rust::Str s) noexcept;
+std::size_t c_call_r_try_parse(rust::Str s) noexcept;
+
+} // namespace no_exceptions
+} // namespace tests
@@ -0,0 +1,20 @@
+// Compiled by build.rs with CFG.exceptions = false and -fno-exceptions, so
+// that the C++ side of these functions never throws.
+
+#[cxx::bridge(namespace = "tests::no_exceptions")]
+pub mod ffi {
+    unsafe extern "C++" {
+        include!("tests/ffi/no_exceptions.h");
+
+        fn c_try_parse(s: &str) -> Result<usize>;
+        fn c_call_r_try_parse(s: &str) -> usize;
+    }
+
+    extern "Rust" {
+        fn r_try_parse(s: &str) -> Result<usize>;
+    }
+}
+
+fn r_try_parse(s: &str) -> Result<usize, std::num::ParseIntError> {
+    s.parse()
+}
@@ -0,0 +1,19 @@
+// Two copies of cxx built with different symbol prefixes may both have their
+// rust/cxx.h included into the same translation unit. Neither may suppress the
+// other's runtime types through its include guards.
+#include "rust/cxx.h"
+#include "cxxtest_other/rust/cxx.h"
+
+namespace tests {
+namespace prefixes {
+using String = ::rust::CXXTEST_PREFIX::String;
+using OtherString = ::rust::cxxtest_other::String;
+static_assert(sizeof(String) == sizeof(OtherString),
+              "both copies of rust::String are defined");
+
+using Slice = ::rust::CXXTEST_PREFIX::Slice<const int>;
+using OtherSlice = ::rust::cxxtest_other::Slice<const int>;
+static_assert(sizeof(Slice) == sizeof(OtherSlice),
+              "both copies of rust::Slice are defined");
+} // namespace prefixes
+} // namespace tests
@@ -36,6 +36,8 @@ size_t C::set_succeed(size_t n) { return this->set(n); }
 
 size_t C::get_fail() { throw std::runtime_error("unimplemented"); }
 
+std::unique_ptr<C> C::create(size_t n) { return std::unique_ptr<C>(new C(n)); }
+
 size_t Shared::c_method_on_shared() const noexcept { return 2021; }
 
 const size_t &Shared::c_method_ref_on_shared() const noexcept {
@@ -52,6 +54,8 @@ const std::vector<uint8_t> &C::get_v() const { return this->v; }
 
 std::vector<uint8_t> &C::get_v() { return this->v; }
 
+const uint8_t &C::operator[](size_t i) const { return this->v[i]; }
+
 size_t c_return_primitive() { return 2020; }
 
 Shared c_return_shared() { return Shared{2020}; }
@@ -76,6 +80,10 @@ std::unique_ptr<C> c_return_unique_ptr() {
   return std::unique_ptr<C>(new C{2020});
 }
 
+std::unique_ptr<R, rust::BoxDeleter> c_return_unique_ptr_r() {
+  return c_return_box().into_unique_ptr();
+}
+
 std::shared_ptr<C> c_return_shared_ptr() {
   return std::shared_ptr<C>(new C{2020});
 }
@@ -116,6 +124,10 @@ rust::String c_return_rust_string_lossy() {
   return rust::String::lossy("Hello \xf0\x90\x80World");
 }
 
+std::tuple<size_t, rust::String> c_return_tuple() {
+  return std::make_tuple(2020, rust::String("2020"));
+}
+
 std::unique_ptr<std::string> c_return_unique_ptr_string() {
   return std::unique_ptr<std::string>(new std::string("2020"));
 }
@@ -259,6 +271,23 @@ void c_take_box(rust::Box<R> r) {
   }
 }
 
+void c_take_arc(rust::Arc<R> r) {
+  if (cxx_test_suite_r_is_correct(&*r)) {
+    cxx_test_suite_set_correct();
+  }
+}
+
+void c_take_unique_ptr_r(std::unique_ptr<R, rust::BoxDeleter> r) {
+  if (cxx_test_suite_r_is_correct(r.get())) {
+    cxx_test_suite_set_correct();
+  }
+}
+
+rust::Arc<R> c_clone_arc(rust::Arc<R> arc) {
+  rust::Arc<R> copy = arc;
+  return copy;
+}
+
 void c_take_unique_ptr(std::unique_ptr<C> c) {
   if (c->get() == 2020) {
     cxx_test_suite_set_correct();
@@ -337,6 +366,27 @@ void c_take_rust_string(rust::String s) {
   }
 }
 
+void c_take_tuple(std::tuple<size_t, rust::Str, rust::Box<R>> tuple) {
+  if (std::get<0>(tuple) == 2020 && std::string(std::get<1>(tuple)) == "2020" &&
+      cxx_test_suite_r_is_correct(&*std::get<2>(tuple))) {
+    cxx_test_suite_set_correct();
+  }
+}
+
+TupleFields c_roundtrip_tuple_fields(TupleFields fields) {
+  std::pair<uint32_t, rust::String> pair = fields.pair;
+  std::tuple<uint8_t, bool, Shared> triple = fields.triple;
+  if (pair.first == 2020 && std::string(pair.second) == "2020" &&
+      std::get<0>(triple) == 20 && std::get<1>(triple) &&
+      std::get<2>(triple).z == 2020) {
+    cxx_test_suite_set_correct();
+  }
+  return TupleFields{
+      std::make_pair(pair.first + 1, rust::String("2021")),
+      {uint8_t(21), false, Shared{2021}},
+  };
+}
+
 void c_take_unique_ptr_string(std::unique_ptr<std::string> s) {
   if (*s == "2020") {
     cxx_test_suite_set_correct();
@@ -529,6 +579,28 @@ void c_take_callback_mut(rust::Fn<void(rust::String &)> callback) {
   callback(string);
 }
 
+size_t c_take_closure(rust::FnMut<size_t(size_t)> closure) {
+  closure(1);
+  std::function<size_t(size_t)> function = std::move(closure);
+  return function(2);
+}
+
+size_t c_take_local_closure(rust::FnMut<size_t(size_t)> closure) {
+  closure(1);
+  return closure(2);
+}
+
+size_t c_take_fallible_closure(rust::FnMut<size_t(rust::String)> closure) {
+  try {
+    closure("fail");
+  } catch (const rust::Error &e) {
+    if (std::strcmp(e.what(), "fail") == 0) {
+      return closure("2020");
+    }
+  }
+  return 0;
+}
+
 void c_take_enum(Enum e) {
   if (e == Enum::AVal) {
     cxx_test_suite_set_correct();
@@ -577,6 +649,10 @@ rust::Slice<uint8_t> c_try_return_mutsliceu8(rust::Slice<uint8_t> s) {
 
 rust::String c_try_return_rust_string() { return c_return_rust_string(); }
 
+std::tuple<uint8_t, Shared> c_try_return_tuple() {
+  return std::make_tuple(20, Shared{2020});
+}
+
 std::unique_ptr<std::string> c_try_return_unique_ptr_string() {
   return c_return_unique_ptr_string();
 }
@@ -594,6 +670,27 @@ const rust::Vec<uint8_t> &c_try_return_ref_rust_vec(const C &c) {
   throw std::runtime_error("unimplemented");
 }
 
+rust::Result<size_t, SharedString> c_try_return_shared_error(bool fail) {
+  if (fail) {
+    return SharedString{"2020"};
+  }
+  return size_t(2020);
+}
+
+rust::Result<rust::String, Enum> c_try_return_enum_error(bool fail) {
+  if (fail) {
+    return Enum::BVal;
+  }
+  return rust::String("2020");
+}
+
+rust::Result<void, Enum> c_try_return_void_enum_error(bool fail) {
+  if (fail) {
+    return Enum::CVal;
+  }
+  return {};
+}
+
 size_t c_get_use_count(const std::weak_ptr<C> &weak) noexcept {
   return weak.use_count();
 }
@@ -757,6 +854,99 @@ std::unique_ptr<::F::F> c_return_ns_opaque_ptr() {
   return f;
 }
 
+size_t J::live = 0;
+
+J::J(uint64_t j) : j(j), self(this) { J::live++; }
+
+J::J(uint64_t j, bool fail) : j(j), self(this) {
+  if (fail) {
+    throw std::runtime_error("J construction failed");
+  }
+  J::live++;
+}
+
+J::~J() { J::live--; }
+
+uint64_t J::get() const { return this == self ? j : 0; }
+
+size_t c_live_j() { return J::live; }
+
+bool K::operator==(const K &other) const { return this->k == other.k; }
+
+bool K::operator<(const K &other) const { return this->k < other.k; }
+
+K operator+(const K &lhs, const K &rhs) { return K{lhs.k + rhs.k}; }
+
+std::ostream &operator<<(std::ostream &os, const K &k) {
+  if (k.k < 0) {
+    throw std::runtime_error("negative K");
+  }
+  return os << "K(" << k.k << ")";
+}
+
+std::unique_ptr<K> c_return_k(int32_t k) {
+  return std::unique_ptr<K>(new K{k});
+}
+
+std::shared_ptr<K> c_return_shared_k(int32_t k) {
+  return std::shared_ptr<K>(new K{k});
+}
+
+size_t Circle::sides() const { return 0; }
+
+Named::Named(std::string name) : name_(std::move(name)) {}
+
+const std::string &Named::name() const { return this->name_; }
+
+void Named::rename(rust::Str name) { this->name_ = std::string(name); }
+
+Square::Square() : Named("square") {}
+
+size_t Square::sides() const { return 4; }
+
+std::unique_ptr<Circle> c_return_circle() {
+  return std::unique_ptr<Circle>(new Circle);
+}
+
+std::shared_ptr<Square> c_return_square() { return std::make_shared<Square>(); }
+
+std::unique_ptr<Square> c_return_unique_square() {
+  return std::unique_ptr<Square>(new Square);
+}
+
+size_t c_take_shape(const Shape &shape) { return shape.sides(); }
+
+size_t c_take_unique_ptr_shape(std::unique_ptr<Shape> shape) {
+  return shape->sides();
+}
+
+size_t c_take_shared_ptr_shape(std::shared_ptr<Shape> shape) {
+  return shape->sides();
+}
+
+static size_t resource_deleted_count = 0;
+
+Resource::Resource(size_t n) : n(n) {}
+
+size_t Resource::get() const { return this->n; }
+
+void ResourceDeleter::operator()(Resource *resource) const noexcept {
+  resource->~Resource();
+  std::free(resource);
+  resource_deleted_count++;
+}
+
+std::unique_ptr<Resource, ResourceDeleter> c_return_resource(size_t n) {
+  void *memory = std::malloc(sizeof(Resource));
+  return std::unique_ptr<Resource, ResourceDeleter>(new (memory) Resource(n));
+}
+
+size_t c_take_resource(std::unique_ptr<Resource, ResourceDeleter> resource) {
+  return resource->get();
+}
+
+size_t c_resource_deleted_count() { return resource_deleted_count; }
+
 extern "C" const char *cxx_run_test() noexcept {
 #define STRINGIFY(x) #x
 #define TOSTRING(x) STRINGIFY(x)
@@ -772,6 +962,11 @@ extern "C" const char *cxx_run_test() noexcept {
   ASSERT(rust::size_of<size_t>() == sizeof(size_t));
   ASSERT(rust::align_of<size_t>() == alignof(size_t));
 
+  ASSERT(ARRAY_LEN == std::tuple_size<decltype(Array::a)>::value);
+  ASSERT(SHARED_I64 == -2020);
+  ASSERT(SHARED_F32 == 0.5f);
+  ASSERT(A::SHARED_FLAG);
+
   ASSERT(r_return_primitive() == 2020);
   ASSERT(r_return_shared().z == 2020);
   ASSERT(cxx_test_suite_r_is_correct(&*r_return_box()));
@@ -786,6 +981,8 @@ extern "C" const char *cxx_run_test() noexcept {
   ASSERT(r_return_enum(0) == Enum::AVal);
   ASSERT(r_return_enum(1) == Enum::BVal);
   ASSERT(r_return_enum(2021) == Enum::CVal);
+  ASSERT(std::get<0>(r_return_tuple()) == 2020);
+  ASSERT(std::string(std::get<1>(r_return_tuple())) == "2020");
 
   r_take_primitive(2020);
   r_take_shared(Shared{2020});
@@ -803,14 +1000,34 @@ extern "C" const char *cxx_run_test() noexcept {
   empty_vector.reserve(10);
   r_take_ref_empty_vector(empty_vector);
   r_take_enum(Enum::AVal);
+  r_take_tuple(std::make_tuple(2020, rust::Str("2020"), Shared{2020}));
+  size_t total = 0;
+  ASSERT(r_take_closure([&](size_t n) { return total += n; }) == 3);
+  ASSERT(r_take_local_closure([&](size_t n) { return total += n; }) == 6);
+  ASSERT(std::string(r_take_fallible_closure([](rust::Str s) -> size_t {
+           if (std::string(s) == "fail") {
+             throw std::runtime_error("failed");
+           }
+           return s.size();
+         })) == "failed:4");
 
   ASSERT(r_try_return_primitive() == 2020);
+  ASSERT(std::get<1>(r_try_return_tuple()).z == 2020);
   try {
     r_fail_return_primitive();
     ASSERT(false);
   } catch (const rust::Error &e) {
     ASSERT(std::strcmp(e.what(), "rust error") == 0);
   }
+  ASSERT(r_panic_return_primitive(2020) == 2020);
+  try {
+    r_panic_return_primitive(0);
+    ASSERT(false);
+  } catch (const rust::Panic &e) {
+    ASSERT(std::strcmp(e.what(), "rust panic") == 0);
+    ASSERT(std::strcmp(e.file(), "tests/ffi/lib.rs") == 0);
+    ASSERT(e.line() != 0);
+  }
 
   auto r = r_return_box();
   ASSERT(r->get() == 2020);
@@ -822,6 +1039,22 @@ extern "C" const char *cxx_run_test() noexcept {
   swap(r, r2);
   ASSERT(r->get() == 2020);
   ASSERT(r2->get() == 2021);
+  ASSERT(R::create(2022)->get() == 2022);
+
+  auto arc = r_return_arc();
+  auto arc2 = arc;
+  ASSERT(&*arc == &*arc2);
+  ASSERT(arc2->get() == 2020);
+  r_take_arc(std::move(arc2));
+  ASSERT(arc->get() == 2020);
+  r_take_arc(arc);
+
+  auto unique_r = r_return_unique_ptr_r();
+  ASSERT(unique_r->get() == 2020);
+  auto box_r = rust::Box<R>::from_unique_ptr(std::move(unique_r));
+  ASSERT(!unique_r);
+  ASSERT(box_r->get() == 2020);
+  r_take_unique_ptr_r(box_r.into_unique_ptr());
 
   ASSERT(std::string(Shared{0}.r_method_on_shared()) == "2020");
 
@@ -932,6 +1165,10 @@ std::unique_ptr<I> ns_c_return_unique_ptr_ns() {
 }
 } // namespace I
 
+size_t std::hash<::tests::K>::operator()(const ::tests::K &k) const noexcept {
+  return std::hash<int32_t>{}(k.k);
+}
+
 // Instantiate any remaining class member functions not already covered above.
 // This is an easy way to at least typecheck anything missed by unit tests.
 // https://en.cppreference.com/w/cpp/language/class_template#Explicit_instantiation
@@ -1,7 +1,10 @@
 #pragma once
 #include "rust/cxx.h"
+#include <functional>
 #include <memory>
+#include <ostream>
 #include <string>
+#include <tuple>
 
 namespace A {
 struct AShared;
@@ -34,9 +37,13 @@ public:
 
 namespace tests {
 
+constexpr uint32_t C_CONSTANT = 1024;
+constexpr double C_SCALE = 1.5;
+
 struct R;
 struct Shared;
 struct SharedString;
+struct TupleFields;
 enum class Enum : uint16_t;
 
 class C {
@@ -49,8 +56,10 @@ public:
   size_t &getMut();
   size_t set_succeed(size_t n);
   size_t get_fail();
+  static std::unique_ptr<C> create(size_t n);
   const std::vector<uint8_t> &get_v() const;
   std::vector<uint8_t> &get_v();
+  const uint8_t &operator[](size_t i) const;
   rust::String cOverloadedMethod(int32_t x) const;
   rust::String cOverloadedMethod(rust::Str x) const;
 
@@ -72,6 +81,74 @@ struct E {
   void c_take_opaque_mut_ref_method();
 };
 
+class J {
+public:
+  J(uint64_t j);
+  J(uint64_t j, bool fail);
+  J(const J &) = delete;
+  J &operator=(const J &) = delete;
+  ~J();
+  uint64_t get() const;
+  static size_t live;
+
+private:
+  uint64_t j;
+  const J *self;
+};
+
+struct K {
+  int32_t k;
+  bool operator==(const K &other) const;
+  bool operator<(const K &other) const;
+};
+
+K operator+(const K &lhs, const K &rhs);
+std::ostream &operator<<(std::ostream &os, const K &k);
+
+class Shape {
+public:
+  virtual ~Shape() = default;
+  virtual size_t sides() const = 0;
+};
+
+class Circle final : public Shape {
+public:
+  size_t sides() const override;
+};
+
+class Named {
+public:
+  Named(std::string name);
+  virtual ~Named() = default;
+  const std::string &name() const;
+  void rename(rust::Str name);
+
+private:
+  std::string name_;
+};
+
+class Square final : public Named,
+                     public Shape,
+                     public std::enable_shared_from_this<Square> {
+public:
+  Square();
+  size_t sides() const override;
+};
+
+// Allocated with malloc, so it must never be released with delete.
+class Resource {
+public:
+  explicit Resource(size_t n);
+  size_t get() const;
+
+private:
+  size_t n;
+};
+
+struct ResourceDeleter {
+  void operator()(Resource *resource) const noexcept;
+};
+
 enum COwnedEnum {
   CVAL1,
   CVAL2,
@@ -92,6 +169,18 @@ Shared c_return_shared();
 ::A::B::ABShared c_return_nested_ns_shared();
 rust::Box<R> c_return_box();
 std::unique_ptr<C> c_return_unique_ptr();
+std::unique_ptr<R, rust::BoxDeleter> c_return_unique_ptr_r();
+std::unique_ptr<K> c_return_k(int32_t k);
+std::shared_ptr<K> c_return_shared_k(int32_t k);
+std::unique_ptr<Circle> c_return_circle();
+std::shared_ptr<Square> c_return_square();
+std::unique_ptr<Square> c_return_unique_square();
+size_t c_take_shape(const Shape &shape);
+size_t c_take_unique_ptr_shape(std::unique_ptr<Shape> shape);
+size_t c_take_shared_ptr_shape(std::shared_ptr<Shape> shape);
+std::unique_ptr<Resource, ResourceDeleter> c_return_resource(size_t n);
+size_t c_take_resource(std::unique_ptr<Resource, ResourceDeleter> resource);
+size_t c_resource_deleted_count();
 std::shared_ptr<C> c_return_shared_ptr();
 std::unique_ptr<::H::H> c_return_ns_unique_ptr();
 const size_t &c_return_ref(const Shared &shared);
@@ -103,6 +192,7 @@ rust::Slice<const char> c_return_slice_char(const Shared &shared);
 rust::Slice<uint8_t> c_return_mutsliceu8(rust::Slice<uint8_t> slice);
 rust::String c_return_rust_string();
 rust::String c_return_rust_string_lossy();
+std::tuple<size_t, rust::String> c_return_tuple();
 std::unique_ptr<std::string> c_return_unique_ptr_string();
 std::unique_ptr<std::vector<uint8_t>> c_return_unique_ptr_vector_u8();
 std::unique_ptr<std::vector<double>> c_return_unique_ptr_vector_f64();
@@ -130,6 +220,9 @@ void c_take_shared(Shared shared);
 void c_take_ns_shared(::A::AShared shared);
 void c_take_nested_ns_shared(::A::B::ABShared shared);
 void c_take_box(rust::Box<R> r);
+void c_take_arc(rust::Arc<R> r);
+void c_take_unique_ptr_r(std::unique_ptr<R, rust::BoxDeleter> r);
+rust::Arc<R> c_clone_arc(rust::Arc<R> arc);
 void c_take_unique_ptr(std::unique_ptr<C> c);
 void c_take_ref_r(const R &r);
 void c_take_ref_c(const C &c);
@@ -141,6 +234,8 @@ void c_take_slice_shared_sort(rust::Slice<Shared> s);
 void c_take_slice_r(rust::Slice<const R> s);
 void c_take_slice_r_sort(rust::Slice<R> s);
 void c_take_rust_string(rust::String s);
+void c_take_tuple(std::tuple<size_t, rust::Str, rust::Box<R>> tuple);
+TupleFields c_roundtrip_tuple_fields(TupleFields fields);
 void c_take_unique_ptr_string(std::unique_ptr<std::string> s);
 void c_take_unique_ptr_vector_u8(std::unique_ptr<std::vector<uint8_t>> v);
 void c_take_unique_ptr_vector_f64(std::unique_ptr<std::vector<double>> v);
@@ -168,6 +263,9 @@ const SharedString &c_take_ref_shared_string(const SharedString &s);
 void c_take_callback(rust::Fn<size_t(rust::String)> callback);
 void c_take_callback_ref(rust::Fn<void(const rust::String &)> callback);
 void c_take_callback_mut(rust::Fn<void(rust::String &)> callback);
+size_t c_take_closure(rust::FnMut<size_t(size_t)> closure);
+size_t c_take_local_closure(rust::FnMut<size_t(size_t)> closure);
+size_t c_take_fallible_closure(rust::FnMut<size_t(rust::String)> closure);
 void c_take_enum(Enum e);
 void c_take_ns_enum(::A::AEnum e);
 void c_take_nested_ns_enum(::A::B::ABEnum e);
@@ -183,10 +281,14 @@ rust::Str c_try_return_str(rust::Str);
 rust::Slice<const uint8_t> c_try_return_sliceu8(rust::Slice<const uint8_t>);
 rust::Slice<uint8_t> c_try_return_mutsliceu8(rust::Slice<uint8_t>);
 rust::String c_try_return_rust_string();
+std::tuple<uint8_t, Shared> c_try_return_tuple();
 std::unique_ptr<std::string> c_try_return_unique_ptr_string();
 rust::Vec<uint8_t> c_try_return_rust_vec();
 rust::Vec<rust::String> c_try_return_rust_vec_string();
 const rust::Vec<uint8_t> &c_try_return_ref_rust_vec(const C &c);
+rust::Result<size_t, SharedString> c_try_return_shared_error(bool fail);
+rust::Result<rust::String, Enum> c_try_return_enum_error(bool fail);
+rust::Result<void, Enum> c_try_return_void_enum_error(bool fail);
 
 size_t c_get_use_count(const std::weak_ptr<C> &weak) noexcept;
 
@@ -211,6 +313,7 @@ std::unique_ptr<::G::G> c_return_trivial_ns_ptr();
 std::unique_ptr<E> c_return_opaque_ptr();
 E &c_return_opaque_mut_pin(E &e);
 std::unique_ptr<::F::F> c_return_ns_opaque_ptr();
+size_t c_live_j();
 
 rust::String cOverloadedFunction(int32_t x);
 rust::String cOverloadedFunction(rust::Str x);
@@ -235,3 +338,10 @@ public:
 
 std::unique_ptr<I> ns_c_return_unique_ptr_ns();
 } // namespace I
+
+namespace std {
+template <>
+struct hash<::tests::K> {
+  size_t operator()(const ::tests::K &k) const noexcept;
+};
+} // namespace std
@@ -9,11 +9,13 @@
     clippy::unseparated_literal_suffix
 )]
 
-use cxx::SharedPtr;
+use cxx::{SharedPtr, UniquePtr};
 use cxx_test_suite::module::ffi2;
 use cxx_test_suite::{cast, ffi, R};
 use std::cell::Cell;
 use std::ffi::CStr;
+use std::rc::Rc;
+use std::sync::Arc;
 
 thread_local! {
     static CORRECT: Cell<bool> = Cell::new(false);
@@ -95,6 +97,9 @@ fn test_c_return() {
         enm @ ffi::ABEnum::ABAVal => assert_eq!(0, enm.repr),
         _ => assert!(false),
     }
+    let (n, s) = ffi::c_return_tuple();
+    assert_eq!(2020, n);
+    assert_eq!("2020", s);
 }
 
 #[test]
@@ -111,6 +116,28 @@ fn test_c_try_return() {
     assert_eq!(b"2020", ffi::c_try_return_sliceu8(b"2020").unwrap());
     assert_eq!("2020", ffi::c_try_return_rust_string().unwrap());
     assert_eq!("2020", &*ffi::c_try_return_unique_ptr_string().unwrap());
+    let (n, shared) = ffi::c_try_return_tuple().unwrap();
+    assert_eq!(20, n);
+    assert_eq!(2020, shared.z);
+}
+
+#[test]
+fn test_c_try_return_error_type() {
+    assert!(matches!(ffi::c_try_return_shared_error(false), Ok(2020)));
+    match ffi::c_try_return_shared_error(true) {
+        Ok(_) => panic!("expected error"),
+        Err(err) => assert_eq!("2020", err.msg),
+    }
+    assert_eq!("2020", ffi::c_try_return_enum_error(false).unwrap());
+    assert_eq!(
+        ffi::Enum::BVal,
+        ffi::c_try_return_enum_error(true).unwrap_err(),
+    );
+    assert_eq!(Ok(()), ffi::c_try_return_void_enum_error(false));
+    assert_eq!(
+        Err(ffi::Enum::LastVal),
+        ffi::c_try_return_void_enum_error(true),
+    );
 }
 
 #[test]
@@ -124,6 +151,7 @@ fn test_c_take() {
     check!(ffi::ns_c_take_ns_shared(ffi::AShared { z: 2020 }));
     check!(ffi::c_take_nested_ns_shared(ffi::ABShared { z: 2020 }));
     check!(ffi::c_take_box(Box::new(R(2020))));
+    check!(ffi::c_take_arc(Arc::new(R(2020))));
     check!(ffi::c_take_ref_c(&unique_ptr));
     check!(ffi2::c_take_ref_ns_c(&unique_ptr_ns));
     check!(cxx_test_suite::module::ffi::c_take_unique_ptr(unique_ptr));
@@ -151,6 +179,18 @@ fn test_c_take() {
     assert_eq!(r_sort_slice[1].0, 2021);
     assert_eq!(r_sort_slice[2].0, 2050);
     check!(ffi::c_take_rust_string("2020".to_owned()));
+    check!(ffi::c_take_tuple((2020, "2020", Box::new(R(2020)))));
+    let fields = ffi::TupleFields {
+        pair: (2020, "2020".to_owned()).into(),
+        triple: (20, true, ffi::Shared { z: 2020 }).into(),
+    };
+    let roundtrip;
+    check!(roundtrip = ffi::c_roundtrip_tuple_fields(fields));
+    let fields = roundtrip;
+    assert_eq!(fields.pair.0, 2021);
+    assert_eq!(fields.pair.1, "2021");
+    let (n, flag, shared) = fields.triple.into();
+    assert_eq!((n, flag, shared.z), (21, false, 2021));
     check!(ffi::c_take_unique_ptr_string(
         ffi::c_return_unique_ptr_string()
     ));
@@ -231,6 +271,33 @@ fn test_c_callback() {
     check!(ffi::c_take_callback_mut(callback_mut));
 }
 
+#[test]
+fn test_c_closure() {
+    let token = Arc::new(());
+    let captured = Arc::clone(&token);
+    let mut total = 0;
+    let closure = Box::new(move |n| {
+        let _ = &captured;
+        total += n;
+        total
+    });
+    assert_eq!(3, ffi::c_take_closure(closure));
+    assert_eq!(1, Arc::strong_count(&token));
+
+    let total = Rc::new(Cell::new(0));
+    let captured = Rc::clone(&total);
+    let closure = Box::new(move |n| {
+        captured.set(captured.get() + n);
+        captured.get()
+    });
+    assert_eq!(3, ffi::c_take_local_closure(closure));
+    assert_eq!(3, total.get());
+    assert_eq!(1, Rc::strong_count(&total));
+
+    let closure = Box::new(|s: String| if s == "2020" { Ok(s.len()) } else { Err(s) });
+    assert_eq!(4, ffi::c_take_fallible_closure(closure));
+}
+
 #[test]
 fn test_c_call_r() {
     fn cxx_run_test() {
@@ -243,9 +310,145 @@ fn test_c_call_r() {
             eprintln!("{}", msg.to_string_lossy());
         }
     }
+    cxx::install_panic_location_hook();
     check!(cxx_run_test());
 }
 
+#[test]
+fn test_c_operators() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::fmt::Write;
+    use std::hash::{Hash, Hasher};
+
+    fn hash(k: &ffi::K) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        k.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let one = ffi::c_return_k(1);
+    let two = ffi::c_return_k(2);
+    assert_eq!(*one, *ffi::c_return_k(1));
+    assert_ne!(*one, *two);
+    assert!(*one < *two);
+    assert_eq!(std::cmp::Ordering::Greater, two.cmp(&one));
+    assert_eq!(hash(&one), hash(&ffi::c_return_k(1)));
+    assert_eq!(*ffi::c_return_k(3), *(&*one + &*two));
+    assert_eq!("K(1)", format!("{:?}", *one));
+
+    let mut repr = String::new();
+    let negative = ffi::c_return_k(-1);
+    assert!(write!(repr, "{:?}", *negative).is_err());
+}
+
+#[test]
+fn test_c_clone() {
+    let unique = ffi::c_return_k(1);
+    let copy = unique.clone();
+    assert_eq!(*unique, *copy);
+    assert!(!std::ptr::eq(&*unique, &*copy));
+    assert!(cxx::UniquePtr::<ffi::K>::null().clone().is_null());
+
+    let shared = ffi::c_return_shared_k(2);
+    let copy = shared.deep_clone();
+    assert_eq!(*shared, *copy);
+    assert!(!std::ptr::eq(&*shared, &*copy));
+    assert!(cxx::SharedPtr::<ffi::K>::null().deep_clone().is_null());
+}
+
+#[test]
+fn test_c_base_class() {
+    use cxx::memory::Upcast;
+
+    let circle = ffi::c_return_circle();
+    assert_eq!(0, ffi::c_take_shape(&circle));
+    assert_eq!(0, circle.sides());
+
+    let square = ffi::c_return_square();
+    let shape: &ffi::Shape = Upcast::<ffi::Shape>::upcast(&*square);
+    assert_eq!(4, ffi::c_take_shape(shape));
+    assert_eq!("square", Upcast::<ffi::Named>::upcast(&*square).name());
+
+    let mut unique_square = ffi::c_return_unique_square();
+    Upcast::<ffi::Named>::upcast_mut(unique_square.pin_mut()).rename("renamed");
+    assert_eq!(
+        "renamed",
+        Upcast::<ffi::Named>::upcast(&*unique_square).name()
+    );
+
+    assert_eq!(0, ffi::c_take_unique_ptr_shape(circle.upcast()));
+    assert!(cxx::UniquePtr::<ffi::Circle>::null()
+        .upcast::<ffi::Shape>()
+        .is_null());
+
+    let shape = square.upcast::<ffi::Shape>();
+    assert!(std::ptr::eq(
+        shape.as_ref().unwrap(),
+        Upcast::<ffi::Shape>::upcast(&*square)
+    ));
+    assert_eq!(4, ffi::c_take_shared_ptr_shape(shape.clone()));
+    let downcast = shape.downcast::<ffi::Square>().unwrap();
+    assert!(std::ptr::eq(&*downcast, &*square));
+    assert!(shape.downcast::<ffi::Circle>().is_none());
+}
+
+#[test]
+fn test_shared_ptr_ownership() {
+    let shared = SharedPtr::new(ffi::Shared { z: 1 });
+    assert_eq!(1, shared.use_count());
+    assert_eq!(0, SharedPtr::<ffi::Shared>::null().use_count());
+
+    let z = shared.map(|shared| &shared.z);
+    assert_eq!(1, *z);
+    assert_eq!(2, shared.use_count());
+    assert!(!z.owner_before(&shared) && !shared.owner_before(&z));
+    assert!(SharedPtr::<ffi::Shared>::null()
+        .map(|shared| &shared.z)
+        .is_null());
+
+    let copy = shared.clone();
+    assert!(copy.ptr_eq(&shared));
+    assert!(!copy.owner_before(&shared) && !shared.owner_before(&copy));
+    let other = SharedPtr::new(ffi::Shared { z: 1 });
+    assert!(!other.ptr_eq(&shared));
+    assert!(other.owner_before(&shared) != shared.owner_before(&other));
+
+    let weak = shared.downgrade();
+    assert_eq!(3, weak.use_count());
+    drop(copy);
+    drop(z);
+    assert_eq!(1, weak.use_count());
+    assert!(!weak.expired());
+
+    let mut shared = shared;
+    let unique = unsafe { shared.get_mut() }.unwrap();
+    unique.get_mut().z = 2;
+    assert_eq!(2, shared.z);
+    let copy = shared.clone();
+    assert!(unsafe { shared.get_mut() }.is_none());
+    drop(copy);
+
+    drop(shared);
+    assert_eq!(0, weak.use_count());
+    assert!(weak.expired());
+}
+
+#[test]
+fn test_c_shared_ptr_casts() {
+    let square = ffi::c_return_square();
+    let shape = square.upcast::<ffi::Shape>();
+    let downcast = unsafe { shape.downcast_unchecked::<ffi::Square>() };
+    assert!(downcast.ptr_eq(&square));
+    assert!(!downcast.owner_before(&square) && !square.owner_before(&downcast));
+
+    // Only available when the C++ side is built as C++17 or newer.
+    if let Some(this) = SharedPtr::shared_from_this(&*square) {
+        assert!(this.ptr_eq(&square));
+        assert_eq!(4, square.use_count());
+    }
+    assert!(SharedPtr::shared_from_this(&*ffi::c_return_shared_k(1)).is_none());
+}
+
 #[test]
 fn test_c_method_calls() {
     let mut unique_ptr = ffi::c_return_unique_ptr();
@@ -259,13 +462,16 @@ fn test_c_method_calls() {
     assert_eq!(2021, *unique_ptr.pin_mut().getMut());
     assert_eq!(2022, unique_ptr.pin_mut().set_succeed(2022).unwrap());
     assert!(unique_ptr.pin_mut().get_fail().is_err());
+    assert_eq!(2024, ffi::C::create(2024).get());
+    ffi::c_return_mut_vector(unique_ptr.pin_mut()).push(7);
+    assert_eq!(7, unique_ptr[0]);
     assert_eq!(2021, ffi::Shared { z: 0 }.c_method_on_shared());
     assert_eq!(2022, *ffi::Shared { z: 2022 }.c_method_ref_on_shared());
     assert_eq!(2023, *ffi::Shared { z: 2023 }.c_method_mut_on_shared());
 
     let val = 42;
     let mut array = ffi::Array {
-        a: [0, 0, 0, 0],
+        a: [0; ffi::ARRAY_LEN],
         b: ffi::Buffer::default(),
     };
     array.c_set_array(val);
@@ -301,6 +507,50 @@ fn test_enum_representations() {
     assert_eq!(2021, ffi::Enum::LastVal.repr);
 }
 
+#[test]
+fn test_constants() {
+    assert_eq!(4, ffi::ARRAY_LEN);
+    assert_eq!(-2020, ffi::SHARED_I64);
+    assert_eq!(0.5, ffi::SHARED_F32);
+    assert!(ffi::A_SHARED_FLAG);
+    assert_eq!(1024, ffi::C_CONSTANT);
+    assert_eq!(1.5, ffi::C_CONSTANT_SCALE);
+}
+
+#[test]
+fn test_unique_ptr_box() {
+    let unique = ffi::c_return_unique_ptr_r();
+    assert_eq!(unique.as_ref().unwrap().0, 2020);
+    let boxed = unique.into_box().unwrap();
+    assert_eq!(boxed.0, 2020);
+    check!(ffi::c_take_unique_ptr_r(UniquePtr::from(boxed)));
+    assert!(UniquePtr::<R>::null().into_box().is_none());
+}
+
+#[test]
+fn test_unique_ptr_custom_deleter() {
+    let deleted = ffi::c_resource_deleted_count();
+    let resource = ffi::c_return_resource(2020);
+    assert_eq!(resource.get(), 2020);
+    drop(resource);
+    assert_eq!(ffi::c_resource_deleted_count(), deleted + 1);
+
+    let raw = ffi::c_return_resource(2021).into_raw();
+    let resource = unsafe { UniquePtr::from_raw(raw) };
+    assert_eq!(ffi::c_take_resource(resource), 2021);
+    assert_eq!(ffi::c_resource_deleted_count(), deleted + 2);
+}
+
+#[test]
+fn test_c_arc() {
+    let arc = Arc::new(R(2020));
+    let copy = ffi::c_clone_arc(Arc::clone(&arc));
+    assert!(Arc::ptr_eq(&arc, &copy));
+    assert_eq!(Arc::strong_count(&arc), 2);
+    drop(copy);
+    assert_eq!(Arc::strong_count(&arc), 1);
+}
+
 #[test]
 fn test_debug() {
     assert_eq!("Shared { z: 1 }", format!("{:?}", ffi::Shared { z: 1 }));
@@ -363,6 +613,31 @@ fn test_extern_opaque() {
     check!(ffi2::c_take_opaque_ns_ptr(f));
 }
 
+#[test]
+fn test_constructors() {
+    let c = ffi::C::new(2020);
+    assert_eq!(2020, c.get());
+
+    let j = ffi2::J::new(2021);
+    assert_eq!(2021, j.get());
+    assert_eq!(1, ffi2::c_live_j());
+    drop(j);
+    assert_eq!(0, ffi2::c_live_j());
+
+    let n = ffi2::J::new_in_place(2022, |j| {
+        assert_eq!(1, ffi2::c_live_j());
+        j.get()
+    });
+    assert_eq!(2022, n);
+    assert_eq!(0, ffi2::c_live_j());
+
+    let n = ffi2::J::try_new_in_place(2023, false, |j| j.get()).unwrap();
+    assert_eq!(2023, n);
+    assert!(ffi2::J::try_new_in_place(2024, true, |j| j.get()).is_err());
+    assert!(ffi2::J::try_new(2024, true).is_err());
+    assert_eq!(0, ffi2::c_live_j());
+}
+
 #[test]
 fn test_raw_ptr() {
     let c = ffi::c_return_mut_ptr(2023);
@@ -378,3 +653,16 @@ fn test_raw_ptr() {
     assert_eq!(2025, unsafe { ffi::c_take_const_ptr(c3) });
     assert_eq!(2025, unsafe { ffi::c_take_mut_ptr(c3 as *mut ffi::C) }); // deletes c3
 }
+
+#[test]
+fn test_no_exceptions() {
+    use cxx_test_suite::no_exceptions::ffi as no_exceptions;
+
+    assert_eq!(2020, no_exceptions::c_try_parse("2020").unwrap());
+    let error = no_exceptions::c_try_parse("twenty").unwrap_err();
+    assert_eq!("not a number", error.what());
+    assert_eq!("empty", no_exceptions::c_try_parse("").unwrap_err().what());
+
+    assert_eq!(2020, no_exceptions::c_call_r_try_parse("2020"));
+    assert_eq!(0, no_exceptions::c_call_r_try_parse("twenty"));
+}
@@ -0,0 +1,24 @@
+#[cxx::bridge]
+mod ffi {
+    struct Shared {
+        arc: Arc<R>,
+    }
+
+    extern "Rust" {
+        type R;
+
+        fn by_ref(arc: &Arc<R>);
+        fn in_tuple(tuple: (usize, Arc<R>));
+    }
+
+    unsafe extern "C++" {
+        type C;
+
+        fn cxx_target(arc: Arc<C>);
+        fn fn_ptr(callback: fn(Arc<R>));
+    }
+}
+
+struct R;
+
+fn main() {}
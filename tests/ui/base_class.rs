@@ -0,0 +1,33 @@
+#[cxx::bridge]
+mod ffi {
+    struct Shared {
+        x: i32,
+    }
+
+    extern "Rust" {
+        #[base(Base)]
+        type RustType;
+    }
+
+    unsafe extern "C++" {
+        type Base;
+        type Generic<'a>;
+
+        #[base(Base)]
+        type WithLifetime<'a>;
+        #[base(Recursive)]
+        type Recursive;
+        #[base(Shared)]
+        type SharedBase;
+        #[base(RustType)]
+        type RustBase;
+        #[base(Generic)]
+        type GenericBase;
+        #[base(Base, Base)]
+        type Duplicate;
+    }
+}
+
+struct RustType;
+
+fn main() {}
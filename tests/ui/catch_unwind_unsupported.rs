@@ -0,0 +1,18 @@
+#[cxx::bridge]
+mod ffi {
+    extern "Rust" {
+        #[catch_unwind]
+        fn infallible() -> usize;
+    }
+
+    unsafe extern "C++" {
+        #[catch_unwind]
+        fn fallible() -> Result<usize>;
+    }
+}
+
+fn infallible() -> usize {
+    0
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+#[cxx::bridge]
+mod ffi {
+    struct S {
+        f: Box<dyn FnMut() + Send>,
+    }
+
+    unsafe extern "C++" {
+        fn f() -> Box<dyn FnMut() + Send>;
+        fn g(f: &Box<dyn FnMut() + Send>);
+        fn h(f: Box<dyn FnMut(*const u8) + Send>);
+    }
+}
+
+fn main() {}
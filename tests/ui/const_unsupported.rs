@@ -0,0 +1,14 @@
+#[cxx::bridge]
+mod ffi {
+    const NAME: &str = "name";
+    const NEGATIVE: u8 = -1;
+    const FLOAT: f64 = 1;
+    const LEN: u32 = 4;
+
+    struct Shared {
+        unknown: [u8; UNKNOWN],
+        wrong_type: [u8; LEN],
+    }
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[cxx::bridge]
+mod ffi {
+    const SUM: usize = 1 + 1;
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+#[cxx::bridge]
+mod ffi {
+    unsafe extern "C++" {
+        type Thing;
+    }
+
+    impl Thing {
+        fn new() -> UniquePtr<Thing>;
+    }
+}
+
+fn main() {}
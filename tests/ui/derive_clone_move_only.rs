@@ -0,0 +1,19 @@
+#[cxx::bridge]
+mod ffi {
+    struct Shared {
+        boxed: Box<Opaque>,
+    }
+
+    extern "Rust" {
+        type Opaque;
+    }
+
+    unsafe extern "C++" {
+        #[derive(Clone)]
+        type Shared;
+    }
+}
+
+pub struct Opaque;
+
+fn main() {}
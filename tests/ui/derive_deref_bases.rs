@@ -0,0 +1,16 @@
+#[cxx::bridge]
+mod ffi {
+    unsafe extern "C++" {
+        type Shape;
+        type Named;
+
+        #[derive(Deref)]
+        type Point;
+
+        #[base(Named, Shape)]
+        #[derive(Deref)]
+        type Square;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#[cxx::bridge]
+mod ffi {
+    unsafe extern "C++" {
+        #[derive(PartialOrd)]
+        type Thing;
+    }
+}
+
+fn main() {}
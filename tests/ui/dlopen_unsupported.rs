@@ -0,0 +1,11 @@
+#[cxx::bridge]
+mod ffi {
+    #[dlopen = "Library"]
+    extern "Rust" {
+        fn f();
+    }
+}
+
+fn f() {}
+
+fn main() {}
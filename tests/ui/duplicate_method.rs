@@ -0,0 +1,15 @@
+#[cxx::bridge]
+mod ffi {
+    extern "Rust" {
+        type T;
+
+        fn f(self: &T);
+        #[Self = "T"]
+        fn f();
+
+        fn g(self: &T);
+        fn g(self: &mut T);
+    }
+}
+
+fn main() {}
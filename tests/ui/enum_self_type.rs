@@ -0,0 +1,12 @@
+#[cxx::bridge]
+mod ffi {
+    enum Enum {
+        Variant,
+    }
+    extern "Rust" {
+        #[Self = "Enum"]
+        fn f();
+    }
+}
+
+fn main() {}
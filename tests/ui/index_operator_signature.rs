@@ -0,0 +1,11 @@
+#[cxx::bridge]
+mod ffi {
+    unsafe extern "C++" {
+        type Thing;
+
+        #[cxx_name = "operator[]"]
+        fn at(self: &Thing, i: usize) -> usize;
+    }
+}
+
+fn main() {}
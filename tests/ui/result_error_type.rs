@@ -0,0 +1,24 @@
+#[cxx::bridge]
+mod ffi {
+    struct Shared {
+        code: i32,
+    }
+
+    extern "Rust" {
+        fn r_fallible() -> Result<usize, Shared>;
+    }
+
+    unsafe extern "C++" {
+        type Opaque;
+
+        fn c_opaque_error() -> Result<usize, UniquePtr<Opaque>>;
+        fn c_same_type() -> Result<Shared, Shared>;
+        fn c_closure(f: Box<dyn FnMut() -> Result<(), Shared>>);
+    }
+}
+
+fn r_fallible() -> Result<usize, ffi::Shared> {
+    Ok(0)
+}
+
+fn main() {}
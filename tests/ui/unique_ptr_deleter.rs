@@ -0,0 +1,25 @@
+#[cxx::bridge]
+mod ffi {
+    struct Shared {
+        x: i32,
+    }
+
+    extern "Rust" {
+        type R;
+    }
+
+    unsafe extern "C++" {
+        type C;
+        type D;
+        type Deleter;
+    }
+
+    impl UniquePtr<R, Deleter> {}
+    impl UniquePtr<Shared, Deleter> {}
+    impl UniquePtr<C, R> {}
+    impl UniquePtr<D, &Deleter> {}
+}
+
+struct R;
+
+fn main() {}
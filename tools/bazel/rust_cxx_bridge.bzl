@@ -2,12 +2,13 @@
 load("@bazel_skylib//rules:run_binary.bzl", "run_binary")
 load("@rules_cc//cc:defs.bzl", "cc_library")
 
-def rust_cxx_bridge(name, src, deps = []):
+def rust_cxx_bridge(name, src, args = [], deps = []):
     """A macro defining a cxx bridge library
 
     Args:
         name (string): The name of the new target
         src (string): The rust source file to generate a bridge for
+        args (list, optional): Extra command line arguments for the code generator. Defaults to [].
         deps (list, optional): A list of dependencies for the underlying cc_library. Defaults to [].
     """
     native.alias(
@@ -15,6 +16,11 @@ def rust_cxx_bridge(name, src, deps = []):
         actual = src + ".h",
     )
 
+    native.alias(
+        name = "%s/fwd_header" % name,
+        actual = src + ".fwd.h",
+    )
+
     native.alias(
         name = "%s/source" % name,
         actual = src + ".cc",
@@ -25,13 +31,16 @@ def rust_cxx_bridge(name, src, deps = []):
         srcs = [src],
         outs = [
             src + ".h",
+            src + ".fwd.h",
             src + ".cc",
         ],
-        args = [
+        args = args + [
             "$(location %s)" % src,
             "-o",
             "$(location %s.h)" % src,
             "-o",
+            "$(location %s.fwd.h)" % src,
+            "-o",
             "$(location %s.cc)" % src,
         ],
         tool = "@cxx.rs//:codegen",
@@ -45,5 +54,8 @@ def rust_cxx_bridge(name, src, deps = []):
 
     cc_library(
         name = "%s/include" % name,
-        hdrs = [src + ".h"],
+        hdrs = [
+            src + ".h",
+            src + ".fwd.h",
+        ],
     )
@@ -1,6 +1,7 @@
 def rust_cxx_bridge(
         name: str.type,
         src: str.type,
+        args: [str.type] = [],
         deps: [str.type] = []):
     native.export_file(
         name = "%s/header" % name,
@@ -8,6 +9,12 @@ def rust_cxx_bridge(
         out = src + ".h",
     )
 
+    native.export_file(
+        name = "%s/fwd_header" % name,
+        src = ":%s/generated[generated.fwd.h]" % name,
+        out = src + ".fwd.h",
+    )
+
     native.export_file(
         name = "%s/source" % name,
         src = ":%s/generated[generated.cc]" % name,
@@ -19,9 +26,10 @@ def rust_cxx_bridge(
         srcs = [src],
         outs = {
             "generated.cc": ["generated.cc"],
+            "generated.fwd.h": ["generated.fwd.h"],
             "generated.h": ["generated.h"],
         },
-        cmd = "$(exe //:codegen) ${SRCS} -o ${OUT}/generated.h -o ${OUT}/generated.cc",
+        cmd = " ".join(["$(exe //:codegen)"] + args + ["${SRCS}"]) + " -o ${OUT}/generated.h -o ${OUT}/generated.fwd.h -o ${OUT}/generated.cc",
         type = "cxxbridge",
     )
 
@@ -34,5 +42,8 @@ def rust_cxx_bridge(
 
     native.cxx_library(
         name = "%s/include" % name,
-        exported_headers = [":%s/header" % name],
+        exported_headers = [
+            ":%s/fwd_header" % name,
+            ":%s/header" % name,
+        ],
     )